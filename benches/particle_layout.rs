@@ -0,0 +1,139 @@
+use coding_challenges::fluid_simulation::cell_manager::CellManager;
+use coding_challenges::fluid_simulation::equation_of_state::{EquationOfState, PressureLaw};
+use coding_challenges::fluid_simulation::particle::Particle;
+use coding_challenges::fluid_simulation::particle_store::ParticleStore;
+use coding_challenges::fluid_simulation::scene::Scene;
+use coding_challenges::fluid_simulation::smoothed_interaction::SmoothedInteraction;
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+use vector2d::Vector2D;
+
+// Compares the density, pressure and viscosity passes over the
+// struct-of-arrays ParticleStore the simulation keeps with the same passes
+// over Particle structs, on a block of fluid at rest spacing.
+// Run with `cargo bench --bench particle_layout`.
+fn main() {
+    const BOX_DIMENSIONS: [usize; 2] = [1000, 1000];
+    const PARTICLES_PER_SIDE: usize = 150;
+    const MEASURED_PASSES: u32 = 50;
+    let scene = Scene::dam_break(BOX_DIMENSIONS, 0);
+    let parameters = &scene.parameters;
+    let smoothed_interaction = SmoothedInteraction::new(
+        EquationOfState::new(
+            PressureLaw::Linear {
+                pressure_multiplier: parameters.pressure_multiplier,
+            },
+            parameters.target_density,
+            scene.pressure.clamp_negative_pressure,
+        ),
+        scene.pressure.gradient,
+        parameters.smoothing_radius,
+        parameters.viscosity,
+        scene.kernels.density.build(),
+        scene.kernels.pressure.build(),
+        scene.kernels.viscosity.build(),
+    );
+    let spacing = (parameters.particle_mass / parameters.target_density).sqrt();
+    let mut particles: Vec<Particle> = (0..PARTICLES_PER_SIDE * PARTICLES_PER_SIDE)
+        .map(|index| Particle {
+            mass: parameters.particle_mass,
+            velocity: Vector2D::new((index % 7) as f32, (index % 5) as f32),
+            ..Particle::new(
+                index,
+                Vector2D::new(
+                    spacing * (1.0 + (index % PARTICLES_PER_SIDE) as f32),
+                    spacing * (1.0 + (index / PARTICLES_PER_SIDE) as f32),
+                ),
+            )
+        })
+        .collect();
+    let particle_count = particles.len();
+    let mut cell_manager = CellManager::new(
+        particle_count as i32,
+        BOX_DIMENSIONS,
+        parameters.smoothing_radius,
+    );
+    cell_manager.update(&mut particles);
+    let store = ParticleStore::from_particles(&particles);
+    let mut densities = vec![0.0; particle_count];
+    let mut pressures = vec![0.0; particle_count];
+    let mut accelerations = vec![Vector2D::new(0.0, 0.0); particle_count];
+
+    let mut particle_time = Duration::from_secs(0);
+    let mut store_time = Duration::from_secs(0);
+    for _ in 0..MEASURED_PASSES {
+        let start = Instant::now();
+        densities
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(index, density)| {
+                *density = smoothed_interaction.calculate_density_from_particles(
+                    index,
+                    cell_manager.get_neighbors(index).iter().copied(),
+                    &particles,
+                )
+            });
+        pressures
+            .par_iter_mut()
+            .zip(densities.par_iter())
+            .for_each(|(pressure, &density)| {
+                *pressure = smoothed_interaction.convert_density_to_pressure(density)
+            });
+        accelerations
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(index, acceleration)| {
+                let neighbors = cell_manager.get_neighbors(index);
+                *acceleration =
+                    smoothed_interaction.calculate_pressure_acceleration_from_particles(
+                        index,
+                        neighbors.iter().copied(),
+                        &particles,
+                        &densities,
+                        &pressures,
+                    ) + smoothed_interaction.calculate_viscosity_acceleration_from_particles(
+                        index,
+                        neighbors.iter().copied(),
+                        &particles,
+                        &densities,
+                    );
+            });
+        particle_time += start.elapsed();
+
+        let start = Instant::now();
+        densities
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(index, density)| {
+                *density = smoothed_interaction.calculate_density(
+                    index,
+                    cell_manager.get_neighbors(index),
+                    &store,
+                )
+            });
+        pressures
+            .par_iter_mut()
+            .zip(densities.par_iter())
+            .for_each(|(pressure, &density)| {
+                *pressure = smoothed_interaction.convert_density_to_pressure(density)
+            });
+        accelerations
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(index, acceleration)| {
+                let neighbors = cell_manager.get_neighbors(index);
+                *acceleration = smoothed_interaction.calculate_pressure_acceleration(
+                    index, neighbors, &store, &densities, &pressures,
+                ) + smoothed_interaction
+                    .calculate_viscosity_acceleration(index, neighbors, &store, &densities);
+            });
+        store_time += start.elapsed();
+    }
+    println!(
+        "{} particles, {} passes: particles {:?}, particle store {:?}",
+        particle_count,
+        MEASURED_PASSES,
+        particle_time / MEASURED_PASSES,
+        store_time / MEASURED_PASSES
+    );
+}
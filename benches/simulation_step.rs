@@ -0,0 +1,47 @@
+use coding_challenges::fluid_simulation::scene::Scene;
+use coding_challenges::fluid_simulation::simulation::Simulation;
+use std::time::{Duration, Instant};
+
+// Average wall time of Simulation::update on the default dam break. Run with
+// `cargo bench`, or pass a scene file:
+// `cargo bench --bench simulation_step -- scenes/dam_break.toml`. The bench
+// command of the binary reports the spread over repeated runs as well.
+fn main() {
+    const DEFAULT_BOX_DIMENSIONS: [usize; 2] = [1000, 800];
+    const DEFAULT_PARTICLE_COUNT: usize = 6500;
+    const WARM_UP_UPDATES: usize = 20;
+    const MEASURED_UPDATES: u32 = 200;
+    let scene = match std::env::args()
+        .skip(1)
+        .find(|argument| !argument.starts_with("--"))
+    {
+        Some(scene_path) => Scene::load(scene_path).unwrap_or_else(|error| {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }),
+        None => Scene::dam_break(DEFAULT_BOX_DIMENSIONS, DEFAULT_PARTICLE_COUNT),
+    };
+
+    let mut simulation = Simulation::new(&scene);
+    for _ in 0..WARM_UP_UPDATES {
+        simulation.update();
+    }
+    let mut total_elapsed_time = Duration::from_secs(0);
+    let mut total_sub_steps = 0;
+    for _ in 0..MEASURED_UPDATES {
+        let start = Instant::now();
+        simulation.update();
+        total_elapsed_time += start.elapsed();
+        total_sub_steps += simulation.sub_steps();
+    }
+    let neighbor_statistics = simulation.neighbor_statistics();
+    println!(
+        "{} particles, {} updates: average update {:?}, average step {:?}, neighbor rebuilds {}/{}",
+        simulation.particles.len(),
+        MEASURED_UPDATES,
+        total_elapsed_time / MEASURED_UPDATES,
+        total_elapsed_time / total_sub_steps as u32,
+        neighbor_statistics.rebuilds,
+        neighbor_statistics.updates
+    );
+}
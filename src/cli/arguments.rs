@@ -0,0 +1,434 @@
+use crate::fluid_simulation::scene::{Scene, SceneError};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub const USAGE: &str = "\
+usage: coding-challenges <command> [options]
+
+commands:
+  view [scene.toml]          run a scene in a window (the dam break by default)
+  view --replay <recording>  play a recording back in a window
+  run [scene.toml]           run a scene without a window, writing its outputs
+  bench [scene.toml]         time repeated runs of a scene
+  convert <recording>        convert a recording to CSV, legacy VTK or VTU files
+  help                       show this message
+
+options:
+  --particles <count>     number of particles, spread over the fluid blocks
+  --seed <seed>           seed for all randomness of the run
+  --threads <count>       worker threads, all cores by default
+  --output-dir <dir>      directory for particle output, benchmark timings
+                          or converted files
+  --opengl <version>      view: OpenGL version, 3.2 by default
+  --fps <rate>            view: frames drawn per second, 60 by default
+  --steps <count>         run: updates to run; bench: updates to time,
+                          200 by default
+  --seconds <time>        run: simulated seconds to run
+  --warm-up <count>       bench: untimed updates before each run, 20 by default
+  --repeats <count>       bench: number of runs, 5 by default
+  --to <format>           convert: csv, vtk or vtu";
+
+const DEFAULT_BOX_DIMENSIONS: [usize; 2] = [1000, 800];
+const DEFAULT_PARTICLE_COUNT: usize = 6500;
+
+#[derive(Debug, PartialEq)]
+pub enum ArgumentError {
+    UnknownCommand(String),
+    UnknownOption { command: String, option: String },
+    MissingValue(String),
+    InvalidValue { option: String, value: String },
+    UnexpectedArgument(String),
+    MissingArgument(&'static str),
+    Conflict(&'static str),
+}
+
+impl fmt::Display for ArgumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgumentError::UnknownCommand(command) => write!(f, "unknown command {}", command),
+            ArgumentError::UnknownOption { command, option } => {
+                write!(f, "{} does not take the option {}", command, option)
+            }
+            ArgumentError::MissingValue(option) => write!(f, "{} needs a value", option),
+            ArgumentError::InvalidValue { option, value } => {
+                write!(f, "invalid value {} for {}", value, option)
+            }
+            ArgumentError::UnexpectedArgument(argument) => {
+                write!(f, "unexpected argument {}", argument)
+            }
+            ArgumentError::MissingArgument(argument) => write!(f, "missing {}", argument),
+            ArgumentError::Conflict(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ArgumentError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpenGlVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl OpenGlVersion {
+    const SUPPORTED: [(u32, u32); 12] = [
+        (2, 0),
+        (2, 1),
+        (3, 0),
+        (3, 1),
+        (3, 2),
+        (3, 3),
+        (4, 0),
+        (4, 1),
+        (4, 2),
+        (4, 3),
+        (4, 4),
+        (4, 5),
+    ];
+}
+
+impl Default for OpenGlVersion {
+    fn default() -> Self {
+        OpenGlVersion { major: 3, minor: 2 }
+    }
+}
+
+impl FromStr for OpenGlVersion {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (major, minor) = value.split_once('.').ok_or(())?;
+        let version = (
+            major.parse().map_err(|_| ())?,
+            minor.parse().map_err(|_| ())?,
+        );
+        if !OpenGlVersion::SUPPORTED.contains(&version) {
+            return Err(());
+        }
+        Ok(OpenGlVersion {
+            major: version.0,
+            minor: version.1,
+        })
+    }
+}
+
+// Where the scene comes from and what the command line changes about it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SceneOptions {
+    pub scene_path: Option<PathBuf>,
+    pub particle_count: Option<usize>,
+    pub seed: Option<u64>,
+    pub output_directory: Option<PathBuf>,
+}
+
+impl SceneOptions {
+    pub fn scene(&self) -> Result<Scene, SceneError> {
+        let mut scene = match &self.scene_path {
+            Some(scene_path) => Scene::load(scene_path)?,
+            None => Scene::dam_break(
+                DEFAULT_BOX_DIMENSIONS,
+                self.particle_count.unwrap_or(DEFAULT_PARTICLE_COUNT),
+            ),
+        };
+        if let Some(particle_count) = self.particle_count {
+            scene.set_particle_count(particle_count)?;
+        }
+        if let Some(seed) = self.seed {
+            scene.seed = seed;
+        }
+        if let Some(output_directory) = &self.output_directory {
+            scene.set_output_directory(output_directory.clone());
+        }
+        Ok(scene)
+    }
+
+    fn is_default(&self) -> bool {
+        *self == SceneOptions::default()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ViewOptions {
+    pub scene: SceneOptions,
+    pub replay_path: Option<PathBuf>,
+    pub threads: Option<usize>,
+    pub opengl_version: OpenGlVersion,
+    pub max_fps: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RunLength {
+    Steps(usize),
+    Seconds(f64),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunOptions {
+    pub scene: SceneOptions,
+    pub threads: Option<usize>,
+    pub length: RunLength,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchOptions {
+    pub scene: SceneOptions,
+    pub threads: Option<usize>,
+    pub steps: usize,
+    pub warm_up_steps: usize,
+    pub repeats: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConvertFormat {
+    Csv,
+    Vtk,
+    Vtu,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConvertOptions {
+    pub recording_path: PathBuf,
+    pub format: ConvertFormat,
+    pub output_directory: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    View(ViewOptions),
+    Run(RunOptions),
+    Bench(BenchOptions),
+    Convert(ConvertOptions),
+    Help,
+}
+
+impl Command {
+    pub fn threads(&self) -> Option<usize> {
+        match self {
+            Command::View(options) => options.threads,
+            Command::Run(options) => options.threads,
+            Command::Bench(options) => options.threads,
+            Command::Convert(_) | Command::Help => None,
+        }
+    }
+}
+
+// Parses the arguments after the program name. Without any the dam break is
+// viewed.
+pub fn parse(arguments: impl IntoIterator<Item = String>) -> Result<Command, ArgumentError> {
+    let arguments: Vec<String> = arguments.into_iter().collect();
+    if arguments
+        .iter()
+        .any(|argument| argument == "--help" || argument == "-h")
+    {
+        return Ok(Command::Help);
+    }
+    let mut arguments = arguments.into_iter();
+    let command = match arguments.next() {
+        Some(command) => command,
+        None => return parse_view(arguments),
+    };
+    match command.as_str() {
+        "view" => parse_view(arguments),
+        "run" => parse_run(arguments),
+        "bench" => parse_bench(arguments),
+        "convert" => parse_convert(arguments),
+        "help" => Ok(Command::Help),
+        _ => Err(ArgumentError::UnknownCommand(command)),
+    }
+}
+
+fn parse_view(arguments: impl Iterator<Item = String>) -> Result<Command, ArgumentError> {
+    let mut scene = SceneOptions::default();
+    let mut replay_path = None;
+    let mut threads = None;
+    let mut opengl_version = OpenGlVersion::default();
+    let mut max_fps = 60;
+    let positionals = parse_options("view", arguments, |option, value| {
+        match option {
+            "--replay" => replay_path = Some(PathBuf::from(value)),
+            "--threads" => threads = Some(parse_positive(option, value)?),
+            "--opengl" => opengl_version = parse_value(option, value)?,
+            "--fps" => max_fps = parse_positive(option, value)? as u64,
+            _ => return parse_scene_option(&mut scene, option, value),
+        }
+        Ok(true)
+    })?;
+    scene.scene_path = optional_positional(positionals)?;
+    if replay_path.is_some() && !scene.is_default() {
+        return Err(ArgumentError::Conflict(
+            "--replay plays a recording and takes no scene or scene options",
+        ));
+    }
+    Ok(Command::View(ViewOptions {
+        scene,
+        replay_path,
+        threads,
+        opengl_version,
+        max_fps,
+    }))
+}
+
+fn parse_run(arguments: impl Iterator<Item = String>) -> Result<Command, ArgumentError> {
+    let mut scene = SceneOptions::default();
+    let mut threads = None;
+    let mut steps = None;
+    let mut seconds = None;
+    let positionals = parse_options("run", arguments, |option, value| {
+        match option {
+            "--threads" => threads = Some(parse_positive(option, value)?),
+            "--steps" => steps = Some(parse_positive(option, value)?),
+            "--seconds" => {
+                let parsed: f64 = parse_value(option, value)?;
+                if !(parsed > 0.0 && parsed.is_finite()) {
+                    return Err(invalid_value(option, value));
+                }
+                seconds = Some(parsed);
+            }
+            _ => return parse_scene_option(&mut scene, option, value),
+        }
+        Ok(true)
+    })?;
+    scene.scene_path = optional_positional(positionals)?;
+    let length = match (steps, seconds) {
+        (Some(steps), None) => RunLength::Steps(steps),
+        (None, Some(seconds)) => RunLength::Seconds(seconds),
+        (None, None) => return Err(ArgumentError::MissingArgument("--steps or --seconds")),
+        (Some(_), Some(_)) => {
+            return Err(ArgumentError::Conflict(
+                "--steps and --seconds cannot be combined",
+            ))
+        }
+    };
+    Ok(Command::Run(RunOptions {
+        scene,
+        threads,
+        length,
+    }))
+}
+
+fn parse_bench(arguments: impl Iterator<Item = String>) -> Result<Command, ArgumentError> {
+    let mut scene = SceneOptions::default();
+    let mut threads = None;
+    let mut steps = 200;
+    let mut warm_up_steps = 20;
+    let mut repeats = 5;
+    let positionals = parse_options("bench", arguments, |option, value| {
+        match option {
+            "--threads" => threads = Some(parse_positive(option, value)?),
+            "--steps" => steps = parse_positive(option, value)?,
+            "--warm-up" => warm_up_steps = parse_value(option, value)?,
+            "--repeats" => repeats = parse_positive(option, value)?,
+            _ => return parse_scene_option(&mut scene, option, value),
+        }
+        Ok(true)
+    })?;
+    scene.scene_path = optional_positional(positionals)?;
+    Ok(Command::Bench(BenchOptions {
+        scene,
+        threads,
+        steps,
+        warm_up_steps,
+        repeats,
+    }))
+}
+
+fn parse_convert(arguments: impl Iterator<Item = String>) -> Result<Command, ArgumentError> {
+    let mut format = None;
+    let mut output_directory = None;
+    let positionals = parse_options("convert", arguments, |option, value| {
+        match option {
+            "--to" => {
+                format = Some(match value {
+                    "csv" => ConvertFormat::Csv,
+                    "vtk" => ConvertFormat::Vtk,
+                    "vtu" => ConvertFormat::Vtu,
+                    _ => return Err(invalid_value(option, value)),
+                })
+            }
+            "--output-dir" => output_directory = Some(PathBuf::from(value)),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    })?;
+    let recording_path =
+        optional_positional(positionals)?.ok_or(ArgumentError::MissingArgument("recording"))?;
+    Ok(Command::Convert(ConvertOptions {
+        recording_path,
+        format: format.ok_or(ArgumentError::MissingArgument("--to"))?,
+        output_directory,
+    }))
+}
+
+// Options take a value, as `--option value` or `--option=value`. The closure
+// applies one and returns false for options the command does not take. The
+// remaining arguments are returned in order.
+fn parse_options(
+    command: &str,
+    arguments: impl Iterator<Item = String>,
+    mut apply_option: impl FnMut(&str, &str) -> Result<bool, ArgumentError>,
+) -> Result<Vec<String>, ArgumentError> {
+    let mut arguments = arguments.peekable();
+    let mut positionals = Vec::new();
+    while let Some(argument) = arguments.next() {
+        if !argument.starts_with("--") {
+            positionals.push(argument);
+            continue;
+        }
+        let (option, value) = match argument.split_once('=') {
+            Some((option, value)) => (option.to_string(), value.to_string()),
+            None => match arguments.next_if(|value| !value.starts_with("--")) {
+                Some(value) => (argument, value),
+                None => return Err(ArgumentError::MissingValue(argument)),
+            },
+        };
+        if !apply_option(&option, &value)? {
+            return Err(ArgumentError::UnknownOption {
+                command: command.to_string(),
+                option,
+            });
+        }
+    }
+    Ok(positionals)
+}
+
+fn parse_scene_option(
+    scene: &mut SceneOptions,
+    option: &str,
+    value: &str,
+) -> Result<bool, ArgumentError> {
+    match option {
+        "--particles" => scene.particle_count = Some(parse_positive(option, value)?),
+        "--seed" => scene.seed = Some(parse_value(option, value)?),
+        "--output-dir" => scene.output_directory = Some(PathBuf::from(value)),
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn optional_positional(positionals: Vec<String>) -> Result<Option<PathBuf>, ArgumentError> {
+    let mut positionals = positionals.into_iter();
+    let first = positionals.next().map(PathBuf::from);
+    match positionals.next() {
+        Some(extra) => Err(ArgumentError::UnexpectedArgument(extra)),
+        None => Ok(first),
+    }
+}
+
+fn parse_value<V: FromStr>(option: &str, value: &str) -> Result<V, ArgumentError> {
+    value.parse().map_err(|_| invalid_value(option, value))
+}
+
+fn parse_positive(option: &str, value: &str) -> Result<usize, ArgumentError> {
+    match parse_value(option, value)? {
+        0 => Err(invalid_value(option, value)),
+        parsed => Ok(parsed),
+    }
+}
+
+fn invalid_value(option: &str, value: &str) -> ArgumentError {
+    ArgumentError::InvalidValue {
+        option: option.to_string(),
+        value: value.to_string(),
+    }
+}
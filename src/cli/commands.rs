@@ -0,0 +1,285 @@
+use crate::cli::arguments::{BenchOptions, ConvertFormat, ConvertOptions, RunLength, RunOptions};
+use crate::fluid_simulation::output_manager::{OutputError, OutputManager};
+use crate::fluid_simulation::recording::{Frame, Recording, RecordingError};
+use crate::fluid_simulation::scene::SceneError;
+use crate::fluid_simulation::simulation::Simulation;
+use crate::fluid_simulation::vtk_writer::{VtkFormat, VtkWriter};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub enum CommandError {
+    Scene(SceneError),
+    Output(OutputError),
+    Recording(RecordingError),
+    Io(io::Error),
+    ViewerUnavailable,
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Scene(error) => write!(f, "{}", error),
+            CommandError::Output(error) => write!(f, "{}", error),
+            CommandError::Recording(error) => write!(f, "{}", error),
+            CommandError::Io(error) => write!(f, "could not write output: {}", error),
+            CommandError::ViewerUnavailable => write!(
+                f,
+                "this build has no viewer, rebuild with the viewer feature to use view"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<SceneError> for CommandError {
+    fn from(error: SceneError) -> Self {
+        CommandError::Scene(error)
+    }
+}
+
+impl From<OutputError> for CommandError {
+    fn from(error: OutputError) -> Self {
+        CommandError::Output(error)
+    }
+}
+
+impl From<RecordingError> for CommandError {
+    fn from(error: RecordingError) -> Self {
+        CommandError::Recording(error)
+    }
+}
+
+impl From<io::Error> for CommandError {
+    fn from(error: io::Error) -> Self {
+        CommandError::Io(error)
+    }
+}
+
+pub struct RunSummary {
+    pub particle_count: usize,
+    pub steps: usize,
+    pub simulated_time: f64,
+    pub elapsed_time: Duration,
+}
+
+impl fmt::Display for RunSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} particles, {} updates, {} s simulated in {:?}",
+            self.particle_count, self.steps, self.simulated_time, self.elapsed_time
+        )
+    }
+}
+
+// Runs the scene without a window, writing the particle output and recording
+// it asks for. A length in seconds is rounded to whole updates.
+pub fn run(options: &RunOptions) -> Result<RunSummary, CommandError> {
+    let scene = options.scene.scene()?;
+    let mut simulation = Simulation::new(&scene);
+    let steps = match options.length {
+        RunLength::Steps(steps) => steps,
+        RunLength::Seconds(seconds) => ((seconds * simulation.ups as f64).round() as usize).max(1),
+    };
+    let mut output_manager = OutputManager::new(&scene, &simulation)?;
+    let start = Instant::now();
+    output_manager.write_step(&simulation, 0)?;
+    for step in 1..=steps {
+        simulation.update();
+        output_manager.write_step(&simulation, step)?;
+    }
+    output_manager.flush()?;
+    Ok(RunSummary {
+        particle_count: simulation.particles.len(),
+        steps,
+        simulated_time: simulation.time() as f64,
+        elapsed_time: start.elapsed(),
+    })
+}
+
+// Wall times of every timed update, per run.
+pub struct BenchReport {
+    pub particle_count: usize,
+    pub update_times: Vec<Vec<Duration>>,
+    pub sub_steps: usize,
+    pub neighbor_rebuilds: usize,
+    pub neighbor_updates: usize,
+}
+
+impl BenchReport {
+    pub fn mean(&self) -> Duration {
+        let times = self.all_times();
+        times.iter().sum::<Duration>() / times.len().max(1) as u32
+    }
+
+    pub fn standard_deviation(&self) -> Duration {
+        let times = self.all_times();
+        let mean = self.mean().as_secs_f64();
+        let variance = times
+            .iter()
+            .map(|time| (time.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / times.len().max(1) as f64;
+        Duration::from_secs_f64(variance.sqrt())
+    }
+
+    // Time that the given fraction of updates took at most.
+    pub fn percentile(&self, fraction: f64) -> Duration {
+        let mut times = self.all_times();
+        if times.is_empty() {
+            return Duration::ZERO;
+        }
+        times.sort();
+        let index = (fraction.clamp(0.0, 1.0) * (times.len() - 1) as f64).round() as usize;
+        times[index]
+    }
+
+    pub fn run_means(&self) -> Vec<Duration> {
+        self.update_times
+            .iter()
+            .map(|times| times.iter().sum::<Duration>() / times.len().max(1) as u32)
+            .collect()
+    }
+
+    pub fn write_csv(&self, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "run,update,seconds")?;
+        for (run, times) in self.update_times.iter().enumerate() {
+            for (update, time) in times.iter().enumerate() {
+                writeln!(writer, "{},{},{}", run, update, time.as_secs_f64())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn all_times(&self) -> Vec<Duration> {
+        self.update_times.iter().flatten().copied().collect()
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let updates = self.all_times().len();
+        writeln!(
+            f,
+            "{} particles, {} runs of {} updates",
+            self.particle_count,
+            self.update_times.len(),
+            updates / self.update_times.len().max(1)
+        )?;
+        writeln!(
+            f,
+            "update: mean {:?}, standard deviation {:?}, min {:?}, median {:?}, max {:?}",
+            self.mean(),
+            self.standard_deviation(),
+            self.percentile(0.0),
+            self.percentile(0.5),
+            self.percentile(1.0)
+        )?;
+        writeln!(f, "run means: {:?}", self.run_means())?;
+        write!(
+            f,
+            "average step {:?}, neighbor rebuilds {}/{}",
+            self.mean() * updates as u32 / self.sub_steps.max(1) as u32,
+            self.neighbor_rebuilds,
+            self.neighbor_updates
+        )
+    }
+}
+
+// Times repeated runs of the scene from the same initial state, without its
+// outputs. With an output directory the timings are written to bench.csv.
+pub fn bench(options: &BenchOptions) -> Result<BenchReport, CommandError> {
+    let mut scene = options.scene.scene()?;
+    scene.output = None;
+    scene.recording = None;
+    let mut report = BenchReport {
+        particle_count: 0,
+        update_times: Vec::with_capacity(options.repeats),
+        sub_steps: 0,
+        neighbor_rebuilds: 0,
+        neighbor_updates: 0,
+    };
+    for _ in 0..options.repeats {
+        let mut simulation = Simulation::new(&scene);
+        for _ in 0..options.warm_up_steps {
+            simulation.update();
+        }
+        let statistics_before = simulation.neighbor_statistics();
+        let mut times = Vec::with_capacity(options.steps);
+        for _ in 0..options.steps {
+            let start = Instant::now();
+            simulation.update();
+            times.push(start.elapsed());
+            report.sub_steps += simulation.sub_steps();
+        }
+        let statistics = simulation.neighbor_statistics();
+        report.neighbor_rebuilds += statistics.rebuilds - statistics_before.rebuilds;
+        report.neighbor_updates += statistics.updates - statistics_before.updates;
+        report.particle_count = simulation.particles.len();
+        report.update_times.push(times);
+    }
+    if let Some(directory) = &options.scene.output_directory {
+        fs::create_dir_all(directory)?;
+        let mut writer = BufWriter::new(File::create(directory.join("bench.csv"))?);
+        report.write_csv(&mut writer)?;
+        writer.flush()?;
+    }
+    Ok(report)
+}
+
+pub struct ConvertSummary {
+    pub frame_count: usize,
+    // The CSV file, or the .pvd collection of the VTK files.
+    pub output_path: PathBuf,
+}
+
+// Converts a recording into files named after it, next to it unless an output
+// directory is given.
+pub fn convert(options: &ConvertOptions) -> Result<ConvertSummary, CommandError> {
+    let mut recording = Recording::open(&options.recording_path)?;
+    let directory = match &options.output_directory {
+        Some(directory) => directory.clone(),
+        None => options
+            .recording_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+    };
+    let name = options
+        .recording_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "recording".to_string());
+    fs::create_dir_all(&directory)?;
+    let output_path = match options.format {
+        ConvertFormat::Csv => {
+            let output_path = directory.join(format!("{}.csv", name));
+            let mut writer = BufWriter::new(File::create(&output_path)?);
+            recording.export_csv(&mut writer)?;
+            writer.flush()?;
+            output_path
+        }
+        ConvertFormat::Vtk | ConvertFormat::Vtu => {
+            let format = match options.format {
+                ConvertFormat::Vtk => VtkFormat::Legacy,
+                _ => VtkFormat::Xml,
+            };
+            let mut writer = VtkWriter::new(directory.clone(), name.clone(), vec![format], 1);
+            let mut frame = Frame::default();
+            for index in 0..recording.frame_count() {
+                recording.read_frame_into(index, &mut frame)?;
+                writer.write_frame(&frame)?;
+            }
+            directory.join(format!("{}.pvd", name))
+        }
+    };
+    Ok(ConvertSummary {
+        frame_count: recording.frame_count(),
+        output_path,
+    })
+}
@@ -0,0 +1,74 @@
+use crate::fluid_simulation::real::{Real, RealVector};
+use serde::Deserialize;
+use vector2d::Vector2D;
+
+// How a surface slows down the velocity along it on impact.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Friction {
+    // The tangential velocity is kept.
+    #[default]
+    FreeSlip,
+    // The tangential velocity is removed.
+    NoSlip,
+    // The tangential speed drops by the coefficient times the change of the
+    // normal speed, down to zero.
+    Coulomb(f32),
+}
+
+// Response of a wall or obstacle surface to particles hitting it. The
+// default is a perfectly elastic, frictionless surface.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct BoundaryMaterial {
+    // Fraction of the normal speed kept after the impact, between 0 and 1.
+    pub restitution: f32,
+    pub friction: Friction,
+}
+
+impl Default for BoundaryMaterial {
+    fn default() -> Self {
+        BoundaryMaterial {
+            restitution: 1.0,
+            friction: Friction::FreeSlip,
+        }
+    }
+}
+
+impl BoundaryMaterial {
+    // Velocity after hitting a surface whose unit normal points out of it.
+    // Velocities moving away from the surface are kept.
+    pub fn collide<T: Real>(&self, velocity: Vector2D<T>, normal: Vector2D<T>) -> Vector2D<T> {
+        let normal_speed = Vector2D::dot(velocity, normal);
+        if normal_speed >= T::zero() {
+            return velocity;
+        }
+        let restitution = T::from_f32(self.restitution);
+        let tangential_velocity = velocity - normal * normal_speed;
+        let tangential_velocity = match self.friction {
+            Friction::FreeSlip => tangential_velocity,
+            Friction::NoSlip => Vector2D::new(T::zero(), T::zero()),
+            Friction::Coulomb(coefficient) => {
+                let tangential_speed = tangential_velocity.length();
+                let speed_loss =
+                    T::from_f32(coefficient) * (T::one() + restitution) * -normal_speed;
+                if tangential_speed <= speed_loss {
+                    Vector2D::new(T::zero(), T::zero())
+                } else {
+                    tangential_velocity * ((tangential_speed - speed_loss) / tangential_speed)
+                }
+            }
+        };
+        tangential_velocity - normal * (normal_speed * restitution)
+    }
+}
+
+// Materials of the four walls of the domain box.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct WallMaterials {
+    pub left: BoundaryMaterial,
+    pub right: BoundaryMaterial,
+    pub top: BoundaryMaterial,
+    pub bottom: BoundaryMaterial,
+}
@@ -1,125 +1,267 @@
-use crate::fluid_simulation::config::PARTICLE_COUNT;
+use crate::fluid_simulation::grids::bounded_grid::BoundedGrid;
+use crate::fluid_simulation::grids::grid_trait::Grid;
 use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::particle_store::ParticleStore;
+use crate::fluid_simulation::real::Real;
+use rayon::prelude::*;
+use std::ops::Range;
 use vector2d::Vector2D;
 
-type SpatialLookup = [(usize, usize); PARTICLE_COUNT];
-pub struct CellManager {
-    particle_count: i32,
-    spatial_lookup: SpatialLookup,
-    starting_indices: Vec<usize>,
-    number_of_columns: i32,
-    number_of_rows: i32,
-    cell_size: f32,
-    number_of_cells: i32,
+const CHUNKS_PER_THREAD: usize = 4;
+// Every chunk counts all cells, so grids with many cells, like hashed ones,
+// are sorted in fewer chunks to keep the counting work proportional to the
+// particle count.
+const PARTICLES_PER_CELL_AND_CHUNK: usize = 8;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NeighborStatistics {
+    pub updates: usize,
+    pub rebuilds: usize,
+    // Largest displacement since the neighbor lists were built.
+    pub max_displacement: f32,
 }
 
-impl CellManager {
-    pub fn new(particle_count: i32, box_dimensions: [usize; 2], smoothing_radius: f32) -> Self {
-        let cell_size = 2.0 * smoothing_radius;
-        let number_of_columns = (box_dimensions[0] as f32 / cell_size).ceil() as i32;
-        let number_of_rows = (box_dimensions[1] as f32 / cell_size).ceil() as i32;
-        let number_of_cells = (number_of_columns * number_of_rows) as i32;
-        CellManager {
+// Particles are kept sorted by cell key, so the particles of cell k are the
+// indices cell_offsets[k]..cell_offsets[k + 1]. Sorting is a stable counting
+// sort: every chunk of particles counts its cells in parallel, a prefix sum
+// over cells and chunks gives each chunk its write positions, and the chunks
+// then compute the destinations of their particles in parallel.
+pub struct CellManager<T: Real = f32> {
+    cell_offsets: Vec<usize>,
+    chunk_offsets: Vec<Vec<usize>>,
+    destinations: Vec<usize>,
+    // New index to index before the last update.
+    permutation: Vec<usize>,
+    id_to_index: Vec<usize>,
+    // Per particle, the indices of the particles within one cell size,
+    // rebuilt on every update and shared by the density and force passes.
+    neighbors: Vec<Vec<usize>>,
+    // Verlet lists: with a positive skin the neighbor lists reach skin beyond
+    // the cell size and are kept until a particle has moved more than half
+    // the skin from its position at the last build.
+    skin: T,
+    build_positions: Vec<Vector2D<T>>,
+    statistics: NeighborStatistics,
+    grid: Box<dyn Grid<T>>,
+    number_of_cells: usize,
+}
+
+impl<T: Real> CellManager<T> {
+    pub fn new(particle_count: i32, box_dimensions: [usize; 2], smoothing_radius: T) -> Self {
+        CellManager::with_grid(
             particle_count,
-            spatial_lookup: core::array::from_fn(|_| (number_of_cells as usize, 0)),
-            starting_indices: (0..number_of_cells)
-                .map(|_| number_of_cells as usize)
-                .collect(),
-            number_of_columns,
-            number_of_rows,
-            cell_size,
+            Box::new(BoundedGrid::new(
+                box_dimensions,
+                T::from_f64(2.0) * smoothing_radius,
+            )),
+            T::zero(),
+        )
+    }
+
+    // The grid cells must already be skin wider than two smoothing radii.
+    pub fn with_grid(particle_count: i32, grid: Box<dyn Grid<T>>, skin: T) -> Self {
+        let number_of_cells = grid.number_of_cells();
+        let particle_count = particle_count as usize;
+        CellManager {
+            cell_offsets: vec![0; number_of_cells + 1],
+            chunk_offsets: Vec::new(),
+            destinations: vec![0; particle_count],
+            permutation: (0..particle_count).collect(),
+            id_to_index: (0..particle_count).collect(),
+            neighbors: vec![Vec::new(); particle_count],
+            skin,
+            build_positions: Vec::with_capacity(particle_count),
+            statistics: NeighborStatistics::default(),
+            grid,
             number_of_cells,
         }
     }
 
-    pub fn update(&mut self, particles: &mut [Particle]) {
-        for particle in particles {
-            self.to_spacial_lookup(particle)
+    // Sorts the particles into cell order and rebuilds the neighbor lists,
+    // unless the Verlet lists are still valid. Returns whether the particles
+    // were reordered; other per-particle arrays that must survive the update
+    // are then reordered with permutation().
+    pub fn update_store(&mut self, particles: &mut ParticleStore<T>) -> bool {
+        self.statistics.updates += 1;
+        if !self.needs_rebuild(particles) {
+            return false;
         }
-        self.spatial_lookup.sort_by(|s_a, s_b| s_a.0.cmp(&s_b.0));
-        self.generate_start_indices();
+        let grid = &self.grid;
+        particles
+            .cell_keys
+            .par_iter_mut()
+            .zip(particles.position_x.par_iter())
+            .zip(particles.position_y.par_iter())
+            .for_each(|((cell_key, &x), &y)| *cell_key = grid.cell_key(Vector2D::new(x, y)));
+        let chunk_size = self.calculate_chunk_size(particles.len());
+        self.count_chunk_cells(&particles.cell_keys, chunk_size);
+        self.generate_offsets();
+        self.generate_destinations(&particles.cell_keys, chunk_size);
+        self.reorder_particles(particles);
+        self.generate_neighbors(particles);
+        self.build_positions.clear();
+        self.build_positions.par_extend(
+            particles
+                .position_x
+                .par_iter()
+                .zip(particles.position_y.par_iter())
+                .map(|(&x, &y)| Vector2D::new(x, y)),
+        );
+        self.statistics.rebuilds += 1;
+        self.statistics.max_displacement = 0.0;
+        true
     }
 
-    pub fn get_adjacent_particles_indices<'a>(
-        &'a self,
-        particle_position: Vector2D<f32>,
-    ) -> impl Iterator<Item = usize> + 'a {
-        self.get_adjacent_cell_keys_from_position(particle_position)
-            .flat_map(|adjacent_cell_key| self.get_particle_indexes_from_cell(adjacent_cell_key))
+    // update_store for particles kept as structs.
+    pub fn update(&mut self, particles: &mut [Particle<T>]) -> bool {
+        let mut store = ParticleStore::from_particles(particles);
+        let is_rebuilt = self.update_store(&mut store);
+        particles
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(index, particle)| *particle = store.get(index));
+        is_rebuilt
+    }
+
+    // Forgets the cell order and neighbor lists after the particles were
+    // replaced, e.g. by a checkpoint. The next update rebuilds them.
+    pub fn reset(&mut self, particles: &ParticleStore<T>) {
+        self.permutation.clear();
+        self.permutation.extend(0..particles.len());
+        self.id_to_index.resize(particles.len(), 0);
+        for (index, &id) in particles.ids.iter().enumerate() {
+            self.id_to_index[id] = index;
+        }
+        self.build_positions.clear();
     }
 
-    fn to_spacial_lookup(&mut self, particle: &mut Particle) {
-        let cell_coord = self.particle_position_to_cell_coord(particle.position);
-        let cell_key = self.cell_coord_to_cell_key(cell_coord);
-        particle.cell_key = cell_key;
-        self.spatial_lookup[particle.id as usize] = (cell_key, particle.id)
+    // Neighbors cached by the last update, including the particle itself.
+    pub fn get_neighbors(&self, particle_index: usize) -> &[usize] {
+        &self.neighbors[particle_index]
     }
 
-    fn generate_start_indices(&mut self) {
-        self.starting_indices = vec![self.particle_count as usize; self.number_of_cells as usize];
-        self.spatial_lookup
-            .iter()
-            .enumerate()
-            .for_each(|(sl_index, &(cell_key, _))| {
-                if self.starting_indices[cell_key] == self.particle_count as usize {
-                    self.starting_indices[cell_key] = sl_index;
-                }
-            });
+    pub fn statistics(&self) -> NeighborStatistics {
+        self.statistics
     }
 
-    fn get_adjacent_cell_keys_from_position<'a>(
-        &'a self,
-        position: Vector2D<f32>,
-    ) -> impl Iterator<Item = usize> + 'a {
-        let current_cell_coord = self.particle_position_to_cell_coord(position);
-        let adjacent_cell_coords = vec![
-            current_cell_coord + Vector2D::new(-1, -1),
-            current_cell_coord + Vector2D::new(-1, 0),
-            current_cell_coord + Vector2D::new(-1, 1),
-            current_cell_coord + Vector2D::new(0, -1),
-            current_cell_coord,
-            current_cell_coord + Vector2D::new(0, 1),
-            current_cell_coord + Vector2D::new(1, -1),
-            current_cell_coord + Vector2D::new(1, 0),
-            current_cell_coord + Vector2D::new(1, 1),
-        ];
-        adjacent_cell_coords
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    pub fn get_particle_index(&self, particle_id: usize) -> usize {
+        self.id_to_index[particle_id]
+    }
+
+    pub fn get_adjacent_particles_indices(
+        &self,
+        particle_position: Vector2D<T>,
+    ) -> impl Iterator<Item = usize> + '_ {
+        self.grid
+            .adjacent_cell_keys(particle_position)
             .into_iter()
-            .filter(|coord| {
-                coord.x >= 0
-                    && coord.x < self.number_of_columns
-                    && coord.y >= 0
-                    && coord.y < self.number_of_rows
-            })
-            .map(|coord| self.cell_coord_to_cell_key(coord))
+            .flat_map(|adjacent_cell_key| self.get_particle_indexes_from_cell(adjacent_cell_key))
     }
 
-    fn get_particle_indexes_from_cell(&self, cell_key: usize) -> Vec<usize> {
-        let mut particle_indexes: Vec<usize> = Vec::new();
-        let mut spatial_lookup_cell: usize = cell_key;
-        let mut spatial_lookup_index = self.starting_indices[cell_key];
-        if spatial_lookup_index >= self.particle_count as usize {
-            return particle_indexes;
+    fn needs_rebuild(&mut self, particles: &ParticleStore<T>) -> bool {
+        if self.skin <= T::zero() || self.build_positions.len() != particles.len() {
+            return true;
         }
-        while cell_key == spatial_lookup_cell {
-            let particle_index = self.spatial_lookup[spatial_lookup_index].1;
-            particle_indexes.push(particle_index);
-            spatial_lookup_index += 1;
-            if spatial_lookup_index >= self.particle_count as usize {
-                break;
+        let max_displacement_squared = particles
+            .position_x
+            .par_iter()
+            .zip(particles.position_y.par_iter())
+            .zip(self.build_positions.par_iter())
+            .map(|((&x, &y), &build_position)| {
+                (Vector2D::new(x, y) - build_position).length_squared()
+            })
+            .reduce(T::zero, T::max);
+        let max_displacement = max_displacement_squared.sqrt();
+        self.statistics.max_displacement = max_displacement.as_f32();
+        max_displacement > T::from_f64(0.5) * self.skin
+    }
+
+    fn calculate_chunk_size(&self, particle_count: usize) -> usize {
+        let chunk_count = (rayon::current_num_threads() * CHUNKS_PER_THREAD)
+            .min(1 + PARTICLES_PER_CELL_AND_CHUNK * particle_count / self.number_of_cells);
+        particle_count.div_ceil(chunk_count).max(1)
+    }
+
+    fn count_chunk_cells(&mut self, cell_keys: &[usize], chunk_size: usize) {
+        let number_of_cells = self.number_of_cells;
+        self.chunk_offsets
+            .resize_with(cell_keys.len().div_ceil(chunk_size), Vec::new);
+        self.chunk_offsets
+            .par_iter_mut()
+            .zip(cell_keys.par_chunks(chunk_size))
+            .for_each(|(cell_counts, chunk)| {
+                cell_counts.clear();
+                cell_counts.resize(number_of_cells, 0);
+                for &cell_key in chunk {
+                    cell_counts[cell_key] += 1;
+                }
+            });
+    }
+
+    // Turns the chunk cell counts into the first destination of each chunk
+    // in each cell. Chunks are visited in order, which keeps the sort stable.
+    fn generate_offsets(&mut self) {
+        let mut offset = 0;
+        for cell_key in 0..self.number_of_cells {
+            self.cell_offsets[cell_key] = offset;
+            for cell_counts in self.chunk_offsets.iter_mut() {
+                let count = cell_counts[cell_key];
+                cell_counts[cell_key] = offset;
+                offset += count;
             }
-            spatial_lookup_cell = self.spatial_lookup[spatial_lookup_index].0;
         }
-        particle_indexes
+        self.cell_offsets[self.number_of_cells] = offset;
     }
 
-    fn particle_position_to_cell_coord(&self, position: Vector2D<f32>) -> Vector2D<i32> {
-        let x = (position.x / self.cell_size).floor() as i32;
-        let y = (position.y / self.cell_size).floor() as i32;
-        Vector2D::new(x, y)
+    fn generate_destinations(&mut self, cell_keys: &[usize], chunk_size: usize) {
+        self.destinations.resize(cell_keys.len(), 0);
+        self.destinations
+            .par_chunks_mut(chunk_size)
+            .zip(cell_keys.par_chunks(chunk_size))
+            .zip(self.chunk_offsets.par_iter_mut())
+            .for_each(|((destinations, chunk), offsets)| {
+                for (destination, &cell_key) in destinations.iter_mut().zip(chunk) {
+                    *destination = offsets[cell_key];
+                    offsets[cell_key] += 1;
+                }
+            });
+    }
+
+    fn reorder_particles(&mut self, particles: &mut ParticleStore<T>) {
+        self.permutation.resize(particles.len(), 0);
+        self.id_to_index.resize(particles.len(), 0);
+        for (index, &destination) in self.destinations.iter().enumerate() {
+            self.permutation[destination] = index;
+            self.id_to_index[particles.ids[index]] = destination;
+        }
+        particles.reorder(&self.permutation);
+    }
+
+    fn generate_neighbors(&mut self, particles: &ParticleStore<T>) {
+        let mut neighbors = std::mem::take(&mut self.neighbors);
+        neighbors.resize_with(particles.len(), Vec::new);
+        let search_radius_squared = self.grid.cell_size().powi(2);
+        neighbors
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(particle_index, particle_neighbors)| {
+                let position = particles.position(particle_index);
+                particle_neighbors.clear();
+                particle_neighbors.extend(self.get_adjacent_particles_indices(position).filter(
+                    |&index| {
+                        (particles.position(index) - position).length_squared()
+                            < search_radius_squared
+                    },
+                ));
+            });
+        self.neighbors = neighbors;
     }
 
-    fn cell_coord_to_cell_key(&self, coord: Vector2D<i32>) -> usize {
-        ((coord.x * self.number_of_rows) + coord.y) as usize
+    fn get_particle_indexes_from_cell(&self, cell_key: usize) -> Range<usize> {
+        self.cell_offsets[cell_key]..self.cell_offsets[cell_key + 1]
     }
 }
@@ -0,0 +1,329 @@
+//! Binary checkpoints of the dynamic state of a simulation. A checkpoint
+//! holds what changes while a scene runs: the particles, their per-particle
+//! arrays, the simulated time, the timestep, the state of dams, gravity and
+//! attractors, and the poses and velocities of rigid bodies. The
+//! configuration comes from the scene, so a checkpoint is restored into a
+//! simulation built from the scene it was saved from.
+//!
+//! All values are little-endian. The header is the magic bytes, the format
+//! version as a u32 and the size in bytes of the stored floats, 4 or 8.
+//! Checkpoints can be restored at either precision.
+
+use crate::fluid_simulation::obstacles::motions::pose::{Pose, PoseVelocity};
+use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::real::Real;
+use std::fmt;
+use std::io::{self, Read, Write};
+use vector2d::Vector2D;
+
+pub const CHECKPOINT_VERSION: u32 = 3;
+const MAGIC: &[u8; 8] = b"SPHCHKPT";
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(io::Error),
+    NotACheckpoint,
+    UnsupportedVersion(u32),
+    Corrupted(String),
+    Incompatible(String),
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::Io(error) => write!(f, "could not access checkpoint: {}", error),
+            CheckpointError::NotACheckpoint => write!(f, "not a checkpoint file"),
+            CheckpointError::UnsupportedVersion(version) => write!(
+                f,
+                "checkpoint version {} is not supported, expected version {}",
+                version, CHECKPOINT_VERSION
+            ),
+            CheckpointError::Corrupted(message) => write!(f, "corrupted checkpoint: {}", message),
+            CheckpointError::Incompatible(message) => {
+                write!(f, "checkpoint does not match the scene: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+impl From<io::Error> for CheckpointError {
+    fn from(error: io::Error) -> Self {
+        CheckpointError::Io(error)
+    }
+}
+
+pub struct AttractorState<T: Real = f32> {
+    pub position: Vector2D<T>,
+    pub active: bool,
+}
+
+pub struct RigidBodyState<T: Real = f32> {
+    pub pose: Pose<T>,
+    pub velocity: PoseVelocity<T>,
+}
+
+// Particles are stored in their current order, and every per-particle array
+// in the same order.
+pub struct CheckpointState<T: Real = f32> {
+    pub particles: Vec<Particle<T>>,
+    pub densities: Vec<T>,
+    pub pressures: Vec<T>,
+    pub accelerations: Vec<Vector2D<T>>,
+    pub non_pressure_accelerations: Vec<Vector2D<T>>,
+    pub pressure_accelerations: Vec<Vector2D<T>>,
+    pub time: T,
+    pub delta_time: T,
+    pub is_gravity_on: bool,
+    pub active_dams: Vec<bool>,
+    pub attractors: Vec<AttractorState<T>>,
+    pub rigid_bodies: Vec<RigidBodyState<T>>,
+}
+
+impl<T: Real> CheckpointState<T> {
+    pub fn write(&self, writer: &mut impl Write) -> Result<(), CheckpointError> {
+        let mut encoder = Encoder {
+            writer,
+            real_size: std::mem::size_of::<T>(),
+        };
+        encoder.write_bytes(MAGIC)?;
+        encoder.write_u32(CHECKPOINT_VERSION)?;
+        encoder.write_bytes(&[encoder.real_size as u8])?;
+
+        encoder.write_real(self.time)?;
+        encoder.write_real(self.delta_time)?;
+        encoder.write_bool(self.is_gravity_on)?;
+        encoder.write_u64(self.active_dams.len() as u64)?;
+        for &active in self.active_dams.iter() {
+            encoder.write_bool(active)?;
+        }
+        encoder.write_u64(self.attractors.len() as u64)?;
+        for attractor in self.attractors.iter() {
+            encoder.write_vector(attractor.position)?;
+            encoder.write_bool(attractor.active)?;
+        }
+        encoder.write_u64(self.rigid_bodies.len() as u64)?;
+        for rigid_body in self.rigid_bodies.iter() {
+            encoder.write_vector(rigid_body.pose.translation)?;
+            encoder.write_real(rigid_body.pose.rotation)?;
+            encoder.write_vector(rigid_body.velocity.linear)?;
+            encoder.write_real(rigid_body.velocity.angular)?;
+        }
+
+        encoder.write_u64(self.particles.len() as u64)?;
+        for particle in self.particles.iter() {
+            encoder.write_u64(particle.id as u64)?;
+            encoder.write_vector(particle.position)?;
+            encoder.write_vector(particle.velocity)?;
+            encoder.write_real(particle.mass)?;
+        }
+        for &value in self.densities.iter().chain(self.pressures.iter()) {
+            encoder.write_real(value)?;
+        }
+        for &vector in self
+            .accelerations
+            .iter()
+            .chain(self.non_pressure_accelerations.iter())
+            .chain(self.pressure_accelerations.iter())
+        {
+            encoder.write_vector(vector)?;
+        }
+        Ok(())
+    }
+
+    pub fn read(reader: &mut impl Read) -> Result<Self, CheckpointError> {
+        let mut decoder = Decoder {
+            reader,
+            real_size: 0,
+        };
+        let mut magic = [0; 8];
+        decoder.reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(CheckpointError::NotACheckpoint);
+        }
+        let version = decoder.read_u32()?;
+        if version != CHECKPOINT_VERSION {
+            return Err(CheckpointError::UnsupportedVersion(version));
+        }
+        decoder.real_size = decoder.read_u8()? as usize;
+        if decoder.real_size != 4 && decoder.real_size != 8 {
+            return Err(CheckpointError::Corrupted(format!(
+                "unknown float size {}",
+                decoder.real_size
+            )));
+        }
+
+        let time = decoder.read_real()?;
+        let delta_time = decoder.read_real()?;
+        let is_gravity_on = decoder.read_bool()?;
+        let dam_count = decoder.read_count()?;
+        let active_dams = (0..dam_count)
+            .map(|_| decoder.read_bool())
+            .collect::<Result<_, _>>()?;
+        let attractor_count = decoder.read_count()?;
+        let attractors = (0..attractor_count)
+            .map(|_| {
+                Ok(AttractorState {
+                    position: decoder.read_vector()?,
+                    active: decoder.read_bool()?,
+                })
+            })
+            .collect::<Result<_, CheckpointError>>()?;
+        let rigid_body_count = decoder.read_count()?;
+        let rigid_bodies = (0..rigid_body_count)
+            .map(|_| {
+                Ok(RigidBodyState {
+                    pose: Pose::new(decoder.read_vector()?, decoder.read_real()?),
+                    velocity: PoseVelocity::new(decoder.read_vector()?, decoder.read_real()?),
+                })
+            })
+            .collect::<Result<_, CheckpointError>>()?;
+
+        // Nothing is allocated from a count up front: a corrupted count
+        // fails once the input ends instead.
+        let particle_count = decoder.read_count()?;
+        let particles: Vec<Particle<T>> = (0..particle_count)
+            .map(|_| {
+                let id = decoder.read_u64()? as usize;
+                Ok(Particle {
+                    position: decoder.read_vector()?,
+                    velocity: decoder.read_vector()?,
+                    mass: decoder.read_real()?,
+                    ..Particle::new(id, Vector2D::new(T::zero(), T::zero()))
+                })
+            })
+            .collect::<Result<_, CheckpointError>>()?;
+        // Particle ids index per-id arrays, so they must be 0..particle_count.
+        let mut is_id_used = vec![false; particle_count];
+        for particle in particles.iter() {
+            if particle.id >= particle_count || is_id_used[particle.id] {
+                return Err(CheckpointError::Corrupted(format!(
+                    "invalid particle id {}",
+                    particle.id
+                )));
+            }
+            is_id_used[particle.id] = true;
+        }
+        let mut read_reals = || {
+            (0..particle_count)
+                .map(|_| decoder.read_real())
+                .collect::<Result<Vec<T>, _>>()
+        };
+        let densities = read_reals()?;
+        let pressures = read_reals()?;
+        let mut read_vectors = || {
+            (0..particle_count)
+                .map(|_| decoder.read_vector())
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let accelerations = read_vectors()?;
+        let non_pressure_accelerations = read_vectors()?;
+        let pressure_accelerations = read_vectors()?;
+
+        Ok(CheckpointState {
+            particles,
+            densities,
+            pressures,
+            accelerations,
+            non_pressure_accelerations,
+            pressure_accelerations,
+            time,
+            delta_time,
+            is_gravity_on,
+            active_dams,
+            attractors,
+            rigid_bodies,
+        })
+    }
+}
+
+struct Encoder<'a, W: Write> {
+    writer: &'a mut W,
+    real_size: usize,
+}
+
+impl<W: Write> Encoder<'_, W> {
+    fn write_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.writer.write_all(bytes)
+    }
+
+    fn write_bool(&mut self, value: bool) -> io::Result<()> {
+        self.write_bytes(&[value as u8])
+    }
+
+    fn write_u32(&mut self, value: u32) -> io::Result<()> {
+        self.write_bytes(&value.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, value: u64) -> io::Result<()> {
+        self.write_bytes(&value.to_le_bytes())
+    }
+
+    fn write_real<T: Real>(&mut self, value: T) -> io::Result<()> {
+        if self.real_size == 4 {
+            self.write_bytes(&value.as_f32().to_le_bytes())
+        } else {
+            self.write_bytes(&value.as_f64().to_le_bytes())
+        }
+    }
+
+    fn write_vector<T: Real>(&mut self, vector: Vector2D<T>) -> io::Result<()> {
+        self.write_real(vector.x)?;
+        self.write_real(vector.y)
+    }
+}
+
+struct Decoder<'a, R: Read> {
+    reader: &'a mut R,
+    real_size: usize,
+}
+
+impl<R: Read> Decoder<'_, R> {
+    fn read_array<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut bytes = [0; N];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> io::Result<u8> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    fn read_bool(&mut self) -> Result<bool, CheckpointError> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(CheckpointError::Corrupted(format!(
+                "invalid flag {}",
+                value
+            ))),
+        }
+    }
+
+    fn read_u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_count(&mut self) -> Result<usize, CheckpointError> {
+        let count = self.read_u64()?;
+        usize::try_from(count)
+            .map_err(|_| CheckpointError::Corrupted(format!("invalid count {}", count)))
+    }
+
+    fn read_real<T: Real>(&mut self) -> io::Result<T> {
+        if self.real_size == 4 {
+            Ok(T::from_f32(f32::from_le_bytes(self.read_array()?)))
+        } else {
+            Ok(T::from_f64(f64::from_le_bytes(self.read_array()?)))
+        }
+    }
+
+    fn read_vector<T: Real>(&mut self) -> io::Result<Vector2D<T>> {
+        Ok(Vector2D::new(self.read_real()?, self.read_real()?))
+    }
+}
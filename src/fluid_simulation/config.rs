@@ -1,7 +1,9 @@
-use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::obstacles::obstacle_trait::Obstacle;
+use crate::fluid_simulation::particle_store::ParticleStore;
 use vector2d::Vector2D;
 
-pub const PARTICLE_COUNT: usize = 6500;
-pub type Particles = [Particle; PARTICLE_COUNT];
-pub type Accelerations = [Vector2D<f32>; PARTICLE_COUNT];
-pub type Densities = [f32; PARTICLE_COUNT];
+pub type Particles<T = f32> = ParticleStore<T>;
+pub type Accelerations<T = f32> = Vec<Vector2D<T>>;
+pub type Densities<T = f32> = Vec<T>;
+pub type Pressures<T = f32> = Vec<T>;
+pub type Obstacles<T = f32> = Vec<Box<dyn Obstacle<T> + Send + Sync>>;
@@ -0,0 +1,53 @@
+use crate::fluid_simulation::real::Real;
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EquationOfStateType {
+    Linear,
+    Tait,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum PressureLaw<T: Real = f32> {
+    Linear { pressure_multiplier: T },
+    Tait { speed_of_sound: T, gamma: T },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EquationOfState<T: Real = f32> {
+    pub law: PressureLaw<T>,
+    pub target_density: T,
+    pub clamp_negative_pressure: bool,
+}
+
+impl<T: Real> EquationOfState<T> {
+    pub fn new(law: PressureLaw<T>, target_density: T, clamp_negative_pressure: bool) -> Self {
+        EquationOfState {
+            law,
+            target_density,
+            clamp_negative_pressure,
+        }
+    }
+
+    pub fn pressure(&self, density: T) -> T {
+        let pressure = match self.law {
+            PressureLaw::Linear {
+                pressure_multiplier,
+            } => pressure_multiplier * (density - self.target_density),
+            // Tait/Cole: p = B ((rho / rho0)^gamma - 1) with B = rho0 c^2 / gamma,
+            // so that dp/drho at rest equals the squared speed of sound.
+            PressureLaw::Tait {
+                speed_of_sound,
+                gamma,
+            } => {
+                let stiffness = self.target_density * speed_of_sound.powi(2) / gamma;
+                stiffness * ((density / self.target_density).powf(gamma) - T::one())
+            }
+        };
+        if self.clamp_negative_pressure {
+            return pressure.max(T::zero());
+        }
+        pressure
+    }
+}
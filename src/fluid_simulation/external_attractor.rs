@@ -1,42 +1,46 @@
 use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::real::{Real, RealVector};
 use vector2d::Vector2D;
 
-pub struct ExternalAttractor {
-    pub position: Vector2D<f32>,
+pub struct ExternalAttractor<T: Real = f32> {
+    pub position: Vector2D<T>,
     pub active: bool,
-    radius: f32,
+    pub follows_pointer: bool,
+    radius: T,
 }
 
-impl ExternalAttractor {
-    pub fn new() -> Self {
+impl<T: Real> ExternalAttractor<T> {
+    pub fn new(position: Vector2D<T>, radius: T, active: bool, follows_pointer: bool) -> Self {
         ExternalAttractor {
-            position: Vector2D::new(0.0, 0.0),
-            active: false,
-            radius: 80.0,
+            position,
+            active,
+            follows_pointer,
+            radius,
         }
     }
 
-    pub fn activate(&mut self, position: Vector2D<f32>) -> () {
+    pub fn activate(&mut self, position: Vector2D<T>) {
         self.active = true;
         self.position = position;
     }
 
     pub fn get_external_attraction_acceleration(
         &self,
-        particle: &Particle,
-        density: f32,
-    ) -> Vector2D<f32> {
+        particle: &Particle<T>,
+        density: T,
+    ) -> Vector2D<T> {
         if !self.active {
-            return Vector2D::new(0.0, 0.0);
+            return Vector2D::new(T::zero(), T::zero());
         }
         let vetcor_to_input_point = self.position - particle.position;
         let distance_to_input_point = vetcor_to_input_point.length();
         if distance_to_input_point >= self.radius {
-            return Vector2D::new(0.0, 0.0);
+            return Vector2D::new(T::zero(), T::zero());
         }
         //-(vetcor_to_input_point.normalise() - particle.velocity * (1.0 - distance_to_input_point / self.radius))
-        ((vetcor_to_input_point.normalise()) / 8.0
-            - particle.velocity * (1.0 - distance_to_input_point / self.radius) / 5000.0)
+        ((vetcor_to_input_point.normalise()) / T::from_f64(8.0)
+            - particle.velocity * (T::one() - distance_to_input_point / self.radius)
+                / T::from_f64(5000.0))
             / density
     }
 }
@@ -0,0 +1,69 @@
+use super::grid_trait::{position_to_cell_coord, AdjacentCellKeys, Grid, ADJACENT_CELL_OFFSETS};
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+// Dense grid covering the domain box. Positions outside the box belong to
+// the nearest border cell.
+pub struct BoundedGrid<T: Real = f32> {
+    number_of_columns: i32,
+    number_of_rows: i32,
+    cell_size: T,
+}
+
+impl<T: Real> BoundedGrid<T> {
+    pub fn new(box_dimensions: [usize; 2], cell_size: T) -> Self {
+        BoundedGrid {
+            number_of_columns: ((T::from_f64(box_dimensions[0] as f64) / cell_size)
+                .ceil()
+                .as_f64() as i32)
+                .max(1),
+            number_of_rows: ((T::from_f64(box_dimensions[1] as f64) / cell_size)
+                .ceil()
+                .as_f64() as i32)
+                .max(1),
+            cell_size,
+        }
+    }
+
+    fn clamped_cell_coord(&self, position: Vector2D<T>) -> Vector2D<i32> {
+        let coord = position_to_cell_coord(position, self.cell_size);
+        Vector2D::new(
+            coord.x.clamp(0, self.number_of_columns - 1),
+            coord.y.clamp(0, self.number_of_rows - 1),
+        )
+    }
+
+    fn cell_coord_to_cell_key(&self, coord: Vector2D<i32>) -> usize {
+        ((coord.x * self.number_of_rows) + coord.y) as usize
+    }
+}
+
+impl<T: Real> Grid<T> for BoundedGrid<T> {
+    fn number_of_cells(&self) -> usize {
+        (self.number_of_columns * self.number_of_rows) as usize
+    }
+
+    fn cell_size(&self) -> T {
+        self.cell_size
+    }
+
+    fn cell_key(&self, position: Vector2D<T>) -> usize {
+        self.cell_coord_to_cell_key(self.clamped_cell_coord(position))
+    }
+
+    fn adjacent_cell_keys(&self, position: Vector2D<T>) -> AdjacentCellKeys {
+        let current_cell_coord = self.clamped_cell_coord(position);
+        let mut cell_keys = AdjacentCellKeys::new();
+        for (x, y) in ADJACENT_CELL_OFFSETS {
+            let coord = current_cell_coord + Vector2D::new(x, y);
+            if coord.x >= 0
+                && coord.x < self.number_of_columns
+                && coord.y >= 0
+                && coord.y < self.number_of_rows
+            {
+                cell_keys.push(self.cell_coord_to_cell_key(coord));
+            }
+        }
+        cell_keys
+    }
+}
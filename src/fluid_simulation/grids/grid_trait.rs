@@ -0,0 +1,72 @@
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+pub const ADJACENT_CELL_OFFSETS: [(i32, i32); 9] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 0),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+// Maps positions to cell keys in 0..number_of_cells. Every position, also
+// far outside the domain or not finite, must get a valid key.
+pub trait Grid<T: Real = f32>: Send + Sync {
+    fn number_of_cells(&self) -> usize;
+
+    fn cell_size(&self) -> T;
+
+    fn cell_key(&self, position: Vector2D<T>) -> usize;
+
+    // Distinct keys of the cells around the cell of position, its own included.
+    fn adjacent_cell_keys(&self, position: Vector2D<T>) -> AdjacentCellKeys;
+}
+
+pub struct AdjacentCellKeys {
+    keys: [usize; 9],
+    count: usize,
+}
+
+impl AdjacentCellKeys {
+    pub fn new() -> Self {
+        AdjacentCellKeys {
+            keys: [0; 9],
+            count: 0,
+        }
+    }
+
+    // Keys already present are skipped, so no cell is visited twice.
+    pub fn push(&mut self, cell_key: usize) {
+        if !self.keys[..self.count].contains(&cell_key) {
+            self.keys[self.count] = cell_key;
+            self.count += 1;
+        }
+    }
+}
+
+impl Default for AdjacentCellKeys {
+    fn default() -> Self {
+        AdjacentCellKeys::new()
+    }
+}
+
+impl IntoIterator for AdjacentCellKeys {
+    type Item = usize;
+    type IntoIter = std::iter::Take<std::array::IntoIter<usize, 9>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.keys.into_iter().take(self.count)
+    }
+}
+
+// Float to int casts saturate and map NaN to zero, so any position gets a
+// coordinate.
+pub fn position_to_cell_coord<T: Real>(position: Vector2D<T>, cell_size: T) -> Vector2D<i32> {
+    let x = (position.x / cell_size).floor().as_f64() as i32;
+    let y = (position.y / cell_size).floor().as_f64() as i32;
+    Vector2D::new(x, y)
+}
@@ -0,0 +1,27 @@
+use super::bounded_grid::BoundedGrid;
+use super::grid_trait::Grid;
+use super::hashed_grid::HashedGrid;
+use crate::fluid_simulation::real::Real;
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GridType {
+    #[default]
+    Bounded,
+    Hashed,
+}
+
+impl GridType {
+    pub fn build<T: Real>(
+        &self,
+        box_dimensions: [usize; 2],
+        particle_count: usize,
+        cell_size: T,
+    ) -> Box<dyn Grid<T>> {
+        match self {
+            GridType::Bounded => Box::new(BoundedGrid::new(box_dimensions, cell_size)),
+            GridType::Hashed => Box::new(HashedGrid::new(particle_count, cell_size)),
+        }
+    }
+}
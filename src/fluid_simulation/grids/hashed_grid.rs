@@ -0,0 +1,56 @@
+use super::grid_trait::{position_to_cell_coord, AdjacentCellKeys, Grid, ADJACENT_CELL_OFFSETS};
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+const X_PRIME: u32 = 73856093;
+const Y_PRIME: u32 = 19349663;
+
+// Unbounded grid whose cells are hashed into a fixed table, so particles can
+// go anywhere. Distinct cells may share a key; neighbor searches filter by
+// distance anyway, they only pay for the extra candidates.
+pub struct HashedGrid<T: Real = f32> {
+    cell_size: T,
+    // A power of two, so the hash is reduced with a mask.
+    table_size: usize,
+}
+
+impl<T: Real> HashedGrid<T> {
+    pub fn new(particle_count: usize, cell_size: T) -> Self {
+        HashedGrid {
+            cell_size,
+            table_size: particle_count.max(1).next_power_of_two(),
+        }
+    }
+
+    fn cell_coord_to_cell_key(&self, coord: Vector2D<i32>) -> usize {
+        let hash = (coord.x as u32).wrapping_mul(X_PRIME) ^ (coord.y as u32).wrapping_mul(Y_PRIME);
+        hash as usize & (self.table_size - 1)
+    }
+}
+
+impl<T: Real> Grid<T> for HashedGrid<T> {
+    fn number_of_cells(&self) -> usize {
+        self.table_size
+    }
+
+    fn cell_size(&self) -> T {
+        self.cell_size
+    }
+
+    fn cell_key(&self, position: Vector2D<T>) -> usize {
+        self.cell_coord_to_cell_key(position_to_cell_coord(position, self.cell_size))
+    }
+
+    fn adjacent_cell_keys(&self, position: Vector2D<T>) -> AdjacentCellKeys {
+        let current_cell_coord = position_to_cell_coord(position, self.cell_size);
+        let mut cell_keys = AdjacentCellKeys::new();
+        for (x, y) in ADJACENT_CELL_OFFSETS {
+            let coord = Vector2D::new(
+                current_cell_coord.x.wrapping_add(x),
+                current_cell_coord.y.wrapping_add(y),
+            );
+            cell_keys.push(self.cell_coord_to_cell_key(coord));
+        }
+        cell_keys
+    }
+}
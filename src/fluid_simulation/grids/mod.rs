@@ -0,0 +1,4 @@
+pub mod bounded_grid;
+pub mod grid_trait;
+pub mod grid_type;
+pub mod hashed_grid;
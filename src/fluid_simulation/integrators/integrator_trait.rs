@@ -0,0 +1,33 @@
+use crate::fluid_simulation::particle_store::ParticleStore;
+use crate::fluid_simulation::real::Real;
+use rayon::prelude::*;
+use vector2d::Vector2D;
+
+// A step advances the particles with predict, using the accelerations of the
+// current state, then evaluates the forces at the new positions and hands the
+// resulting accelerations to correct. Accelerations always include gravity.
+pub trait Integrator<T: Real = f32>: Send + Sync {
+    fn predict(
+        &mut self,
+        particles: &mut ParticleStore<T>,
+        accelerations: &[Vector2D<T>],
+        delta_time: T,
+    );
+
+    fn correct(
+        &mut self,
+        particles: &mut ParticleStore<T>,
+        accelerations: &[Vector2D<T>],
+        delta_time: T,
+    );
+
+    // Called when the particles were reordered between predict and correct;
+    // permutation maps each new particle index to its previous one.
+    fn reorder(&mut self, _permutation: &[usize]) {}
+}
+
+pub fn reorder_by_permutation<T: Copy + Send + Sync>(values: &mut Vec<T>, permutation: &[usize]) {
+    if values.len() == permutation.len() {
+        *values = permutation.par_iter().map(|&index| values[index]).collect();
+    }
+}
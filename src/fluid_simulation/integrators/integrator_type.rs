@@ -0,0 +1,27 @@
+use super::integrator_trait::Integrator;
+use super::leapfrog_integrator::LeapfrogIntegrator;
+use super::predictor_corrector_integrator::PredictorCorrectorIntegrator;
+use super::symplectic_euler_integrator::SymplecticEulerIntegrator;
+use super::velocity_verlet_integrator::VelocityVerletIntegrator;
+use crate::fluid_simulation::real::Real;
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegratorType {
+    SymplecticEuler,
+    Leapfrog,
+    VelocityVerlet,
+    PredictorCorrector,
+}
+
+impl IntegratorType {
+    pub fn build<T: Real>(&self) -> Box<dyn Integrator<T>> {
+        match self {
+            IntegratorType::SymplecticEuler => Box::new(SymplecticEulerIntegrator {}),
+            IntegratorType::Leapfrog => Box::new(LeapfrogIntegrator {}),
+            IntegratorType::VelocityVerlet => Box::new(VelocityVerletIntegrator::new()),
+            IntegratorType::PredictorCorrector => Box::new(PredictorCorrectorIntegrator::new()),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use super::integrator_trait::Integrator;
+use crate::fluid_simulation::particle_store::ParticleStore;
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+// Kick-drift-kick: half a velocity kick with the old accelerations, a full
+// drift, and the second half kick once the new accelerations are known.
+pub struct LeapfrogIntegrator {}
+
+impl<T: Real> Integrator<T> for LeapfrogIntegrator {
+    fn predict(
+        &mut self,
+        particles: &mut ParticleStore<T>,
+        accelerations: &[Vector2D<T>],
+        delta_time: T,
+    ) {
+        particles.par_update(|index, particle| {
+            particle.velocity += accelerations[index] * T::from_f64(0.5) * delta_time;
+            particle.position += particle.velocity * delta_time;
+        });
+    }
+
+    fn correct(
+        &mut self,
+        particles: &mut ParticleStore<T>,
+        accelerations: &[Vector2D<T>],
+        delta_time: T,
+    ) {
+        particles.par_update(|index, particle| {
+            particle.velocity += accelerations[index] * T::from_f64(0.5) * delta_time;
+        });
+    }
+}
@@ -0,0 +1,6 @@
+pub mod integrator_trait;
+pub mod integrator_type;
+pub mod leapfrog_integrator;
+pub mod predictor_corrector_integrator;
+pub mod symplectic_euler_integrator;
+pub mod velocity_verlet_integrator;
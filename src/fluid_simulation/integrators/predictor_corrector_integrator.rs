@@ -0,0 +1,63 @@
+use super::integrator_trait::{reorder_by_permutation, Integrator};
+use crate::fluid_simulation::particle_store::ParticleStore;
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+// Heun's method: an Euler predictor provides the state the forces are
+// evaluated at, and the corrector restarts from the stored state with the
+// trapezoidal average of the old and predicted accelerations and velocities.
+#[derive(Default)]
+pub struct PredictorCorrectorIntegrator<T: Real = f32> {
+    initial_states: Vec<(Vector2D<T>, Vector2D<T>, Vector2D<T>)>,
+}
+
+impl<T: Real> PredictorCorrectorIntegrator<T> {
+    pub fn new() -> Self {
+        PredictorCorrectorIntegrator {
+            initial_states: Vec::new(),
+        }
+    }
+}
+
+impl<T: Real> Integrator<T> for PredictorCorrectorIntegrator<T> {
+    fn predict(
+        &mut self,
+        particles: &mut ParticleStore<T>,
+        accelerations: &[Vector2D<T>],
+        delta_time: T,
+    ) {
+        self.initial_states.clear();
+        self.initial_states.extend(
+            particles
+                .iter()
+                .zip(accelerations.iter())
+                .map(|(particle, &acceleration)| {
+                    (particle.position, particle.velocity, acceleration)
+                }),
+        );
+        particles.par_update(|index, particle| {
+            particle.position += particle.velocity * delta_time;
+            particle.velocity += accelerations[index] * delta_time;
+        });
+    }
+
+    fn correct(
+        &mut self,
+        particles: &mut ParticleStore<T>,
+        accelerations: &[Vector2D<T>],
+        delta_time: T,
+    ) {
+        let initial_states = &self.initial_states;
+        particles.par_update(|index, particle| {
+            let (position, velocity, initial_acceleration) = initial_states[index];
+            particle.velocity = velocity
+                + (initial_acceleration + accelerations[index]) * T::from_f64(0.5) * delta_time;
+            particle.position =
+                position + (velocity + particle.velocity) * T::from_f64(0.5) * delta_time;
+        });
+    }
+
+    fn reorder(&mut self, permutation: &[usize]) {
+        reorder_by_permutation(&mut self.initial_states, permutation);
+    }
+}
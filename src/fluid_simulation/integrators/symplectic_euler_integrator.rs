@@ -0,0 +1,24 @@
+use super::integrator_trait::Integrator;
+use crate::fluid_simulation::particle_store::ParticleStore;
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+// v(n+1) = v(n) + a(n) dt, x(n+1) = x(n) + v(n+1) dt. First order, but it is
+// the scheme the predictive pressure solvers assume.
+pub struct SymplecticEulerIntegrator {}
+
+impl<T: Real> Integrator<T> for SymplecticEulerIntegrator {
+    fn predict(
+        &mut self,
+        particles: &mut ParticleStore<T>,
+        accelerations: &[Vector2D<T>],
+        delta_time: T,
+    ) {
+        particles.par_update(|index, particle| {
+            particle.velocity += accelerations[index] * delta_time;
+            particle.position += particle.velocity * delta_time;
+        });
+    }
+
+    fn correct(&mut self, _: &mut ParticleStore<T>, _: &[Vector2D<T>], _: T) {}
+}
@@ -0,0 +1,53 @@
+use super::integrator_trait::{reorder_by_permutation, Integrator};
+use crate::fluid_simulation::particle_store::ParticleStore;
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+// x(n+1) = x(n) + v(n) dt + a(n) dt^2 / 2, then
+// v(n+1) = v(n) + (a(n) + a(n+1)) dt / 2 once a(n+1) is known.
+#[derive(Default)]
+pub struct VelocityVerletIntegrator<T: Real = f32> {
+    previous_accelerations: Vec<Vector2D<T>>,
+}
+
+impl<T: Real> VelocityVerletIntegrator<T> {
+    pub fn new() -> Self {
+        VelocityVerletIntegrator {
+            previous_accelerations: Vec::new(),
+        }
+    }
+}
+
+impl<T: Real> Integrator<T> for VelocityVerletIntegrator<T> {
+    fn predict(
+        &mut self,
+        particles: &mut ParticleStore<T>,
+        accelerations: &[Vector2D<T>],
+        delta_time: T,
+    ) {
+        particles.par_update(|index, particle| {
+            particle.position += particle.velocity * delta_time
+                + accelerations[index] * T::from_f64(0.5) * delta_time.powi(2);
+        });
+        self.previous_accelerations.clear();
+        self.previous_accelerations.extend_from_slice(accelerations);
+    }
+
+    fn correct(
+        &mut self,
+        particles: &mut ParticleStore<T>,
+        accelerations: &[Vector2D<T>],
+        delta_time: T,
+    ) {
+        let previous_accelerations = &self.previous_accelerations;
+        particles.par_update(|index, particle| {
+            particle.velocity += (accelerations[index] + previous_accelerations[index])
+                * T::from_f64(0.5)
+                * delta_time;
+        });
+    }
+
+    fn reorder(&mut self, permutation: &[usize]) {
+        reorder_by_permutation(&mut self.previous_accelerations, permutation);
+    }
+}
@@ -0,0 +1,70 @@
+use super::kernel_trait::Kernel;
+use crate::fluid_simulation::real::Real;
+
+// Monaghan's M4 cubic spline, supported up to twice the smoothing radius.
+pub struct CubicSplineKernel {}
+
+impl<T: Real> Kernel<T> for CubicSplineKernel {
+    fn support_radius(&self, smoothing_radius: T) -> T {
+        T::from_f64(2.0) * smoothing_radius
+    }
+
+    fn normalization(&self, smoothing_radius: T) -> T {
+        T::from_f64(5.0) / (T::from_f64(14.0) * T::PI() * smoothing_radius.powi(2))
+    }
+
+    fn value(&self, distance: T, smoothing_radius: T) -> T {
+        let two = T::from_f64(2.0);
+        let ratio = distance / smoothing_radius;
+        if ratio >= two {
+            return T::zero();
+        }
+        let normalization = self.normalization(smoothing_radius);
+        if ratio < T::one() {
+            return normalization
+                * ((two - ratio).powi(3) - T::from_f64(4.0) * (T::one() - ratio).powi(3));
+        }
+        normalization * (two - ratio).powi(3)
+    }
+
+    fn gradient(&self, distance: T, smoothing_radius: T) -> T {
+        let ratio = distance / smoothing_radius;
+        self.ratio_derivative(ratio, smoothing_radius) / smoothing_radius
+    }
+
+    fn laplacian(&self, distance: T, smoothing_radius: T) -> T {
+        let two = T::from_f64(2.0);
+        let six = T::from_f64(6.0);
+        let ratio = distance / smoothing_radius;
+        if ratio >= two {
+            return T::zero();
+        }
+        let normalization = self.normalization(smoothing_radius);
+        let second_derivative = if ratio < T::one() {
+            normalization * (six * (two - ratio) - T::from_f64(24.0) * (T::one() - ratio))
+        } else {
+            normalization * six * (two - ratio)
+        };
+        if ratio == T::zero() {
+            return two * second_derivative / smoothing_radius.powi(2);
+        }
+        (second_derivative + self.ratio_derivative(ratio, smoothing_radius) / ratio)
+            / smoothing_radius.powi(2)
+    }
+}
+
+impl CubicSplineKernel {
+    fn ratio_derivative<T: Real>(&self, ratio: T, smoothing_radius: T) -> T {
+        let two = T::from_f64(2.0);
+        if ratio >= two {
+            return T::zero();
+        }
+        let normalization = Kernel::<T>::normalization(self, smoothing_radius);
+        if ratio < T::one() {
+            return normalization
+                * (T::from_f64(12.0) * (T::one() - ratio).powi(2)
+                    - T::from_f64(3.0) * (two - ratio).powi(2));
+        }
+        -T::from_f64(3.0) * normalization * (two - ratio).powi(2)
+    }
+}
@@ -0,0 +1,35 @@
+use crate::fluid_simulation::real::Real;
+
+// Radially symmetric 2D smoothing kernels. `gradient` is the radial derivative
+// dW/dr, so the gradient vector is `gradient * unit_vector` and `laplacian` is
+// d²W/dr² + (dW/dr) / r. Every kernel vanishes beyond `support_radius`, which
+// never exceeds twice the smoothing radius so the cell grid finds all
+// neighbors.
+pub trait Kernel<T: Real = f32>: Send + Sync {
+    fn support_radius(&self, smoothing_radius: T) -> T;
+    fn normalization(&self, smoothing_radius: T) -> T;
+    fn value(&self, distance: T, smoothing_radius: T) -> T;
+    fn gradient(&self, distance: T, smoothing_radius: T) -> T;
+    fn laplacian(&self, distance: T, smoothing_radius: T) -> T;
+
+    // Batched forms for the struct-of-arrays loops, replacing each distance
+    // with the kernel value, gradient or laplacian. One dynamic call per batch
+    // lets every kernel inline its scalar function into a vectorizable loop.
+    fn values(&self, distances: &mut [T], smoothing_radius: T) {
+        for distance in distances.iter_mut() {
+            *distance = self.value(*distance, smoothing_radius);
+        }
+    }
+
+    fn gradients(&self, distances: &mut [T], smoothing_radius: T) {
+        for distance in distances.iter_mut() {
+            *distance = self.gradient(*distance, smoothing_radius);
+        }
+    }
+
+    fn laplacians(&self, distances: &mut [T], smoothing_radius: T) {
+        for distance in distances.iter_mut() {
+            *distance = self.laplacian(*distance, smoothing_radius);
+        }
+    }
+}
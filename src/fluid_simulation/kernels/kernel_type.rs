@@ -0,0 +1,39 @@
+use super::cubic_spline_kernel::CubicSplineKernel;
+use super::kernel_trait::Kernel;
+use super::poly6_kernel::Poly6Kernel;
+use super::quintic_spline_kernel::QuinticSplineKernel;
+use super::sb_kernel::SbKernel;
+use super::spiky_kernel::SpikyKernel;
+use super::viscosity_kernel::ViscosityKernel;
+use super::wendland_c2_kernel::WendlandC2Kernel;
+use super::wendland_c4_kernel::WendlandC4Kernel;
+use crate::fluid_simulation::real::Real;
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KernelType {
+    CubicSpline,
+    Spiky,
+    Poly6,
+    Sb,
+    Viscosity,
+    WendlandC2,
+    WendlandC4,
+    QuinticSpline,
+}
+
+impl KernelType {
+    pub fn build<T: Real>(&self) -> Box<dyn Kernel<T>> {
+        match self {
+            KernelType::CubicSpline => Box::new(CubicSplineKernel {}),
+            KernelType::Spiky => Box::new(SpikyKernel {}),
+            KernelType::Poly6 => Box::new(Poly6Kernel {}),
+            KernelType::Sb => Box::new(SbKernel {}),
+            KernelType::Viscosity => Box::new(ViscosityKernel {}),
+            KernelType::WendlandC2 => Box::new(WendlandC2Kernel {}),
+            KernelType::WendlandC4 => Box::new(WendlandC4Kernel {}),
+            KernelType::QuinticSpline => Box::new(QuinticSplineKernel {}),
+        }
+    }
+}
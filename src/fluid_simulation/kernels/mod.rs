@@ -0,0 +1,10 @@
+pub mod cubic_spline_kernel;
+pub mod kernel_trait;
+pub mod kernel_type;
+pub mod poly6_kernel;
+pub mod quintic_spline_kernel;
+pub mod sb_kernel;
+pub mod spiky_kernel;
+pub mod viscosity_kernel;
+pub mod wendland_c2_kernel;
+pub mod wendland_c4_kernel;
@@ -0,0 +1,42 @@
+use super::kernel_trait::Kernel;
+use crate::fluid_simulation::real::Real;
+
+pub struct Poly6Kernel {}
+
+impl<T: Real> Kernel<T> for Poly6Kernel {
+    fn support_radius(&self, smoothing_radius: T) -> T {
+        smoothing_radius
+    }
+
+    fn normalization(&self, smoothing_radius: T) -> T {
+        T::from_f64(4.0) / (T::PI() * smoothing_radius.powi(8))
+    }
+
+    fn value(&self, distance: T, smoothing_radius: T) -> T {
+        if distance >= smoothing_radius {
+            return T::zero();
+        }
+        self.normalization(smoothing_radius) * (smoothing_radius.powi(2) - distance.powi(2)).powi(3)
+    }
+
+    fn gradient(&self, distance: T, smoothing_radius: T) -> T {
+        if distance >= smoothing_radius {
+            return T::zero();
+        }
+        -T::from_f64(6.0)
+            * self.normalization(smoothing_radius)
+            * distance
+            * (smoothing_radius.powi(2) - distance.powi(2)).powi(2)
+    }
+
+    fn laplacian(&self, distance: T, smoothing_radius: T) -> T {
+        if distance >= smoothing_radius {
+            return T::zero();
+        }
+        let squared_gap = smoothing_radius.powi(2) - distance.powi(2);
+        -T::from_f64(12.0)
+            * self.normalization(smoothing_radius)
+            * squared_gap
+            * (smoothing_radius.powi(2) - T::from_f64(3.0) * distance.powi(2))
+    }
+}
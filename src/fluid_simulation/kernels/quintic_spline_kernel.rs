@@ -0,0 +1,59 @@
+use super::kernel_trait::Kernel;
+use crate::fluid_simulation::real::Real;
+
+// Morris' quintic spline, scaled so that its three lobes fit inside the
+// smoothing radius like the other compact kernels.
+pub struct QuinticSplineKernel {}
+
+impl<T: Real> Kernel<T> for QuinticSplineKernel {
+    fn support_radius(&self, smoothing_radius: T) -> T {
+        smoothing_radius
+    }
+
+    fn normalization(&self, smoothing_radius: T) -> T {
+        T::from_f64(7.0)
+            / (T::from_f64(478.0) * T::PI() * (smoothing_radius / T::from_f64(3.0)).powi(2))
+    }
+
+    fn value(&self, distance: T, smoothing_radius: T) -> T {
+        let ratio = T::from_f64(3.0) * distance / smoothing_radius;
+        self.normalization(smoothing_radius)
+            * (lobe(T::from_f64(3.0) - ratio, 5)
+                - T::from_f64(6.0) * lobe(T::from_f64(2.0) - ratio, 5)
+                + T::from_f64(15.0) * lobe(T::one() - ratio, 5))
+    }
+
+    fn gradient(&self, distance: T, smoothing_radius: T) -> T {
+        let ratio = T::from_f64(3.0) * distance / smoothing_radius;
+        self.ratio_derivative(ratio, smoothing_radius) * T::from_f64(3.0) / smoothing_radius
+    }
+
+    fn laplacian(&self, distance: T, smoothing_radius: T) -> T {
+        let ratio = T::from_f64(3.0) * distance / smoothing_radius;
+        let scale = (T::from_f64(3.0) / smoothing_radius).powi(2);
+        let second_derivative = self.normalization(smoothing_radius)
+            * (T::from_f64(20.0) * lobe(T::from_f64(3.0) - ratio, 3)
+                - T::from_f64(120.0) * lobe(T::from_f64(2.0) - ratio, 3)
+                + T::from_f64(300.0) * lobe(T::one() - ratio, 3));
+        if ratio == T::zero() {
+            return T::from_f64(2.0) * second_derivative * scale;
+        }
+        (second_derivative + self.ratio_derivative(ratio, smoothing_radius) / ratio) * scale
+    }
+}
+
+impl QuinticSplineKernel {
+    fn ratio_derivative<T: Real>(&self, ratio: T, smoothing_radius: T) -> T {
+        Kernel::<T>::normalization(self, smoothing_radius)
+            * (-T::from_f64(5.0) * lobe(T::from_f64(3.0) - ratio, 4)
+                + T::from_f64(30.0) * lobe(T::from_f64(2.0) - ratio, 4)
+                - T::from_f64(75.0) * lobe(T::one() - ratio, 4))
+    }
+}
+
+fn lobe<T: Real>(gap: T, power: i32) -> T {
+    if gap <= T::zero() {
+        return T::zero();
+    }
+    gap.powi(power)
+}
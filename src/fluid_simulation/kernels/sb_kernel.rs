@@ -0,0 +1,38 @@
+use super::kernel_trait::Kernel;
+use crate::fluid_simulation::real::Real;
+
+// Squared falloff kernel, (h - r)^2, with a linear gradient.
+pub struct SbKernel {}
+
+impl<T: Real> Kernel<T> for SbKernel {
+    fn support_radius(&self, smoothing_radius: T) -> T {
+        smoothing_radius
+    }
+
+    fn normalization(&self, smoothing_radius: T) -> T {
+        T::from_f64(6.0) / (T::PI() * smoothing_radius.powi(4))
+    }
+
+    fn value(&self, distance: T, smoothing_radius: T) -> T {
+        if distance >= smoothing_radius {
+            return T::zero();
+        }
+        self.normalization(smoothing_radius) * (smoothing_radius - distance).powi(2)
+    }
+
+    fn gradient(&self, distance: T, smoothing_radius: T) -> T {
+        if distance >= smoothing_radius {
+            return T::zero();
+        }
+        T::from_f64(2.0) * self.normalization(smoothing_radius) * (distance - smoothing_radius)
+    }
+
+    fn laplacian(&self, distance: T, smoothing_radius: T) -> T {
+        if distance >= smoothing_radius || distance == T::zero() {
+            return T::zero();
+        }
+        T::from_f64(2.0)
+            * self.normalization(smoothing_radius)
+            * (T::from_f64(2.0) - smoothing_radius / distance)
+    }
+}
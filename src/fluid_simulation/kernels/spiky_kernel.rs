@@ -0,0 +1,41 @@
+use super::kernel_trait::Kernel;
+use crate::fluid_simulation::real::Real;
+
+pub struct SpikyKernel {}
+
+impl<T: Real> Kernel<T> for SpikyKernel {
+    fn support_radius(&self, smoothing_radius: T) -> T {
+        smoothing_radius
+    }
+
+    fn normalization(&self, smoothing_radius: T) -> T {
+        T::from_f64(10.0) / (T::PI() * smoothing_radius.powi(5))
+    }
+
+    fn value(&self, distance: T, smoothing_radius: T) -> T {
+        if distance >= smoothing_radius {
+            return T::zero();
+        }
+        self.normalization(smoothing_radius) * (smoothing_radius - distance).powi(3)
+    }
+
+    fn gradient(&self, distance: T, smoothing_radius: T) -> T {
+        if distance >= smoothing_radius {
+            return T::zero();
+        }
+        -T::from_f64(3.0)
+            * self.normalization(smoothing_radius)
+            * (smoothing_radius - distance).powi(2)
+    }
+
+    fn laplacian(&self, distance: T, smoothing_radius: T) -> T {
+        // The laplacian is singular at the origin, where it is never needed
+        // because the self contribution carries no relative quantity.
+        if distance >= smoothing_radius || distance == T::zero() {
+            return T::zero();
+        }
+        let gap = smoothing_radius - distance;
+        self.normalization(smoothing_radius)
+            * (T::from_f64(6.0) * gap - T::from_f64(3.0) * gap.powi(2) / distance)
+    }
+}
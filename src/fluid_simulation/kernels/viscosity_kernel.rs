@@ -0,0 +1,44 @@
+use super::kernel_trait::Kernel;
+use crate::fluid_simulation::real::Real;
+
+// Müller et al.'s viscosity kernel. Its laplacian is the positive, linear 2D
+// form the kernel was designed around rather than the derivative of `value`.
+pub struct ViscosityKernel {}
+
+impl<T: Real> Kernel<T> for ViscosityKernel {
+    fn support_radius(&self, smoothing_radius: T) -> T {
+        smoothing_radius
+    }
+
+    fn normalization(&self, smoothing_radius: T) -> T {
+        T::from_f64(10.0) / (T::from_f64(3.0) * T::PI() * smoothing_radius.powi(2))
+    }
+
+    fn value(&self, distance: T, smoothing_radius: T) -> T {
+        if distance >= smoothing_radius || distance == T::zero() {
+            return T::zero();
+        }
+        let ratio = distance / smoothing_radius;
+        let half = T::from_f64(0.5);
+        self.normalization(smoothing_radius)
+            * (-half * ratio.powi(3) + ratio.powi(2) + half / ratio - T::one())
+    }
+
+    fn gradient(&self, distance: T, smoothing_radius: T) -> T {
+        if distance >= smoothing_radius || distance == T::zero() {
+            return T::zero();
+        }
+        let ratio = distance / smoothing_radius;
+        self.normalization(smoothing_radius)
+            * (-T::from_f64(1.5) * ratio.powi(2) + T::from_f64(2.0) * ratio
+                - T::from_f64(0.5) / ratio.powi(2))
+            / smoothing_radius
+    }
+
+    fn laplacian(&self, distance: T, smoothing_radius: T) -> T {
+        if distance >= smoothing_radius {
+            return T::zero();
+        }
+        T::from_f64(40.0) / (T::PI() * smoothing_radius.powi(5)) * (smoothing_radius - distance)
+    }
+}
@@ -0,0 +1,48 @@
+use super::kernel_trait::Kernel;
+use crate::fluid_simulation::real::Real;
+
+pub struct WendlandC2Kernel {}
+
+impl<T: Real> Kernel<T> for WendlandC2Kernel {
+    fn support_radius(&self, smoothing_radius: T) -> T {
+        smoothing_radius
+    }
+
+    fn normalization(&self, smoothing_radius: T) -> T {
+        T::from_f64(7.0) / (T::PI() * smoothing_radius.powi(2))
+    }
+
+    fn value(&self, distance: T, smoothing_radius: T) -> T {
+        let ratio = distance / smoothing_radius;
+        if ratio >= T::one() {
+            return T::zero();
+        }
+        self.normalization(smoothing_radius)
+            * (T::one() - ratio).powi(4)
+            * (T::one() + T::from_f64(4.0) * ratio)
+    }
+
+    fn gradient(&self, distance: T, smoothing_radius: T) -> T {
+        let ratio = distance / smoothing_radius;
+        if ratio >= T::one() {
+            return T::zero();
+        }
+        -T::from_f64(20.0)
+            * self.normalization(smoothing_radius)
+            * ratio
+            * (T::one() - ratio).powi(3)
+            / smoothing_radius
+    }
+
+    fn laplacian(&self, distance: T, smoothing_radius: T) -> T {
+        let ratio = distance / smoothing_radius;
+        if ratio >= T::one() {
+            return T::zero();
+        }
+        -T::from_f64(20.0)
+            * self.normalization(smoothing_radius)
+            * (T::one() - ratio).powi(2)
+            * (T::from_f64(2.0) - T::from_f64(5.0) * ratio)
+            / smoothing_radius.powi(2)
+    }
+}
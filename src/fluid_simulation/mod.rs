@@ -1,10 +1,26 @@
+pub mod boundary_material;
 pub mod cell_manager;
+pub mod checkpoint;
 pub mod config;
+pub mod equation_of_state;
 pub mod external_attractor;
-pub mod fluid_simulation_app;
+pub mod grids;
+pub mod integrators;
+pub mod kernels;
 pub mod obstacle_collision_manager;
 pub mod obstacles;
+pub mod output_manager;
 pub mod particle;
 pub mod particle_dynamics_manager;
+pub mod particle_store;
+pub mod real;
+pub mod recording;
+pub mod replay_player;
+pub mod rigid_body;
+pub mod rigid_body_manager;
+pub mod scene;
+pub mod simulation;
 pub mod smoothed_interaction;
-pub mod smothing_kernels;
+pub mod solvers;
+pub mod time_step_manager;
+pub mod vtk_writer;
@@ -1,40 +1,59 @@
+use crate::fluid_simulation::boundary_material::WallMaterials;
 use crate::fluid_simulation::particle::Particle;
-pub struct ObstacleCollisionManager {
-    pub box_width: f32,
-    pub box_height: f32,
-    particle_radius: f32,
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+pub struct ObstacleCollisionManager<T: Real = f32> {
+    pub box_width: T,
+    pub box_height: T,
+    has_walls: bool,
+    particle_radius: T,
+    wall_materials: WallMaterials,
 }
 
-impl ObstacleCollisionManager {
-    pub fn new(box_dimensions: [usize; 2]) -> Self {
+impl<T: Real> ObstacleCollisionManager<T> {
+    pub fn new(box_dimensions: [usize; 2], has_walls: bool, wall_materials: WallMaterials) -> Self {
         ObstacleCollisionManager {
-            box_width: box_dimensions[0] as f32,
-            box_height: box_dimensions[1] as f32,
-            particle_radius: 3.0,
+            box_width: T::from_f64(box_dimensions[0] as f64),
+            box_height: T::from_f64(box_dimensions[1] as f64),
+            has_walls,
+            particle_radius: T::from_f64(3.0),
+            wall_materials,
         }
     }
 
-    pub fn apply_boundary_conditions(&self, particle: &mut Particle) {
-        self.apply_box_boundary(particle);
+    pub fn apply_boundary_conditions(&self, particle: &mut Particle<T>) {
+        if self.has_walls {
+            self.apply_box_boundary(particle);
+        }
     }
 
-    fn apply_box_boundary(&self, particle: &mut Particle) {
-        let distance_from_wall = self.particle_radius * 3.0;
+    fn apply_box_boundary(&self, particle: &mut Particle<T>) {
+        let distance_from_wall = self.particle_radius * T::from_f64(3.0);
+        let materials = &self.wall_materials;
         if particle.position.x < distance_from_wall {
             particle.position.x = distance_from_wall;
-            particle.velocity.x = -particle.velocity.x;
+            particle.velocity = materials
+                .left
+                .collide(particle.velocity, Vector2D::new(T::one(), T::zero()));
         }
         if particle.position.x > self.box_width - distance_from_wall {
             particle.position.x = self.box_width - distance_from_wall;
-            particle.velocity.x = -particle.velocity.x;
+            particle.velocity = materials
+                .right
+                .collide(particle.velocity, Vector2D::new(-T::one(), T::zero()));
         }
         if particle.position.y < distance_from_wall {
             particle.position.y = distance_from_wall;
-            particle.velocity.y = -particle.velocity.y;
+            particle.velocity = materials
+                .top
+                .collide(particle.velocity, Vector2D::new(T::zero(), T::one()));
         }
         if particle.position.y > self.box_height - distance_from_wall {
             particle.position.y = self.box_height - distance_from_wall;
-            particle.velocity.y = -particle.velocity.y;
+            particle.velocity = materials
+                .bottom
+                .collide(particle.velocity, Vector2D::new(T::zero(), -T::one()));
         }
     }
 }
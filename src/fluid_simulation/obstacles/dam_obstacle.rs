@@ -1,34 +1,69 @@
-use super::obstacle_trait::Obstacle;
+use super::obstacle_trait::{Obstacle, Outline};
+use crate::fluid_simulation::boundary_material::BoundaryMaterial;
 use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
 
-pub struct DamObstacle {
-    x_position: f32,
+// Holds the fluid left of x_position over the whole height until it breaks.
+pub struct DamObstacle<T: Real = f32> {
+    x_position: T,
+    height: T,
     is_dam_active: bool,
-    box_dimensions: [usize; 2],
+    material: BoundaryMaterial,
 }
 
-impl Obstacle for DamObstacle {
-    fn apply_obstruction_boundary(&self, particle: &mut Particle) {
-        if !self.is_dam_active {
-            return;
-        }
+impl<T: Real> Obstacle<T> for DamObstacle<T> {
+    fn apply_obstruction_boundary(&self, particle: &mut Particle<T>) {
         if particle.position.x > self.x_position {
             particle.position.x = self.x_position;
-            particle.velocity.x = -particle.velocity.x;
+            particle.velocity = self
+                .material
+                .collide(particle.velocity, Vector2D::new(-T::one(), T::zero()));
         }
     }
+
+    fn outline(&self) -> Outline<T> {
+        Outline::Segments(vec![[
+            Vector2D::new(self.x_position, T::zero()),
+            Vector2D::new(self.x_position, self.height),
+        ]])
+    }
+
+    fn material(&self) -> BoundaryMaterial {
+        self.material
+    }
+
+    fn set_material(&mut self, material: BoundaryMaterial) {
+        self.material = material;
+    }
+
+    fn is_active(&self) -> bool {
+        self.is_dam_active
+    }
+
+    fn set_active(&mut self, is_active: bool) {
+        self.is_dam_active = is_active;
+    }
+
+    fn is_breakable(&self) -> bool {
+        true
+    }
+
+    fn distance_and_normal(&self, point: Vector2D<T>) -> Option<(T, Vector2D<T>)> {
+        Some((
+            self.x_position - point.x,
+            Vector2D::new(-T::one(), T::zero()),
+        ))
+    }
 }
 
-impl DamObstacle {
-    pub fn new(box_dimensions: [usize; 2]) -> Self {
+impl<T: Real> DamObstacle<T> {
+    pub fn new(x_position: T, height: T) -> Self {
         DamObstacle {
-            x_position: 600.0,
-            box_dimensions,
+            x_position,
+            height,
             is_dam_active: true,
+            material: BoundaryMaterial::default(),
         }
     }
-
-    pub fn break_dam(&mut self) {
-        self.is_dam_active = false;
-    }
 }
@@ -0,0 +1,70 @@
+use super::motions::motion_trait::Motion;
+use super::motions::pose::{Pose, PoseVelocity};
+use super::moving_shape::MovingShape;
+use super::obstacle_trait::{Obstacle, Outline};
+use super::shapes::shape_trait::Shape;
+use crate::fluid_simulation::boundary_material::BoundaryMaterial;
+use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+// Shape obstacle that follows a scripted motion, passing the velocity of its
+// surface on to the particles it hits.
+pub struct KinematicObstacle<T: Real = f32> {
+    moving_shape: MovingShape<T>,
+    motion: Box<dyn Motion<T> + Send + Sync>,
+}
+
+impl<T: Real> Obstacle<T> for KinematicObstacle<T> {
+    fn apply_obstruction_boundary(&self, particle: &mut Particle<T>) {
+        self.moving_shape.apply_obstruction_boundary(particle);
+    }
+
+    fn outline(&self) -> Outline<T> {
+        self.moving_shape.outline()
+    }
+
+    fn material(&self) -> BoundaryMaterial {
+        self.moving_shape.material()
+    }
+
+    fn set_material(&mut self, material: BoundaryMaterial) {
+        self.moving_shape.set_material(material);
+    }
+
+    fn update(&mut self, time: T) {
+        self.moving_shape
+            .set_pose(self.motion.pose(time), self.motion.velocity(time));
+    }
+
+    fn distance_and_normal(&self, point: Vector2D<T>) -> Option<(T, Vector2D<T>)> {
+        Some(self.moving_shape.distance_and_normal(point))
+    }
+
+    fn surface_velocity(&self, point: Vector2D<T>) -> Vector2D<T> {
+        self.moving_shape.surface_velocity(point)
+    }
+}
+
+impl<T: Real> KinematicObstacle<T> {
+    pub fn new(
+        shape: Box<dyn Shape<T> + Send + Sync>,
+        pivot: Vector2D<T>,
+        motion: Box<dyn Motion<T> + Send + Sync>,
+    ) -> Self {
+        let mut moving_shape = MovingShape::new(shape, pivot);
+        moving_shape.set_pose(motion.pose(T::zero()), motion.velocity(T::zero()));
+        KinematicObstacle {
+            moving_shape,
+            motion,
+        }
+    }
+
+    pub fn pose(&self) -> Pose<T> {
+        self.moving_shape.pose()
+    }
+
+    pub fn velocity(&self) -> PoseVelocity<T> {
+        self.moving_shape.velocity()
+    }
+}
@@ -1,3 +1,7 @@
 pub mod dam_obstacle;
+pub mod kinematic_obstacle;
+pub mod motions;
+pub mod moving_shape;
 pub mod obstacle_trait;
-pub mod rectangle_obstacle;
+pub mod shape_obstacle;
+pub mod shapes;
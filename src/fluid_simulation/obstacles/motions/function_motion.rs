@@ -0,0 +1,23 @@
+use super::motion_trait::Motion;
+use super::pose::Pose;
+use crate::fluid_simulation::real::Real;
+
+// Trajectory given by a function of the simulated time, for motions built in
+// code. Its velocity comes from differences of the pose.
+pub struct FunctionMotion<T: Real = f32> {
+    function: Box<dyn Fn(T) -> Pose<T> + Send + Sync>,
+}
+
+impl<T: Real> Motion<T> for FunctionMotion<T> {
+    fn pose(&self, time: T) -> Pose<T> {
+        (self.function)(time)
+    }
+}
+
+impl<T: Real> FunctionMotion<T> {
+    pub fn new(function: impl Fn(T) -> Pose<T> + Send + Sync + 'static) -> Self {
+        FunctionMotion {
+            function: Box::new(function),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use super::motion_trait::Motion;
+use super::pose::{Pose, PoseVelocity};
+use crate::fluid_simulation::real::Real;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Keyframe<T: Real = f32> {
+    pub time: T,
+    pub pose: Pose<T>,
+}
+
+// Moves linearly between poses at given times. Before the first keyframe the
+// obstacle rests at its pose, and so it does after the last one unless the
+// motion loops; a loop should end on the pose it starts with.
+pub struct KeyframeMotion<T: Real = f32> {
+    keyframes: Vec<Keyframe<T>>,
+    is_looping: bool,
+}
+
+impl<T: Real> Motion<T> for KeyframeMotion<T> {
+    fn pose(&self, time: T) -> Pose<T> {
+        match self.segment(time) {
+            Segment::Before => self.keyframes[0].pose,
+            Segment::After => self.keyframes[self.keyframes.len() - 1].pose,
+            Segment::Between(index, fraction) => {
+                let start = self.keyframes[index].pose;
+                let end = self.keyframes[index + 1].pose;
+                Pose::new(
+                    start.translation + (end.translation - start.translation) * fraction,
+                    start.rotation + (end.rotation - start.rotation) * fraction,
+                )
+            }
+        }
+    }
+
+    fn velocity(&self, time: T) -> PoseVelocity<T> {
+        match self.segment(time) {
+            Segment::Between(index, _) => {
+                let start = self.keyframes[index];
+                let end = self.keyframes[index + 1];
+                let scale = T::one() / (end.time - start.time);
+                PoseVelocity::new(
+                    (end.pose.translation - start.pose.translation) * scale,
+                    (end.pose.rotation - start.pose.rotation) * scale,
+                )
+            }
+            _ => PoseVelocity::zero(),
+        }
+    }
+}
+
+enum Segment<T> {
+    Before,
+    After,
+    // Index of the keyframe the segment starts at and the fraction of it
+    // that has passed.
+    Between(usize, T),
+}
+
+impl<T: Real> KeyframeMotion<T> {
+    // Keyframes must be given in order of strictly increasing time.
+    pub fn new(keyframes: Vec<Keyframe<T>>, is_looping: bool) -> Self {
+        assert!(!keyframes.is_empty(), "a keyframe motion needs keyframes");
+        KeyframeMotion {
+            keyframes,
+            is_looping,
+        }
+    }
+
+    fn segment(&self, time: T) -> Segment<T> {
+        let first_time = self.keyframes[0].time;
+        let last_time = self.keyframes[self.keyframes.len() - 1].time;
+        let duration = last_time - first_time;
+        let time = if self.is_looping && duration > T::zero() {
+            let elapsed = time - first_time;
+            first_time + elapsed - (elapsed / duration).floor() * duration
+        } else {
+            time
+        };
+        if time < first_time {
+            return Segment::Before;
+        }
+        if time >= last_time {
+            return Segment::After;
+        }
+        let index = self
+            .keyframes
+            .windows(2)
+            .position(|pair| time < pair[1].time)
+            .unwrap_or(0);
+        let start = self.keyframes[index].time;
+        let end = self.keyframes[index + 1].time;
+        Segment::Between(index, (time - start) / (end - start))
+    }
+}
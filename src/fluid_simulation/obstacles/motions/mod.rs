@@ -0,0 +1,6 @@
+pub mod function_motion;
+pub mod keyframe_motion;
+pub mod motion_trait;
+pub mod oscillating_motion;
+pub mod pose;
+pub mod rotating_motion;
@@ -0,0 +1,23 @@
+use super::pose::{Pose, PoseVelocity};
+use crate::fluid_simulation::real::Real;
+
+// Half width of the central differences that approximate the velocity, in
+// seconds.
+const VELOCITY_STEP: f64 = 1e-3;
+
+// Scripted trajectory of a moving obstacle over the simulated time.
+pub trait Motion<T: Real = f32> {
+    fn pose(&self, time: T) -> Pose<T>;
+
+    // Central differences of the pose unless the motion knows better.
+    fn velocity(&self, time: T) -> PoseVelocity<T> {
+        let step = T::from_f64(VELOCITY_STEP);
+        let before = self.pose(time - step);
+        let after = self.pose(time + step);
+        let scale = T::one() / (step + step);
+        PoseVelocity::new(
+            (after.translation - before.translation) * scale,
+            (after.rotation - before.rotation) * scale,
+        )
+    }
+}
@@ -0,0 +1,38 @@
+use super::motion_trait::Motion;
+use super::pose::{Pose, PoseVelocity};
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+// Sinusoidal back and forth motion, like a piston, a wave maker or a swinging
+// paddle. Translation and rotation oscillate in phase and start at their
+// rest pose when the phase is zero.
+pub struct OscillatingMotion<T: Real = f32> {
+    amplitude: Vector2D<T>,
+    angular_amplitude: T,
+    angular_frequency: T,
+    phase: T,
+}
+
+impl<T: Real> Motion<T> for OscillatingMotion<T> {
+    fn pose(&self, time: T) -> Pose<T> {
+        let sin = (self.angular_frequency * time + self.phase).sin();
+        Pose::new(self.amplitude * sin, self.angular_amplitude * sin)
+    }
+
+    fn velocity(&self, time: T) -> PoseVelocity<T> {
+        let rate = self.angular_frequency * (self.angular_frequency * time + self.phase).cos();
+        PoseVelocity::new(self.amplitude * rate, self.angular_amplitude * rate)
+    }
+}
+
+impl<T: Real> OscillatingMotion<T> {
+    // The frequency is in cycles per second and the phase in radians.
+    pub fn new(amplitude: Vector2D<T>, angular_amplitude: T, frequency: T, phase: T) -> Self {
+        OscillatingMotion {
+            amplitude,
+            angular_amplitude,
+            angular_frequency: (T::PI() + T::PI()) * frequency,
+            phase,
+        }
+    }
+}
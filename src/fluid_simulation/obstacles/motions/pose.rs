@@ -0,0 +1,56 @@
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+// Placement of a moving obstacle relative to where the scene puts it: a
+// rotation in radians about its pivot, followed by a translation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pose<T: Real = f32> {
+    pub translation: Vector2D<T>,
+    pub rotation: T,
+}
+
+impl<T: Real> Pose<T> {
+    pub fn new(translation: Vector2D<T>, rotation: T) -> Self {
+        Pose {
+            translation,
+            rotation,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Pose::new(Vector2D::new(T::zero(), T::zero()), T::zero())
+    }
+
+    pub fn rotate(&self, vector: Vector2D<T>) -> Vector2D<T> {
+        let (sin, cos) = self.rotation.sin_cos();
+        Vector2D::new(
+            vector.x * cos - vector.y * sin,
+            vector.x * sin + vector.y * cos,
+        )
+    }
+
+    pub fn unrotate(&self, vector: Vector2D<T>) -> Vector2D<T> {
+        let (sin, cos) = self.rotation.sin_cos();
+        Vector2D::new(
+            vector.x * cos + vector.y * sin,
+            -vector.x * sin + vector.y * cos,
+        )
+    }
+}
+
+// Rate of change of a pose, in simulation units and radians per second.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PoseVelocity<T: Real = f32> {
+    pub linear: Vector2D<T>,
+    pub angular: T,
+}
+
+impl<T: Real> PoseVelocity<T> {
+    pub fn new(linear: Vector2D<T>, angular: T) -> Self {
+        PoseVelocity { linear, angular }
+    }
+
+    pub fn zero() -> Self {
+        PoseVelocity::new(Vector2D::new(T::zero(), T::zero()), T::zero())
+    }
+}
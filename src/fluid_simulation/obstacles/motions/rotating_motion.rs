@@ -0,0 +1,28 @@
+use super::motion_trait::Motion;
+use super::pose::{Pose, PoseVelocity};
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+// Turns about the pivot at a constant rate, like a stirrer.
+pub struct RotatingMotion<T: Real = f32> {
+    angular_velocity: T,
+}
+
+impl<T: Real> Motion<T> for RotatingMotion<T> {
+    fn pose(&self, time: T) -> Pose<T> {
+        Pose::new(
+            Vector2D::new(T::zero(), T::zero()),
+            self.angular_velocity * time,
+        )
+    }
+
+    fn velocity(&self, _time: T) -> PoseVelocity<T> {
+        PoseVelocity::new(Vector2D::new(T::zero(), T::zero()), self.angular_velocity)
+    }
+}
+
+impl<T: Real> RotatingMotion<T> {
+    pub fn new(angular_velocity: T) -> Self {
+        RotatingMotion { angular_velocity }
+    }
+}
@@ -0,0 +1,122 @@
+use super::motions::pose::{Pose, PoseVelocity};
+use super::obstacle_trait::{Obstacle, Outline};
+use super::shape_obstacle::ShapeObstacle;
+use super::shapes::shape_trait::Shape;
+use crate::fluid_simulation::boundary_material::BoundaryMaterial;
+use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+// Shape obstacle at a pose that changes over time. The shape is given where
+// it rests, and collisions are resolved there: particles are moved into the
+// rest frame with their velocity relative to the surface, collide with the
+// resting shape and are moved back. Particles so pick up the velocity of the
+// surface they hit.
+pub struct MovingShape<T: Real = f32> {
+    obstacle: ShapeObstacle<T>,
+    // Point the shape rotates about, in the rest frame.
+    pivot: Vector2D<T>,
+    pose: Pose<T>,
+    velocity: PoseVelocity<T>,
+}
+
+impl<T: Real> MovingShape<T> {
+    pub fn new(shape: Box<dyn Shape<T> + Send + Sync>, pivot: Vector2D<T>) -> Self {
+        MovingShape {
+            obstacle: ShapeObstacle::new(shape),
+            pivot,
+            pose: Pose::identity(),
+            velocity: PoseVelocity::zero(),
+        }
+    }
+
+    // Returns whether the particle was inside the shape.
+    pub fn apply_obstruction_boundary(&self, particle: &mut Particle<T>) -> bool {
+        let rest_position = self.rest_point(particle.position);
+        if self.obstacle.shape().distance(rest_position) >= T::zero() {
+            return false;
+        }
+        let surface_velocity = self.surface_velocity(particle.position);
+        particle.position = rest_position;
+        particle.velocity = self.pose.unrotate(particle.velocity - surface_velocity);
+        self.obstacle.apply_obstruction_boundary(particle);
+        particle.position = self.placed_point(particle.position);
+        particle.velocity = self.pose.rotate(particle.velocity) + surface_velocity;
+        true
+    }
+
+    pub fn outline(&self) -> Outline<T> {
+        match self.obstacle.outline() {
+            Outline::Polygon(points) => Outline::Polygon(
+                points
+                    .into_iter()
+                    .map(|point| self.placed_point(point))
+                    .collect(),
+            ),
+            Outline::Segments(segments) => Outline::Segments(
+                segments
+                    .into_iter()
+                    .map(|[start, end]| [self.placed_point(start), self.placed_point(end)])
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn shape(&self) -> &(dyn Shape<T> + Send + Sync) {
+        self.obstacle.shape()
+    }
+
+    pub fn material(&self) -> BoundaryMaterial {
+        self.obstacle.material()
+    }
+
+    pub fn set_material(&mut self, material: BoundaryMaterial) {
+        self.obstacle.set_material(material);
+    }
+
+    pub fn pivot(&self) -> Vector2D<T> {
+        self.pivot
+    }
+
+    pub fn pose(&self) -> Pose<T> {
+        self.pose
+    }
+
+    pub fn velocity(&self) -> PoseVelocity<T> {
+        self.velocity
+    }
+
+    pub fn set_pose(&mut self, pose: Pose<T>, velocity: PoseVelocity<T>) {
+        self.pose = pose;
+        self.velocity = velocity;
+    }
+
+    // Where the pivot is now.
+    pub fn center(&self) -> Vector2D<T> {
+        self.pivot + self.pose.translation
+    }
+
+    // Signed distance and outward normal at a point in simulation coordinates.
+    pub fn distance_and_normal(&self, point: Vector2D<T>) -> (T, Vector2D<T>) {
+        let rest_position = self.rest_point(point);
+        (
+            self.obstacle.shape().distance(rest_position),
+            self.pose
+                .rotate(self.obstacle.shape().normal(rest_position)),
+        )
+    }
+
+    // Velocity of the shape at a point in simulation coordinates.
+    pub fn surface_velocity(&self, point: Vector2D<T>) -> Vector2D<T> {
+        let offset = point - self.center();
+        self.velocity.linear + Vector2D::new(-offset.y, offset.x) * self.velocity.angular
+    }
+
+    pub fn rest_point(&self, point: Vector2D<T>) -> Vector2D<T> {
+        self.pivot + self.pose.unrotate(point - self.center())
+    }
+
+    pub fn placed_point(&self, point: Vector2D<T>) -> Vector2D<T> {
+        self.center() + self.pose.rotate(point - self.pivot)
+    }
+}
@@ -1,5 +1,53 @@
+use crate::fluid_simulation::boundary_material::BoundaryMaterial;
 use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
 
-pub trait Obstacle {
-    fn apply_obstruction_boundary(&self, particle: &mut Particle) {}
+pub trait Obstacle<T: Real = f32> {
+    fn apply_obstruction_boundary(&self, particle: &mut Particle<T>);
+
+    // Shape for the renderer in simulation coordinates.
+    fn outline(&self) -> Outline<T>;
+
+    // Restitution and friction applied to particles hitting the obstacle.
+    fn material(&self) -> BoundaryMaterial;
+
+    fn set_material(&mut self, material: BoundaryMaterial);
+
+    // Moves the obstacle to where it is at the given simulated time. Static
+    // obstacles ignore it.
+    fn update(&mut self, _time: T) {}
+
+    // Inactive obstacles are neither applied nor drawn. Obstacles that cannot
+    // be switched off ignore set_active.
+    fn is_active(&self) -> bool {
+        true
+    }
+
+    fn set_active(&mut self, _is_active: bool) {}
+
+    // Breakable obstacles are switched off when the dam breaks, and whether
+    // they are active is saved in checkpoints.
+    fn is_breakable(&self) -> bool {
+        false
+    }
+
+    // Signed distance to the surface and outward normal at a point, which
+    // rigid bodies collide with. Obstacles without one let bodies through.
+    fn distance_and_normal(&self, _point: Vector2D<T>) -> Option<(T, Vector2D<T>)> {
+        None
+    }
+
+    // Velocity of the surface at a point in simulation coordinates.
+    fn surface_velocity(&self, _point: Vector2D<T>) -> Vector2D<T> {
+        Vector2D::new(T::zero(), T::zero())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outline<T: Real = f32> {
+    // Convex polygon, drawn filled.
+    Polygon(Vec<Vector2D<T>>),
+    // Line segments along the surface.
+    Segments(Vec<[Vector2D<T>; 2]>),
 }
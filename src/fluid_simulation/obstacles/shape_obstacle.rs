@@ -0,0 +1,146 @@
+use super::obstacle_trait::{Obstacle, Outline};
+use super::shapes::shape_trait::Shape;
+use crate::fluid_simulation::boundary_material::BoundaryMaterial;
+use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+// Projections per collision. Distances inside unions and differences are only
+// bounds, so one projection can stop short of the surface.
+const MAX_PROJECTIONS: usize = 4;
+// Cell size of the grid the outline is traced on, in simulation units.
+const OUTLINE_CELL_SIZE: f64 = 2.0;
+const MAX_OUTLINE_CELLS: f64 = 1000.0;
+
+// Obstacle of any shape given by a signed distance function. Particles that
+// end up inside are projected back to the surface along the distance
+// gradient, and the velocity component into the surface bounces off it as
+// the material says.
+pub struct ShapeObstacle<T: Real = f32> {
+    shape: Box<dyn Shape<T> + Send + Sync>,
+    outline: Vec<[Vector2D<T>; 2]>,
+    material: BoundaryMaterial,
+}
+
+impl<T: Real> Obstacle<T> for ShapeObstacle<T> {
+    fn apply_obstruction_boundary(&self, particle: &mut Particle<T>) {
+        let mut distance = self.shape.distance(particle.position);
+        if distance >= T::zero() {
+            return;
+        }
+        let mut normal = self.shape.normal(particle.position);
+        particle.position -= normal * distance;
+        for _ in 1..MAX_PROJECTIONS {
+            distance = self.shape.distance(particle.position);
+            if distance >= T::zero() {
+                break;
+            }
+            normal = self.shape.normal(particle.position);
+            particle.position -= normal * distance;
+        }
+        particle.velocity = self.material.collide(particle.velocity, normal);
+    }
+
+    fn outline(&self) -> Outline<T> {
+        Outline::Segments(self.outline.clone())
+    }
+
+    fn material(&self) -> BoundaryMaterial {
+        self.material
+    }
+
+    fn set_material(&mut self, material: BoundaryMaterial) {
+        self.material = material;
+    }
+
+    fn distance_and_normal(&self, point: Vector2D<T>) -> Option<(T, Vector2D<T>)> {
+        Some((self.shape.distance(point), self.shape.normal(point)))
+    }
+}
+
+impl<T: Real> ShapeObstacle<T> {
+    pub fn new(shape: Box<dyn Shape<T> + Send + Sync>) -> Self {
+        let outline = trace_outline(shape.as_ref());
+        ShapeObstacle {
+            shape,
+            outline,
+            material: BoundaryMaterial::default(),
+        }
+    }
+
+    pub fn shape(&self) -> &(dyn Shape<T> + Send + Sync) {
+        self.shape.as_ref()
+    }
+}
+
+// Marching squares over the bounds of the shape: every grid cell whose
+// corners lie on both sides of the surface contributes the segments between
+// the interpolated crossings of its edges.
+fn trace_outline<T: Real>(shape: &(dyn Shape<T> + Send + Sync)) -> Vec<[Vector2D<T>; 2]> {
+    let [top_left, bottom_right] = shape.bounds();
+    let width = (bottom_right.x - top_left.x).as_f64();
+    let height = (bottom_right.y - top_left.y).as_f64();
+    let cell_size = OUTLINE_CELL_SIZE.max(width.max(height) / MAX_OUTLINE_CELLS);
+    // One cell of margin on every side, so the surface lies inside the grid.
+    let columns = (width / cell_size).ceil() as usize + 2;
+    let rows = (height / cell_size).ceil() as usize + 2;
+    let origin = top_left - Vector2D::new(T::from_f64(cell_size), T::from_f64(cell_size));
+    let corner = |column: usize, row: usize| {
+        origin
+            + Vector2D::new(
+                T::from_f64(column as f64 * cell_size),
+                T::from_f64(row as f64 * cell_size),
+            )
+    };
+    let distances: Vec<T> = (0..=rows)
+        .flat_map(|row| (0..=columns).map(move |column| (column, row)))
+        .map(|(column, row)| shape.distance(corner(column, row)))
+        .collect();
+    let distance = |column: usize, row: usize| distances[row * (columns + 1) + column];
+
+    let mut segments = Vec::new();
+    for row in 0..rows {
+        for column in 0..columns {
+            // Corners clockwise from the top left; edge i runs from corner i
+            // to corner i + 1.
+            let corners = [
+                (column, row),
+                (column + 1, row),
+                (column + 1, row + 1),
+                (column, row + 1),
+            ];
+            let corner_distances = corners.map(|(column, row)| distance(column, row));
+            let mut crossings = Vec::with_capacity(4);
+            for edge in 0..4 {
+                let (start, end) = (edge, (edge + 1) % 4);
+                let (start_distance, end_distance) =
+                    (corner_distances[start], corner_distances[end]);
+                if (start_distance < T::zero()) != (end_distance < T::zero()) {
+                    let start_point = corner(corners[start].0, corners[start].1);
+                    let end_point = corner(corners[end].0, corners[end].1);
+                    let fraction = start_distance / (start_distance - end_distance);
+                    crossings.push(start_point + (end_point - start_point) * fraction);
+                }
+            }
+            match crossings.len() {
+                2 => segments.push([crossings[0], crossings[1]]),
+                4 => {
+                    // Saddle: the center decides which corners are connected.
+                    let center = corner(column, row)
+                        + Vector2D::new(T::from_f64(cell_size), T::from_f64(cell_size))
+                            * T::from_f64(0.5);
+                    let is_center_inside = shape.distance(center) < T::zero();
+                    if is_center_inside == (corner_distances[0] < T::zero()) {
+                        segments.push([crossings[0], crossings[1]]);
+                        segments.push([crossings[2], crossings[3]]);
+                    } else {
+                        segments.push([crossings[3], crossings[0]]);
+                        segments.push([crossings[1], crossings[2]]);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    segments
+}
@@ -0,0 +1,45 @@
+use super::shape_trait::Shape;
+use crate::fluid_simulation::real::{Real, RealVector};
+use vector2d::Vector2D;
+
+// All points within radius of the segment from start to end.
+pub struct CapsuleShape<T: Real = f32> {
+    start: Vector2D<T>,
+    end: Vector2D<T>,
+    radius: T,
+}
+
+impl<T: Real> Shape<T> for CapsuleShape<T> {
+    fn distance(&self, point: Vector2D<T>) -> T {
+        let axis = self.end - self.start;
+        let offset = point - self.start;
+        let axis_length_squared = axis.length_squared();
+        let fraction = if axis_length_squared == T::zero() {
+            T::zero()
+        } else {
+            (Vector2D::dot(offset, axis) / axis_length_squared)
+                .max(T::zero())
+                .min(T::one())
+        };
+        (offset - axis * fraction).length() - self.radius
+    }
+
+    fn bounds(&self) -> [Vector2D<T>; 2] {
+        [
+            Vector2D::new(
+                self.start.x.min(self.end.x) - self.radius,
+                self.start.y.min(self.end.y) - self.radius,
+            ),
+            Vector2D::new(
+                self.start.x.max(self.end.x) + self.radius,
+                self.start.y.max(self.end.y) + self.radius,
+            ),
+        ]
+    }
+}
+
+impl<T: Real> CapsuleShape<T> {
+    pub fn new(start: Vector2D<T>, end: Vector2D<T>, radius: T) -> Self {
+        CapsuleShape { start, end, radius }
+    }
+}
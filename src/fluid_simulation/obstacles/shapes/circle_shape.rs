@@ -0,0 +1,33 @@
+use super::shape_trait::Shape;
+use crate::fluid_simulation::real::{Real, RealVector};
+use vector2d::Vector2D;
+
+pub struct CircleShape<T: Real = f32> {
+    center: Vector2D<T>,
+    radius: T,
+}
+
+impl<T: Real> Shape<T> for CircleShape<T> {
+    fn distance(&self, point: Vector2D<T>) -> T {
+        (point - self.center).length() - self.radius
+    }
+
+    fn bounds(&self) -> [Vector2D<T>; 2] {
+        let extent = Vector2D::new(self.radius, self.radius);
+        [self.center - extent, self.center + extent]
+    }
+
+    fn normal(&self, point: Vector2D<T>) -> Vector2D<T> {
+        let offset = point - self.center;
+        if offset.length_squared() == T::zero() {
+            return Vector2D::new(T::zero(), -T::one());
+        }
+        offset.normalise()
+    }
+}
+
+impl<T: Real> CircleShape<T> {
+    pub fn new(center: Vector2D<T>, radius: T) -> Self {
+        CircleShape { center, radius }
+    }
+}
@@ -0,0 +1,30 @@
+use super::shape_trait::Shape;
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+// The points of shape outside of subtracted.
+pub struct DifferenceShape<T: Real = f32> {
+    shape: Box<dyn Shape<T> + Send + Sync>,
+    subtracted: Box<dyn Shape<T> + Send + Sync>,
+}
+
+impl<T: Real> Shape<T> for DifferenceShape<T> {
+    fn distance(&self, point: Vector2D<T>) -> T {
+        self.shape
+            .distance(point)
+            .max(-self.subtracted.distance(point))
+    }
+
+    fn bounds(&self) -> [Vector2D<T>; 2] {
+        self.shape.bounds()
+    }
+}
+
+impl<T: Real> DifferenceShape<T> {
+    pub fn new(
+        shape: Box<dyn Shape<T> + Send + Sync>,
+        subtracted: Box<dyn Shape<T> + Send + Sync>,
+    ) -> Self {
+        DifferenceShape { shape, subtracted }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod capsule_shape;
+pub mod circle_shape;
+pub mod difference_shape;
+pub mod polygon_shape;
+pub mod rounded_box_shape;
+pub mod shape_trait;
+pub mod union_shape;
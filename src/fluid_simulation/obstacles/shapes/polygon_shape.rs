@@ -0,0 +1,56 @@
+use super::shape_trait::Shape;
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+// Simple polygon, convex or concave, with the vertices in either winding
+// order.
+pub struct PolygonShape<T: Real = f32> {
+    vertices: Vec<Vector2D<T>>,
+}
+
+impl<T: Real> Shape<T> for PolygonShape<T> {
+    // Distance to the nearest edge, negative if an odd number of edges
+    // cross the horizontal ray from the point.
+    fn distance(&self, point: Vector2D<T>) -> T {
+        let mut distance_squared = T::infinity();
+        let mut is_inside = false;
+        let mut previous = self.vertices[self.vertices.len() - 1];
+        for &vertex in self.vertices.iter() {
+            let edge = previous - vertex;
+            let offset = point - vertex;
+            let fraction = (Vector2D::dot(offset, edge) / edge.length_squared())
+                .max(T::zero())
+                .min(T::one());
+            distance_squared = distance_squared.min((offset - edge * fraction).length_squared());
+            let crosses_ray = (vertex.y <= point.y) != (previous.y <= point.y);
+            let winding = edge.x * offset.y - edge.y * offset.x;
+            if crosses_ray && (winding > T::zero()) == (edge.y > T::zero()) {
+                is_inside = !is_inside;
+            }
+            previous = vertex;
+        }
+        if is_inside {
+            -distance_squared.sqrt()
+        } else {
+            distance_squared.sqrt()
+        }
+    }
+
+    fn bounds(&self) -> [Vector2D<T>; 2] {
+        let mut top_left = self.vertices[0];
+        let mut bottom_right = self.vertices[0];
+        for vertex in self.vertices.iter() {
+            top_left = Vector2D::new(top_left.x.min(vertex.x), top_left.y.min(vertex.y));
+            bottom_right =
+                Vector2D::new(bottom_right.x.max(vertex.x), bottom_right.y.max(vertex.y));
+        }
+        [top_left, bottom_right]
+    }
+}
+
+impl<T: Real> PolygonShape<T> {
+    // Needs at least three vertices, no two consecutive ones equal.
+    pub fn new(vertices: Vec<Vector2D<T>>) -> Self {
+        PolygonShape { vertices }
+    }
+}
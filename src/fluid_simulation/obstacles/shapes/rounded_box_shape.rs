@@ -0,0 +1,45 @@
+use super::shape_trait::Shape;
+use crate::fluid_simulation::real::{Real, RealVector};
+use vector2d::Vector2D;
+
+// Axis-aligned box whose corners are rounded with corner_radius. A radius of
+// zero gives sharp corners.
+pub struct RoundedBoxShape<T: Real = f32> {
+    center: Vector2D<T>,
+    half_dimensions: Vector2D<T>,
+    corner_radius: T,
+}
+
+impl<T: Real> Shape<T> for RoundedBoxShape<T> {
+    fn distance(&self, point: Vector2D<T>) -> T {
+        let offset = point - self.center;
+        let inner_extent = Vector2D::new(
+            self.half_dimensions.x - self.corner_radius,
+            self.half_dimensions.y - self.corner_radius,
+        );
+        let excess = Vector2D::new(
+            offset.x.abs() - inner_extent.x,
+            offset.y.abs() - inner_extent.y,
+        );
+        let outside = Vector2D::new(excess.x.max(T::zero()), excess.y.max(T::zero()));
+        outside.length() + excess.x.max(excess.y).min(T::zero()) - self.corner_radius
+    }
+
+    fn bounds(&self) -> [Vector2D<T>; 2] {
+        [
+            self.center - self.half_dimensions,
+            self.center + self.half_dimensions,
+        ]
+    }
+}
+
+impl<T: Real> RoundedBoxShape<T> {
+    pub fn new(top_left: Vector2D<T>, bottom_right: Vector2D<T>, corner_radius: T) -> Self {
+        let half = T::from_f64(0.5);
+        RoundedBoxShape {
+            center: (top_left + bottom_right) * half,
+            half_dimensions: (bottom_right - top_left) * half,
+            corner_radius,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use crate::fluid_simulation::real::{Real, RealVector};
+use vector2d::Vector2D;
+
+// Step of the central differences that approximate the distance gradient, in
+// simulation units.
+const GRADIENT_STEP: f64 = 0.01;
+
+// A region described by its signed distance function.
+pub trait Shape<T: Real = f32> {
+    // Distance to the surface, negative inside. Unions and differences give a
+    // bound on the distance inside the shape rather than the exact value.
+    fn distance(&self, point: Vector2D<T>) -> T;
+
+    // Top left and bottom right corners of a box holding the shape.
+    fn bounds(&self) -> [Vector2D<T>; 2];
+
+    // Outward unit normal, the normalised distance gradient. Where the
+    // gradient vanishes, e.g. at the center of a circle, it points up.
+    fn normal(&self, point: Vector2D<T>) -> Vector2D<T> {
+        let step = T::from_f64(GRADIENT_STEP);
+        let step_x = Vector2D::new(step, T::zero());
+        let step_y = Vector2D::new(T::zero(), step);
+        let gradient = Vector2D::new(
+            self.distance(point + step_x) - self.distance(point - step_x),
+            self.distance(point + step_y) - self.distance(point - step_y),
+        );
+        if gradient.length_squared() == T::zero() {
+            return Vector2D::new(T::zero(), -T::one());
+        }
+        gradient.normalise()
+    }
+}
@@ -0,0 +1,43 @@
+use super::shape_trait::Shape;
+use crate::fluid_simulation::real::Real;
+use vector2d::Vector2D;
+
+pub struct UnionShape<T: Real = f32> {
+    shapes: Vec<Box<dyn Shape<T> + Send + Sync>>,
+}
+
+impl<T: Real> Shape<T> for UnionShape<T> {
+    fn distance(&self, point: Vector2D<T>) -> T {
+        self.shapes
+            .iter()
+            .map(|shape| shape.distance(point))
+            .fold(T::infinity(), T::min)
+    }
+
+    fn bounds(&self) -> [Vector2D<T>; 2] {
+        let mut bounds = self.shapes.iter().map(|shape| shape.bounds());
+        let first = bounds.next().unwrap();
+        bounds.fold(
+            first,
+            |[top_left, bottom_right], [other_top_left, other_bottom_right]| {
+                [
+                    Vector2D::new(
+                        top_left.x.min(other_top_left.x),
+                        top_left.y.min(other_top_left.y),
+                    ),
+                    Vector2D::new(
+                        bottom_right.x.max(other_bottom_right.x),
+                        bottom_right.y.max(other_bottom_right.y),
+                    ),
+                ]
+            },
+        )
+    }
+}
+
+impl<T: Real> UnionShape<T> {
+    // Needs at least one shape.
+    pub fn new(shapes: Vec<Box<dyn Shape<T> + Send + Sync>>) -> Self {
+        UnionShape { shapes }
+    }
+}
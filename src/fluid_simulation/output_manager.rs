@@ -0,0 +1,83 @@
+use crate::fluid_simulation::real::Real;
+use crate::fluid_simulation::recording::{Recorder, RecordingError};
+use crate::fluid_simulation::scene::Scene;
+use crate::fluid_simulation::simulation::Simulation;
+use crate::fluid_simulation::vtk_writer::VtkWriter;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum OutputError {
+    Vtk(io::Error),
+    Recording(RecordingError),
+}
+
+impl fmt::Display for OutputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputError::Vtk(error) => write!(f, "could not write particle output: {}", error),
+            OutputError::Recording(error) => write!(f, "could not write recording: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for OutputError {}
+
+// The particle output and recording a scene asks for. An output that fails is
+// switched off, so the other one keeps going.
+pub struct OutputManager {
+    vtk_writer: Option<VtkWriter>,
+    recorder: Option<Recorder>,
+}
+
+impl OutputManager {
+    pub fn new<T: Real>(scene: &Scene, simulation: &Simulation<T>) -> Result<Self, OutputError> {
+        let recorder = match &scene.recording {
+            Some(recording) => Some(
+                Recorder::create(
+                    &recording.path,
+                    recording.csv_path.as_deref(),
+                    simulation,
+                    scene.box_dimensions(),
+                    recording.interval,
+                )
+                .map_err(OutputError::Recording)?,
+            ),
+            None => None,
+        };
+        Ok(OutputManager {
+            vtk_writer: scene.output.as_ref().map(|output| output.build()),
+            recorder,
+        })
+    }
+
+    // Step is the number of updates so far, starting with 0 for the initial
+    // state.
+    pub fn write_step<T: Real>(
+        &mut self,
+        simulation: &Simulation<T>,
+        step: usize,
+    ) -> Result<(), OutputError> {
+        let mut result = Ok(());
+        if let Some(writer) = self.vtk_writer.as_mut() {
+            if let Err(error) = writer.write_step(simulation, step) {
+                self.vtk_writer = None;
+                result = Err(OutputError::Vtk(error));
+            }
+        }
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(error) = recorder.record_step(simulation, step) {
+                self.recorder = None;
+                result = result.and(Err(OutputError::Recording(error)));
+            }
+        }
+        result
+    }
+
+    pub fn flush(&mut self) -> Result<(), OutputError> {
+        match self.recorder.as_mut() {
+            Some(recorder) => recorder.flush().map_err(OutputError::Recording),
+            None => Ok(()),
+        }
+    }
+}
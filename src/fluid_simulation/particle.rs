@@ -1,32 +1,33 @@
+use crate::fluid_simulation::real::{Real, RealVector};
 use vector2d::Vector2D;
 
 #[derive(Clone)]
-pub struct Particle {
+pub struct Particle<T: Real = f32> {
     pub id: usize,
     pub cell_key: usize,
-    pub position: Vector2D<f32>,
-    pub velocity: Vector2D<f32>,
-    pub mass: f32,
+    pub position: Vector2D<T>,
+    pub velocity: Vector2D<T>,
+    pub mass: T,
 }
 
-impl Particle {
-    pub fn new(id: usize, position: Vector2D<f32>) -> Self {
+impl<T: Real> Particle<T> {
+    pub fn new(id: usize, position: Vector2D<T>) -> Self {
         // Generate random position and velocity
-        let velocity = Vector2D::new(0.0, 0.0);
+        let velocity = Vector2D::new(T::zero(), T::zero());
         Particle {
             id,
             cell_key: 0,
             position,
             velocity,
-            mass: 0.0008,
+            mass: T::from_f64(0.0008),
         }
     }
 
-    pub fn get_predicted_position(&self) -> Vector2D<f32> {
-        self.position + self.velocity * 1.0 / 120.0
+    pub fn get_predicted_position(&self, delta_time: T) -> Vector2D<T> {
+        self.position + self.velocity * delta_time
     }
 
-    pub fn speed(&self) -> f32 {
+    pub fn speed(&self) -> T {
         self.velocity.length()
     }
 }
@@ -1,16 +1,24 @@
-use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::integrators::integrator_trait::Integrator;
+use crate::fluid_simulation::particle_store::ParticleStore;
+use crate::fluid_simulation::real::Real;
 use vector2d::Vector2D;
 
-pub struct ParticleDynamicsManager {
+pub struct ParticleDynamicsManager<T: Real = f32> {
+    gravity: Vector2D<T>,
     is_gravity_on: bool,
-    delta_time: f32,
+    integrator: Box<dyn Integrator<T>>,
 }
 
-impl ParticleDynamicsManager {
-    pub fn new(is_gravity_on: bool, delta_time: f32) -> Self {
+impl<T: Real> ParticleDynamicsManager<T> {
+    pub fn new(
+        gravity: Vector2D<T>,
+        is_gravity_on: bool,
+        integrator: Box<dyn Integrator<T>>,
+    ) -> Self {
         ParticleDynamicsManager {
+            gravity,
             is_gravity_on,
-            delta_time,
+            integrator,
         }
     }
 
@@ -18,23 +26,43 @@ impl ParticleDynamicsManager {
         self.is_gravity_on = !self.is_gravity_on;
     }
 
-    pub fn update_velocity(
-        &self,
-        particle: &mut Particle,
-        acceleration: Vector2D<f32>,
-        previous_acceleration: Vector2D<f32>,
+    pub fn is_gravity_on(&self) -> bool {
+        self.is_gravity_on
+    }
+
+    pub fn set_gravity_on(&mut self, is_gravity_on: bool) {
+        self.is_gravity_on = is_gravity_on;
+    }
+
+    pub fn predict(
+        &mut self,
+        particles: &mut ParticleStore<T>,
+        accelerations: &[Vector2D<T>],
+        delta_time: T,
+    ) {
+        self.integrator
+            .predict(particles, accelerations, delta_time);
+    }
+
+    pub fn correct(
+        &mut self,
+        particles: &mut ParticleStore<T>,
+        accelerations: &[Vector2D<T>],
+        delta_time: T,
     ) {
-        let gravity: Vector2D<f32> =
-            Vector2D::new(0.0, if self.is_gravity_on { 980.0 } else { 0.0 });
-        let acceleration = gravity + acceleration;
-        particle.velocity += (acceleration + previous_acceleration) * self.delta_time * 0.5;
-    }
-
-    pub fn update_position(&self, particle: &mut Particle, acceleration: Vector2D<f32>) {
-        let gravity: Vector2D<f32> =
-            Vector2D::new(0.0, if self.is_gravity_on { 980.0 } else { 0.0 });
-        let acceleration = gravity + acceleration;
-        particle.position +=
-            particle.velocity * self.delta_time + acceleration * 0.5 * self.delta_time.powi(2);
+        self.integrator
+            .correct(particles, accelerations, delta_time);
+    }
+
+    pub fn reorder(&mut self, permutation: &[usize]) {
+        self.integrator.reorder(permutation);
+    }
+
+    pub fn get_gravity(&self) -> Vector2D<T> {
+        if self.is_gravity_on {
+            self.gravity
+        } else {
+            Vector2D::new(T::zero(), T::zero())
+        }
     }
 }
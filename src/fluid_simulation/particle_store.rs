@@ -0,0 +1,175 @@
+use crate::fluid_simulation::integrators::integrator_trait::reorder_by_permutation;
+use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::real::Real;
+use rayon::prelude::*;
+use vector2d::Vector2D;
+
+// Particle state as a struct of arrays, the storage of the simulation. The
+// density and force loops read the contiguous arrays they need instead of
+// whole particles. Per-particle code, like boundaries and obstacles, works on
+// Particle values gathered from the arrays and scattered back.
+#[derive(Clone)]
+pub struct ParticleStore<T: Real = f32> {
+    pub ids: Vec<usize>,
+    pub cell_keys: Vec<usize>,
+    pub position_x: Vec<T>,
+    pub position_y: Vec<T>,
+    pub velocity_x: Vec<T>,
+    pub velocity_y: Vec<T>,
+    pub masses: Vec<T>,
+}
+
+impl<T: Real> ParticleStore<T> {
+    pub fn new(particle_count: usize) -> Self {
+        ParticleStore {
+            ids: (0..particle_count).collect(),
+            cell_keys: vec![0; particle_count],
+            position_x: vec![T::zero(); particle_count],
+            position_y: vec![T::zero(); particle_count],
+            velocity_x: vec![T::zero(); particle_count],
+            velocity_y: vec![T::zero(); particle_count],
+            masses: vec![T::zero(); particle_count],
+        }
+    }
+
+    pub fn from_particles(particles: &[Particle<T>]) -> Self {
+        let mut store = ParticleStore::new(particles.len());
+        store.update(particles);
+        store
+    }
+
+    pub fn len(&self) -> usize {
+        self.masses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.masses.is_empty()
+    }
+
+    // Replaces the state with that of the particles.
+    pub fn update(&mut self, particles: &[Particle<T>]) {
+        let particle_count = particles.len();
+        self.ids.resize(particle_count, 0);
+        self.cell_keys.resize(particle_count, 0);
+        self.position_x.resize(particle_count, T::zero());
+        self.position_y.resize(particle_count, T::zero());
+        self.velocity_x.resize(particle_count, T::zero());
+        self.velocity_y.resize(particle_count, T::zero());
+        self.masses.resize(particle_count, T::zero());
+        self.position_x
+            .par_iter_mut()
+            .zip(self.position_y.par_iter_mut())
+            .zip(self.ids.par_iter_mut())
+            .zip(self.cell_keys.par_iter_mut())
+            .zip(particles.par_iter())
+            .for_each(|((((x, y), id), cell_key), particle)| {
+                *id = particle.id;
+                *cell_key = particle.cell_key;
+                *x = particle.position.x;
+                *y = particle.position.y;
+            });
+        self.velocity_x
+            .par_iter_mut()
+            .zip(self.velocity_y.par_iter_mut())
+            .zip(self.masses.par_iter_mut())
+            .zip(particles.par_iter())
+            .for_each(|(((x, y), mass), particle)| {
+                *x = particle.velocity.x;
+                *y = particle.velocity.y;
+                *mass = particle.mass;
+            });
+    }
+
+    pub fn get(&self, index: usize) -> Particle<T> {
+        Particle {
+            id: self.ids[index],
+            cell_key: self.cell_keys[index],
+            position: self.position(index),
+            velocity: self.velocity(index),
+            mass: self.masses[index],
+        }
+    }
+
+    pub fn set(&mut self, index: usize, particle: &Particle<T>) {
+        self.ids[index] = particle.id;
+        self.cell_keys[index] = particle.cell_key;
+        self.position_x[index] = particle.position.x;
+        self.position_y[index] = particle.position.y;
+        self.velocity_x[index] = particle.velocity.x;
+        self.velocity_y[index] = particle.velocity.y;
+        self.masses[index] = particle.mass;
+    }
+
+    pub fn position(&self, index: usize) -> Vector2D<T> {
+        Vector2D::new(self.position_x[index], self.position_y[index])
+    }
+
+    pub fn velocity(&self, index: usize) -> Vector2D<T> {
+        Vector2D::new(self.velocity_x[index], self.velocity_y[index])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Particle<T>> + '_ {
+        (0..self.len()).map(|index| self.get(index))
+    }
+
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = Particle<T>> + '_ {
+        (0..self.len()).into_par_iter().map(|index| self.get(index))
+    }
+
+    pub fn to_particles(&self) -> Vec<Particle<T>> {
+        self.par_iter().collect()
+    }
+
+    // Calls apply in parallel with the index and a copy of every particle,
+    // then writes its position and velocity back.
+    pub fn par_update(&mut self, apply: impl Fn(usize, &mut Particle<T>) + Sync) {
+        let mut outputs = vec![(); self.len()];
+        self.par_update_with(&mut outputs, |index, particle, _| apply(index, particle));
+    }
+
+    // par_update that also hands apply the output slot of the particle.
+    pub fn par_update_with<U: Send>(
+        &mut self,
+        outputs: &mut [U],
+        apply: impl Fn(usize, &mut Particle<T>, &mut U) + Sync,
+    ) {
+        let ids = &self.ids;
+        let cell_keys = &self.cell_keys;
+        let masses = &self.masses;
+        self.position_x
+            .par_iter_mut()
+            .zip(self.position_y.par_iter_mut())
+            .zip(self.velocity_x.par_iter_mut())
+            .zip(self.velocity_y.par_iter_mut())
+            .zip(outputs.par_iter_mut())
+            .enumerate()
+            .for_each(
+                |(index, ((((position_x, position_y), velocity_x), velocity_y), output))| {
+                    let mut particle = Particle {
+                        id: ids[index],
+                        cell_key: cell_keys[index],
+                        position: Vector2D::new(*position_x, *position_y),
+                        velocity: Vector2D::new(*velocity_x, *velocity_y),
+                        mass: masses[index],
+                    };
+                    apply(index, &mut particle, output);
+                    *position_x = particle.position.x;
+                    *position_y = particle.position.y;
+                    *velocity_x = particle.velocity.x;
+                    *velocity_y = particle.velocity.y;
+                },
+            );
+    }
+
+    // Moves every array into the new order; permutation maps each new index
+    // to the previous one.
+    pub fn reorder(&mut self, permutation: &[usize]) {
+        reorder_by_permutation(&mut self.ids, permutation);
+        reorder_by_permutation(&mut self.cell_keys, permutation);
+        reorder_by_permutation(&mut self.position_x, permutation);
+        reorder_by_permutation(&mut self.position_y, permutation);
+        reorder_by_permutation(&mut self.velocity_x, permutation);
+        reorder_by_permutation(&mut self.velocity_y, permutation);
+        reorder_by_permutation(&mut self.masses, permutation);
+    }
+}
@@ -0,0 +1,84 @@
+use num_traits::{Float, FloatConst};
+use std::fmt::Debug;
+use std::iter::Sum;
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+use vector2d::Vector2D;
+
+// Floating-point type the simulation is computed in: f32 for interactive
+// runs, f64 for validation. Scene values are f32 and converted on
+// construction; statistics are reported as f32.
+pub trait Real:
+    Float
+    + FloatConst
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+    + Default
+    + Debug
+    + Send
+    + Sync
+    + 'static
+{
+    fn from_f64(value: f64) -> Self;
+    fn as_f64(self) -> f64;
+
+    fn from_f32(value: f32) -> Self {
+        Self::from_f64(value as f64)
+    }
+
+    fn as_f32(self) -> f32 {
+        self.as_f64() as f32
+    }
+}
+
+impl Real for f32 {
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Real for f64 {
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn as_f64(self) -> f64 {
+        self
+    }
+}
+
+// vector2d only implements length and normalise for f32 and f64 vectors;
+// this makes them available to code generic over Real.
+pub trait RealVector<T> {
+    fn length(self) -> T;
+    fn normalise(self) -> Self;
+}
+
+impl<T: Real> RealVector<T> for Vector2D<T> {
+    fn length(self) -> T {
+        self.length_squared().sqrt()
+    }
+
+    fn normalise(self) -> Self {
+        let length = RealVector::length(self);
+        if length == T::zero() {
+            self
+        } else {
+            self / length
+        }
+    }
+}
+
+pub fn vector_from_f32<T: Real>(vector: Vector2D<f32>) -> Vector2D<T> {
+    Vector2D::new(T::from_f32(vector.x), T::from_f32(vector.y))
+}
+
+pub fn vector_as_f32<T: Real>(vector: Vector2D<T>) -> Vector2D<f32> {
+    Vector2D::new(vector.x.as_f32(), vector.y.as_f32())
+}
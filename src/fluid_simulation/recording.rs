@@ -0,0 +1,370 @@
+//! Recordings of a run for replay without the physics. Every interval-th
+//! step the positions, velocities and densities of all particles are
+//! appended as one frame, in particle id order so a particle keeps its index
+//! across frames.
+//!
+//! All values are little-endian. The header is the magic bytes, the format
+//! version as a u32, then the particle count, domain width and height,
+//! updates per second and interval as u64. Every frame is the step as a u64,
+//! the time as an f64 and x, y, vx, vy and density of each particle as f32.
+//! Frames have a fixed size, so they can be read in any order, and a frame
+//! cut short by an interrupted run is ignored.
+
+use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::real::Real;
+use crate::fluid_simulation::simulation::Simulation;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use vector2d::Vector2D;
+
+pub const RECORDING_VERSION: u32 = 1;
+const MAGIC: &[u8; 8] = b"SPHRECRD";
+const HEADER_SIZE: u64 = 8 + 4 + 5 * 8;
+const FRAME_HEADER_SIZE: u64 = 8 + 8;
+const PARTICLE_SIZE: u64 = 5 * 4;
+
+#[derive(Debug)]
+pub enum RecordingError {
+    Io(io::Error),
+    NotARecording,
+    UnsupportedVersion(u32),
+    ParticleCountChanged { expected: usize, found: usize },
+    FrameOutOfRange { index: usize, frame_count: usize },
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordingError::Io(error) => write!(f, "could not access recording: {}", error),
+            RecordingError::NotARecording => write!(f, "not a recording file"),
+            RecordingError::UnsupportedVersion(version) => write!(
+                f,
+                "recording version {} is not supported, expected version {}",
+                version, RECORDING_VERSION
+            ),
+            RecordingError::ParticleCountChanged { expected, found } => write!(
+                f,
+                "recording holds {} particles, the simulation has {}",
+                expected, found
+            ),
+            RecordingError::FrameOutOfRange { index, frame_count } => write!(
+                f,
+                "frame {} is out of range, the recording has {} frames",
+                index, frame_count
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
+
+impl From<io::Error> for RecordingError {
+    fn from(error: io::Error) -> Self {
+        RecordingError::Io(error)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Frame {
+    pub step: usize,
+    pub time: f64,
+    pub positions: Vec<Vector2D<f32>>,
+    pub velocities: Vec<Vector2D<f32>>,
+    pub densities: Vec<f32>,
+}
+
+impl Frame {
+    // Particles for the renderer, with the index as id.
+    pub fn particles(&self) -> Vec<Particle> {
+        self.positions
+            .iter()
+            .zip(self.velocities.iter())
+            .enumerate()
+            .map(|(id, (&position, &velocity))| Particle {
+                velocity,
+                ..Particle::new(id, position)
+            })
+            .collect()
+    }
+
+    fn capture<T: Real>(&mut self, simulation: &Simulation<T>, step: usize) {
+        let particle_count = simulation.particles.len();
+        self.step = step;
+        self.time = simulation.time().as_f64();
+        self.positions
+            .resize(particle_count, Vector2D::new(0.0, 0.0));
+        self.velocities
+            .resize(particle_count, Vector2D::new(0.0, 0.0));
+        self.densities.resize(particle_count, 0.0);
+        for (particle, &density) in simulation
+            .particles
+            .iter()
+            .zip(simulation.densities().iter())
+        {
+            self.positions[particle.id] =
+                Vector2D::new(particle.position.x.as_f32(), particle.position.y.as_f32());
+            self.velocities[particle.id] =
+                Vector2D::new(particle.velocity.x.as_f32(), particle.velocity.y.as_f32());
+            self.densities[particle.id] = density.as_f32();
+        }
+    }
+}
+
+// Appends a frame every interval steps, and optionally the same frames as
+// CSV rows.
+pub struct Recorder {
+    writer: BufWriter<File>,
+    csv_writer: Option<BufWriter<File>>,
+    particle_count: usize,
+    interval: usize,
+    frame: Frame,
+    bytes: Vec<u8>,
+}
+
+impl Recorder {
+    pub fn create<T: Real>(
+        path: impl AsRef<Path>,
+        csv_path: Option<&Path>,
+        simulation: &Simulation<T>,
+        box_dimensions: [usize; 2],
+        interval: usize,
+    ) -> Result<Self, RecordingError> {
+        let interval = interval.max(1);
+        let particle_count = simulation.particles.len();
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&RECORDING_VERSION.to_le_bytes())?;
+        for value in [
+            particle_count,
+            box_dimensions[0],
+            box_dimensions[1],
+            simulation.ups,
+            interval,
+        ] {
+            writer.write_all(&(value as u64).to_le_bytes())?;
+        }
+        let csv_writer = match csv_path {
+            Some(csv_path) => {
+                let mut csv_writer = BufWriter::new(File::create(csv_path)?);
+                write_csv_header(&mut csv_writer)?;
+                Some(csv_writer)
+            }
+            None => None,
+        };
+        Ok(Recorder {
+            writer,
+            csv_writer,
+            particle_count,
+            interval,
+            frame: Frame::default(),
+            bytes: Vec::new(),
+        })
+    }
+
+    // Records the simulation if step, the number of updates so far, is a
+    // multiple of the interval, and returns whether it did.
+    pub fn record_step<T: Real>(
+        &mut self,
+        simulation: &Simulation<T>,
+        step: usize,
+    ) -> Result<bool, RecordingError> {
+        if !step.is_multiple_of(self.interval) {
+            return Ok(false);
+        }
+        if simulation.particles.len() != self.particle_count {
+            return Err(RecordingError::ParticleCountChanged {
+                expected: self.particle_count,
+                found: simulation.particles.len(),
+            });
+        }
+        self.frame.capture(simulation, step);
+        self.bytes.clear();
+        encode_frame(&self.frame, &mut self.bytes);
+        self.writer.write_all(&self.bytes)?;
+        if let Some(csv_writer) = self.csv_writer.as_mut() {
+            write_csv_rows(&self.frame, csv_writer)?;
+        }
+        Ok(true)
+    }
+
+    pub fn flush(&mut self) -> Result<(), RecordingError> {
+        self.writer.flush()?;
+        if let Some(csv_writer) = self.csv_writer.as_mut() {
+            csv_writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Recording<R: Read + Seek = BufReader<File>> {
+    reader: R,
+    particle_count: usize,
+    box_dimensions: [usize; 2],
+    updates_per_second: usize,
+    interval: usize,
+    frame_size: u64,
+    frame_count: usize,
+    bytes: Vec<u8>,
+}
+
+impl Recording {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        Recording::from_reader(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> Recording<R> {
+    pub fn from_reader(mut reader: R) -> Result<Self, RecordingError> {
+        let mut header = [0; HEADER_SIZE as usize];
+        reader.read_exact(&mut header).map_err(|error| {
+            if error.kind() == io::ErrorKind::UnexpectedEof {
+                RecordingError::NotARecording
+            } else {
+                RecordingError::Io(error)
+            }
+        })?;
+        if &header[..8] != MAGIC {
+            return Err(RecordingError::NotARecording);
+        }
+        let version = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        if version != RECORDING_VERSION {
+            return Err(RecordingError::UnsupportedVersion(version));
+        }
+        let mut values = header[12..]
+            .chunks_exact(8)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()) as usize);
+        let mut next_value = || values.next().unwrap();
+        let particle_count = next_value();
+        let box_dimensions = [next_value(), next_value()];
+        let updates_per_second = next_value();
+        let interval = next_value();
+        let length = reader.seek(SeekFrom::End(0))?;
+        let frame_size =
+            FRAME_HEADER_SIZE.saturating_add((particle_count as u64).saturating_mul(PARTICLE_SIZE));
+        Ok(Recording {
+            reader,
+            particle_count,
+            box_dimensions,
+            updates_per_second,
+            interval: interval.max(1),
+            frame_size,
+            frame_count: ((length - HEADER_SIZE) / frame_size) as usize,
+            bytes: Vec::new(),
+        })
+    }
+
+    pub fn particle_count(&self) -> usize {
+        self.particle_count
+    }
+
+    pub fn box_dimensions(&self) -> [usize; 2] {
+        self.box_dimensions
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    // Recorded frames per second of simulated time.
+    pub fn frame_rate(&self) -> f64 {
+        self.updates_per_second as f64 / self.interval as f64
+    }
+
+    pub fn read_frame(&mut self, index: usize) -> Result<Frame, RecordingError> {
+        let mut frame = Frame::default();
+        self.read_frame_into(index, &mut frame)?;
+        Ok(frame)
+    }
+
+    pub fn read_frame_into(
+        &mut self,
+        index: usize,
+        frame: &mut Frame,
+    ) -> Result<(), RecordingError> {
+        if index >= self.frame_count {
+            return Err(RecordingError::FrameOutOfRange {
+                index,
+                frame_count: self.frame_count,
+            });
+        }
+        self.reader.seek(SeekFrom::Start(
+            HEADER_SIZE + index as u64 * self.frame_size,
+        ))?;
+        self.bytes.resize(self.frame_size as usize, 0);
+        self.reader.read_exact(&mut self.bytes)?;
+        decode_frame(&self.bytes, self.particle_count, frame);
+        Ok(())
+    }
+
+    pub fn export_csv(&mut self, writer: &mut impl Write) -> Result<(), RecordingError> {
+        write_csv_header(writer)?;
+        let mut frame = Frame::default();
+        for index in 0..self.frame_count {
+            self.read_frame_into(index, &mut frame)?;
+            write_csv_rows(&frame, writer)?;
+        }
+        Ok(())
+    }
+}
+
+fn encode_frame(frame: &Frame, bytes: &mut Vec<u8>) {
+    bytes.extend_from_slice(&(frame.step as u64).to_le_bytes());
+    bytes.extend_from_slice(&frame.time.to_le_bytes());
+    for ((position, velocity), density) in frame
+        .positions
+        .iter()
+        .zip(frame.velocities.iter())
+        .zip(frame.densities.iter())
+    {
+        for value in [position.x, position.y, velocity.x, velocity.y, *density] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+fn decode_frame(bytes: &[u8], particle_count: usize, frame: &mut Frame) {
+    frame.step = u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize;
+    frame.time = f64::from_le_bytes(bytes[8..16].try_into().unwrap());
+    frame.positions.clear();
+    frame.velocities.clear();
+    frame.densities.clear();
+    for particle_bytes in bytes[16..]
+        .chunks_exact(PARTICLE_SIZE as usize)
+        .take(particle_count)
+    {
+        let mut values = particle_bytes
+            .chunks_exact(4)
+            .map(|value_bytes| f32::from_le_bytes(value_bytes.try_into().unwrap()));
+        let mut next_value = || values.next().unwrap();
+        frame
+            .positions
+            .push(Vector2D::new(next_value(), next_value()));
+        frame
+            .velocities
+            .push(Vector2D::new(next_value(), next_value()));
+        frame.densities.push(next_value());
+    }
+}
+
+fn write_csv_header(writer: &mut impl Write) -> io::Result<()> {
+    writeln!(writer, "step,time,id,x,y,vx,vy,density")
+}
+
+fn write_csv_rows(frame: &Frame, writer: &mut impl Write) -> io::Result<()> {
+    for (id, ((position, velocity), density)) in frame
+        .positions
+        .iter()
+        .zip(frame.velocities.iter())
+        .zip(frame.densities.iter())
+        .enumerate()
+    {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{},{}",
+            frame.step, frame.time, id, position.x, position.y, velocity.x, velocity.y, density
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,79 @@
+const MIN_SPEED: f64 = 1.0 / 16.0;
+const MAX_SPEED: f64 = 16.0;
+
+// Playback position in a recording. At speed 1 frames advance at the rate
+// they were recorded at in simulated time. Playback pauses at the last frame,
+// and seeking works while paused.
+pub struct ReplayPlayer {
+    frame_count: usize,
+    frame_rate: f64,
+    position: f64,
+    speed: f64,
+    is_paused: bool,
+}
+
+impl ReplayPlayer {
+    pub fn new(frame_count: usize, frame_rate: f64) -> Self {
+        ReplayPlayer {
+            frame_count,
+            frame_rate,
+            position: 0.0,
+            speed: 1.0,
+            is_paused: false,
+        }
+    }
+
+    pub fn advance(&mut self, elapsed_seconds: f64) {
+        if self.is_paused || self.frame_count == 0 {
+            return;
+        }
+        self.position += elapsed_seconds * self.frame_rate * self.speed;
+        let last_frame = (self.frame_count - 1) as f64;
+        if self.position >= last_frame {
+            self.position = last_frame;
+            self.is_paused = true;
+        }
+    }
+
+    pub fn current_frame(&self) -> usize {
+        self.position as usize
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    pub fn toggle_pause(&mut self) {
+        // Playing from the last frame starts over.
+        if self.is_paused && self.current_frame() + 1 >= self.frame_count {
+            self.position = 0.0;
+        }
+        self.is_paused = !self.is_paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_paused
+    }
+
+    // Moves by a number of frames, clamped to the recording.
+    pub fn seek(&mut self, frames: isize) {
+        let frame = self.current_frame() as isize + frames;
+        self.seek_to(frame.max(0) as usize);
+    }
+
+    pub fn seek_to(&mut self, frame: usize) {
+        self.position = frame.min(self.frame_count.saturating_sub(1)) as f64;
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed * 2.0).min(MAX_SPEED);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = (self.speed / 2.0).max(MIN_SPEED);
+    }
+}
@@ -0,0 +1,447 @@
+use crate::fluid_simulation::boundary_material::{BoundaryMaterial, WallMaterials};
+use crate::fluid_simulation::obstacles::motions::pose::{Pose, PoseVelocity};
+use crate::fluid_simulation::obstacles::moving_shape::MovingShape;
+use crate::fluid_simulation::obstacles::obstacle_trait::{Obstacle, Outline};
+use crate::fluid_simulation::obstacles::shapes::shape_trait::Shape;
+use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::real::{Real, RealVector};
+use std::fmt;
+use vector2d::Vector2D;
+
+// Cells per side of the grid the mass properties are sampled on.
+const MASS_SAMPLES: usize = 200;
+
+// Area, centroid and polar second moment of area about the centroid of a
+// shape, sampled at the centers of a grid over its bounds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AreaMoments<T: Real = f32> {
+    pub area: T,
+    pub centroid: Vector2D<T>,
+    pub second_moment: T,
+}
+
+pub fn area_moments<T: Real>(shape: &dyn Shape<T>) -> AreaMoments<T> {
+    let [top_left, bottom_right] = shape.bounds();
+    let cell_width = (bottom_right.x - top_left.x).as_f64() / MASS_SAMPLES as f64;
+    let cell_height = (bottom_right.y - top_left.y).as_f64() / MASS_SAMPLES as f64;
+    let inside_points: Vec<[f64; 2]> = (0..MASS_SAMPLES)
+        .flat_map(|row| (0..MASS_SAMPLES).map(move |column| (column, row)))
+        .map(|(column, row)| {
+            [
+                top_left.x.as_f64() + (column as f64 + 0.5) * cell_width,
+                top_left.y.as_f64() + (row as f64 + 0.5) * cell_height,
+            ]
+        })
+        .filter(|&[x, y]| shape.distance(Vector2D::new(T::from_f64(x), T::from_f64(y))) < T::zero())
+        .collect();
+    let count = inside_points.len().max(1) as f64;
+    let centroid_x = inside_points.iter().map(|point| point[0]).sum::<f64>() / count;
+    let centroid_y = inside_points.iter().map(|point| point[1]).sum::<f64>() / count;
+    let cell_area = cell_width * cell_height;
+    let second_moment = inside_points
+        .iter()
+        .map(|point| (point[0] - centroid_x).powi(2) + (point[1] - centroid_y).powi(2))
+        .sum::<f64>()
+        * cell_area;
+    AreaMoments {
+        area: T::from_f64(inside_points.len() as f64 * cell_area),
+        centroid: Vector2D::new(T::from_f64(centroid_x), T::from_f64(centroid_y)),
+        second_moment: T::from_f64(second_moment),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RigidBodyError {
+    NoArea,
+    InvalidMass(f64),
+    InvalidMomentOfInertia(f64),
+}
+
+impl fmt::Display for RigidBodyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RigidBodyError::NoArea => write!(f, "the shape encloses no area"),
+            RigidBodyError::InvalidMass(mass) => {
+                write!(f, "the mass must be positive, got {}", mass)
+            }
+            RigidBodyError::InvalidMomentOfInertia(moment_of_inertia) => write!(
+                f,
+                "the moment of inertia must be positive, got {}",
+                moment_of_inertia
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RigidBodyError {}
+
+// Solid body moved by gravity, the fluid around it, the walls and the
+// obstacles. It turns about its center of mass, the centroid of its shape,
+// and its pose is relative to where the scene puts it.
+pub struct RigidBody<T: Real = f32> {
+    moving_shape: MovingShape<T>,
+    mass: T,
+    moment_of_inertia: T,
+    // Largest distance of the shape from its center of mass.
+    radius: T,
+    // Forces of the fluid during the current step, and impulses of particles
+    // bouncing off, applied by the next integration.
+    force: Vector2D<T>,
+    torque: T,
+    impulse: Vector2D<T>,
+    angular_impulse: T,
+}
+
+impl<T: Real> RigidBody<T> {
+    // The moment of inertia is that of the mass spread evenly over the shape,
+    // which must enclose some area.
+    pub fn new(shape: Box<dyn Shape<T> + Send + Sync>, mass: T) -> Result<Self, RigidBodyError> {
+        let moments = area_moments(shape.as_ref());
+        RigidBody::from_moments(shape, moments, mass)
+    }
+
+    // Density is mass per unit area, in the units of the fluid density.
+    pub fn with_density(
+        shape: Box<dyn Shape<T> + Send + Sync>,
+        density: T,
+    ) -> Result<Self, RigidBodyError> {
+        let moments = area_moments(shape.as_ref());
+        RigidBody::from_moments(shape, moments, density * moments.area)
+    }
+
+    pub fn with_moment_of_inertia(mut self, moment_of_inertia: T) -> Result<Self, RigidBodyError> {
+        if moment_of_inertia.is_nan() || moment_of_inertia <= T::zero() {
+            return Err(RigidBodyError::InvalidMomentOfInertia(
+                moment_of_inertia.as_f64(),
+            ));
+        }
+        self.moment_of_inertia = moment_of_inertia;
+        Ok(self)
+    }
+
+    fn from_moments(
+        shape: Box<dyn Shape<T> + Send + Sync>,
+        moments: AreaMoments<T>,
+        mass: T,
+    ) -> Result<Self, RigidBodyError> {
+        if !(moments.area > T::zero() && moments.second_moment > T::zero()) {
+            return Err(RigidBodyError::NoArea);
+        }
+        if mass.is_nan() || mass <= T::zero() {
+            return Err(RigidBodyError::InvalidMass(mass.as_f64()));
+        }
+        let [top_left, bottom_right] = shape.bounds();
+        let radius = [
+            top_left,
+            bottom_right,
+            Vector2D::new(top_left.x, bottom_right.y),
+            Vector2D::new(bottom_right.x, top_left.y),
+        ]
+        .iter()
+        .map(|&corner| (corner - moments.centroid).length())
+        .fold(T::zero(), T::max);
+        let moment_of_inertia = mass * moments.second_moment / moments.area;
+        Ok(RigidBody {
+            moving_shape: MovingShape::new(shape, moments.centroid),
+            mass,
+            moment_of_inertia,
+            radius,
+            force: Vector2D::new(T::zero(), T::zero()),
+            torque: T::zero(),
+            impulse: Vector2D::new(T::zero(), T::zero()),
+            angular_impulse: T::zero(),
+        })
+    }
+
+    pub fn mass(&self) -> T {
+        self.mass
+    }
+
+    pub fn moment_of_inertia(&self) -> T {
+        self.moment_of_inertia
+    }
+
+    pub fn radius(&self) -> T {
+        self.radius
+    }
+
+    pub fn pose(&self) -> Pose<T> {
+        self.moving_shape.pose()
+    }
+
+    pub fn velocity(&self) -> PoseVelocity<T> {
+        self.moving_shape.velocity()
+    }
+
+    pub fn set_state(&mut self, pose: Pose<T>, velocity: PoseVelocity<T>) {
+        self.moving_shape.set_pose(pose, velocity);
+    }
+
+    // Center of mass in simulation coordinates.
+    pub fn center(&self) -> Vector2D<T> {
+        self.moving_shape.center()
+    }
+
+    pub fn moving_shape(&self) -> &MovingShape<T> {
+        &self.moving_shape
+    }
+
+    pub fn outline(&self) -> Outline<T> {
+        self.moving_shape.outline()
+    }
+
+    pub fn material(&self) -> BoundaryMaterial {
+        self.moving_shape.material()
+    }
+
+    pub fn set_material(&mut self, material: BoundaryMaterial) {
+        self.moving_shape.set_material(material);
+    }
+
+    pub fn linear_momentum(&self) -> Vector2D<T> {
+        self.velocity().linear * self.mass
+    }
+
+    // Force held up to the next integration, acting at a point in simulation
+    // coordinates.
+    pub fn apply_force(&mut self, force: Vector2D<T>, point: Vector2D<T>) {
+        self.force += force;
+        self.torque += cross(point - self.center(), force);
+    }
+
+    pub fn apply_impulse(&mut self, impulse: Vector2D<T>, point: Vector2D<T>) {
+        self.impulse += impulse;
+        self.angular_impulse += cross(point - self.center(), impulse);
+    }
+
+    // Impulse on the body of a particle pushed out of it and where it acts,
+    // leaving the body itself unchanged.
+    pub fn collision_impulse(
+        &self,
+        particle: &mut Particle<T>,
+    ) -> Option<(Vector2D<T>, Vector2D<T>)> {
+        let velocity = particle.velocity;
+        if !self.moving_shape.apply_obstruction_boundary(particle) {
+            return None;
+        }
+        Some((
+            (velocity - particle.velocity) * particle.mass,
+            particle.position,
+        ))
+    }
+
+    // Symplectic Euler step with the forces and impulses gathered since the
+    // last one, which are then cleared.
+    pub fn integrate(&mut self, gravity: Vector2D<T>, delta_time: T) {
+        let pose = self.pose();
+        let velocity = self.velocity();
+        let linear = velocity.linear
+            + (self.force * delta_time + self.impulse) / self.mass
+            + gravity * delta_time;
+        let angular = velocity.angular
+            + (self.torque * delta_time + self.angular_impulse) / self.moment_of_inertia;
+        self.moving_shape.set_pose(
+            Pose::new(
+                pose.translation + linear * delta_time,
+                pose.rotation + angular * delta_time,
+            ),
+            PoseVelocity::new(linear, angular),
+        );
+        self.force = Vector2D::new(T::zero(), T::zero());
+        self.torque = T::zero();
+        self.impulse = Vector2D::new(T::zero(), T::zero());
+        self.angular_impulse = T::zero();
+    }
+
+    // Pushes the body back inside the box and applies the impulse that gives
+    // the deepest point outside each wall the velocity the wall material
+    // leaves it with.
+    pub fn collide_with_walls(&mut self, box_dimensions: [T; 2], materials: &WallMaterials) {
+        let [width, height] = box_dimensions;
+        let walls = [
+            (
+                Vector2D::new(T::one(), T::zero()),
+                T::zero(),
+                materials.left,
+            ),
+            (Vector2D::new(-T::one(), T::zero()), -width, materials.right),
+            (Vector2D::new(T::zero(), T::one()), T::zero(), materials.top),
+            (
+                Vector2D::new(T::zero(), -T::one()),
+                -height,
+                materials.bottom,
+            ),
+        ];
+        let mut points = outline_points(self.outline());
+        for (normal, offset, material) in walls {
+            // Penetration is how far a point lies behind the wall.
+            let deepest = points
+                .iter()
+                .map(|&point| (offset - Vector2D::dot(point, normal), point))
+                .filter(|(penetration, _)| *penetration > T::zero())
+                .max_by(|a, b| a.0.as_f64().total_cmp(&b.0.as_f64()));
+            let Some((penetration, point)) = deepest else {
+                continue;
+            };
+            let zero = Vector2D::new(T::zero(), T::zero());
+            self.resolve_contact(point, normal, penetration, zero, material);
+            // The outline moves with the body.
+            for point in points.iter_mut() {
+                *point += normal * penetration;
+            }
+        }
+    }
+
+    // Pushes the body out of an obstacle along the deepest contact, where
+    // either a point of the body outline lies inside the obstacle or a point
+    // of the obstacle outline inside the body, and applies the impulse that
+    // gives the body there the velocity relative to the obstacle surface its
+    // material leaves it with.
+    pub fn collide_with_obstacle(&mut self, obstacle: &dyn Obstacle<T>) {
+        // Contacts are the penetration, the point and the direction the body
+        // is pushed in.
+        let body_contacts = outline_points(self.outline())
+            .into_iter()
+            .filter_map(|point| {
+                let (distance, normal) = obstacle.distance_and_normal(point)?;
+                Some((-distance, point, normal))
+            });
+        let obstacle_contacts = outline_points(obstacle.outline()).into_iter().map(|point| {
+            let (distance, normal) = self.moving_shape.distance_and_normal(point);
+            (-distance, point, -normal)
+        });
+        let deepest = body_contacts
+            .chain(obstacle_contacts)
+            .filter(|(penetration, _, _)| *penetration > T::zero())
+            .max_by(|a, b| a.0.as_f64().total_cmp(&b.0.as_f64()));
+        let Some((penetration, point, normal)) = deepest else {
+            return;
+        };
+        let surface_velocity = obstacle.surface_velocity(point);
+        self.resolve_contact(
+            point,
+            normal,
+            penetration,
+            surface_velocity,
+            obstacle.material(),
+        );
+    }
+
+    // Moves the body by the penetration along the normal, then applies the
+    // impulse at the contact point that gives its velocity relative to the
+    // surface there the velocity the material leaves it with.
+    fn resolve_contact(
+        &mut self,
+        point: Vector2D<T>,
+        normal: Vector2D<T>,
+        penetration: T,
+        surface_velocity: Vector2D<T>,
+        material: BoundaryMaterial,
+    ) {
+        self.translate(normal * penetration);
+        let point = point + normal * penetration;
+        let relative_velocity = self.moving_shape.surface_velocity(point) - surface_velocity;
+        let velocity_change = material.collide(relative_velocity, normal) - relative_velocity;
+        let impulse = solve(self.effective_mass(point), velocity_change);
+        self.change_velocity(impulse, point);
+    }
+
+    // Resolves the deepest contact between two bodies like a contact with an
+    // obstacle, with the material of the surface that is hit. The
+    // penetration is split by inverse mass, and the impulse gives the
+    // velocity of one body relative to the other at the contact the velocity
+    // the material leaves it with.
+    pub fn collide_with_body(&mut self, other: &mut RigidBody<T>) {
+        let reach = self.radius + other.radius;
+        if (self.center() - other.center()).length_squared() > reach * reach {
+            return;
+        }
+        // Contacts are the penetration, the point, the direction this body
+        // is pushed in and the material hit.
+        let own_contacts = outline_points(self.outline()).into_iter().map(|point| {
+            let (distance, normal) = other.moving_shape.distance_and_normal(point);
+            (-distance, point, normal, other.material())
+        });
+        let other_contacts = outline_points(other.outline()).into_iter().map(|point| {
+            let (distance, normal) = self.moving_shape.distance_and_normal(point);
+            (-distance, point, -normal, self.material())
+        });
+        let deepest = own_contacts
+            .chain(other_contacts)
+            .filter(|(penetration, _, _, _)| *penetration > T::zero())
+            .max_by(|a, b| a.0.as_f64().total_cmp(&b.0.as_f64()));
+        let Some((penetration, point, normal, material)) = deepest else {
+            return;
+        };
+        let own_share = other.mass / (self.mass + other.mass);
+        self.translate(normal * (penetration * own_share));
+        other.translate(-normal * (penetration * (T::one() - own_share)));
+        let point = point + normal * (penetration * own_share);
+        let relative_velocity =
+            self.moving_shape.surface_velocity(point) - other.moving_shape.surface_velocity(point);
+        let velocity_change = material.collide(relative_velocity, normal) - relative_velocity;
+        let [own_xx, own_xy, own_yy] = self.effective_mass(point);
+        let [other_xx, other_xy, other_yy] = other.effective_mass(point);
+        let impulse = solve(
+            [own_xx + other_xx, own_xy + other_xy, own_yy + other_yy],
+            velocity_change,
+        );
+        self.change_velocity(impulse, point);
+        other.change_velocity(-impulse, point);
+    }
+
+    fn translate(&mut self, offset: Vector2D<T>) {
+        let pose = self.pose();
+        self.moving_shape.set_pose(
+            Pose::new(pose.translation + offset, pose.rotation),
+            self.velocity(),
+        );
+    }
+
+    // Applies an impulse at a point to the velocity right away.
+    fn change_velocity(&mut self, impulse: Vector2D<T>, point: Vector2D<T>) {
+        let offset = point - self.center();
+        let velocity = self.velocity();
+        self.moving_shape.set_pose(
+            self.pose(),
+            PoseVelocity::new(
+                velocity.linear + impulse / self.mass,
+                velocity.angular + cross(offset, impulse) / self.moment_of_inertia,
+            ),
+        );
+    }
+
+    // Entries xx, xy and yy of the symmetric 2x2 matrix K that maps an
+    // impulse at a point to the change of the velocity of the body there.
+    fn effective_mass(&self, point: Vector2D<T>) -> [T; 3] {
+        let offset = point - self.center();
+        let inverse_mass = T::one() / self.mass;
+        let inverse_inertia = T::one() / self.moment_of_inertia;
+        [
+            inverse_mass + offset.y * offset.y * inverse_inertia,
+            -offset.x * offset.y * inverse_inertia,
+            inverse_mass + offset.x * offset.x * inverse_inertia,
+        ]
+    }
+}
+
+// Impulse j with K j = dv for the velocity change dv.
+fn solve<T: Real>(effective_mass: [T; 3], velocity_change: Vector2D<T>) -> Vector2D<T> {
+    let [k_xx, k_xy, k_yy] = effective_mass;
+    let determinant = k_xx * k_yy - k_xy * k_xy;
+    Vector2D::new(
+        (k_yy * velocity_change.x - k_xy * velocity_change.y) / determinant,
+        (k_xx * velocity_change.y - k_xy * velocity_change.x) / determinant,
+    )
+}
+
+fn outline_points<T: Real>(outline: Outline<T>) -> Vec<Vector2D<T>> {
+    match outline {
+        Outline::Polygon(points) => points,
+        Outline::Segments(segments) => segments.into_iter().flatten().collect(),
+    }
+}
+
+fn cross<T: Real>(a: Vector2D<T>, b: Vector2D<T>) -> T {
+    a.x * b.y - a.y * b.x
+}
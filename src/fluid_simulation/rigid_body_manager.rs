@@ -0,0 +1,153 @@
+use crate::fluid_simulation::boundary_material::WallMaterials;
+use crate::fluid_simulation::obstacles::obstacle_trait::Obstacle;
+use crate::fluid_simulation::particle_store::ParticleStore;
+use crate::fluid_simulation::real::Real;
+use crate::fluid_simulation::rigid_body::RigidBody;
+use crate::fluid_simulation::smoothed_interaction::SmoothedInteraction;
+use rayon::prelude::*;
+use vector2d::Vector2D;
+
+// Couples the rigid bodies with the fluid both ways: particles feel the
+// bodies as moving boundaries, and the bodies take up the opposite forces and
+// impulses. Each pass computes the contribution of every particle in
+// parallel and sums them in particle order, so runs stay deterministic.
+// Bodies collide with each other, the active obstacles and the walls.
+pub struct RigidBodyManager<T: Real = f32> {
+    rigid_bodies: Vec<RigidBody<T>>,
+    box_dimensions: [T; 2],
+    has_walls: bool,
+    wall_materials: WallMaterials,
+    // Per-particle force or impulse on the current body and where it acts.
+    contributions: Vec<Option<(Vector2D<T>, Vector2D<T>)>>,
+}
+
+impl<T: Real> RigidBodyManager<T> {
+    pub fn new(
+        rigid_bodies: Vec<RigidBody<T>>,
+        box_dimensions: [usize; 2],
+        has_walls: bool,
+        wall_materials: WallMaterials,
+    ) -> Self {
+        RigidBodyManager {
+            rigid_bodies,
+            box_dimensions: [
+                T::from_f64(box_dimensions[0] as f64),
+                T::from_f64(box_dimensions[1] as f64),
+            ],
+            has_walls,
+            wall_materials,
+            contributions: Vec::new(),
+        }
+    }
+
+    pub fn rigid_bodies(&self) -> &[RigidBody<T>] {
+        &self.rigid_bodies
+    }
+
+    pub fn rigid_bodies_mut(&mut self) -> &mut [RigidBody<T>] {
+        &mut self.rigid_bodies
+    }
+
+    pub fn add(&mut self, rigid_body: RigidBody<T>) -> usize {
+        self.rigid_bodies.push(rigid_body);
+        self.rigid_bodies.len() - 1
+    }
+
+    // Pushes particles out of the bodies, which take up the opposite of the
+    // momentum the particles receive.
+    pub fn collide(&mut self, particles: &mut ParticleStore<T>) {
+        self.contributions.resize(particles.len(), None);
+        for rigid_body in self.rigid_bodies.iter_mut() {
+            let body = &*rigid_body;
+            particles.par_update_with(&mut self.contributions, |_, particle, contribution| {
+                *contribution = body.collision_impulse(particle);
+            });
+            for &(impulse, point) in self.contributions.iter().flatten() {
+                rigid_body.apply_impulse(impulse, point);
+            }
+        }
+    }
+
+    // Adds the pressure and viscous accelerations of the bodies on the
+    // particles near them, and the opposite forces to the bodies.
+    pub fn apply_fluid_forces(
+        &mut self,
+        particles: &ParticleStore<T>,
+        densities: &[T],
+        pressures: &[T],
+        smoothed_interaction: &SmoothedInteraction<T>,
+        accelerations: &mut [Vector2D<T>],
+    ) {
+        self.contributions.resize(particles.len(), None);
+        // Particles interact with their mirror image, twice as far away.
+        let reach = smoothed_interaction
+            .pressure_kernel()
+            .support_radius(smoothed_interaction.smoothing_radius())
+            / T::from_f64(2.0);
+        for rigid_body in self.rigid_bodies.iter_mut() {
+            let moving_shape = rigid_body.moving_shape();
+            let center = rigid_body.center();
+            let radius = rigid_body.radius() + reach;
+            particles
+                .par_iter()
+                .zip(densities.par_iter())
+                .zip(pressures.par_iter())
+                .zip(self.contributions.par_iter_mut())
+                .for_each(|(((particle, &density), &pressure), contribution)| {
+                    *contribution = None;
+                    if (particle.position - center).length_squared() > radius * radius {
+                        return;
+                    }
+                    let (distance, normal) = moving_shape.distance_and_normal(particle.position);
+                    if distance < T::zero() || distance >= reach {
+                        return;
+                    }
+                    let point = particle.position - normal * distance;
+                    let acceleration = smoothed_interaction.calculate_boundary_acceleration(
+                        &particle,
+                        density,
+                        pressure,
+                        distance,
+                        normal,
+                        moving_shape.surface_velocity(point),
+                    );
+                    *contribution = Some((acceleration, point));
+                });
+            for (index, contribution) in self.contributions.iter().enumerate() {
+                if let Some((acceleration, point)) = *contribution {
+                    accelerations[index] += acceleration;
+                    rigid_body.apply_force(-acceleration * particles.masses[index], point);
+                }
+            }
+        }
+    }
+
+    // Moves the bodies with gravity and what the fluid applied to them since
+    // the last step, then resolves their contacts with each other, the
+    // obstacles and the walls, which go last so bodies stay in the box.
+    pub fn integrate(
+        &mut self,
+        gravity: Vector2D<T>,
+        delta_time: T,
+        obstacles: &[Box<dyn Obstacle<T> + Send + Sync>],
+    ) {
+        for rigid_body in self.rigid_bodies.iter_mut() {
+            rigid_body.integrate(gravity, delta_time);
+        }
+        for index in 1..self.rigid_bodies.len() {
+            let (before, after) = self.rigid_bodies.split_at_mut(index);
+            let rigid_body = &mut after[0];
+            for other in before.iter_mut() {
+                rigid_body.collide_with_body(other);
+            }
+        }
+        for rigid_body in self.rigid_bodies.iter_mut() {
+            for obstacle in obstacles.iter().filter(|obstacle| obstacle.is_active()) {
+                rigid_body.collide_with_obstacle(obstacle.as_ref());
+            }
+            if self.has_walls {
+                rigid_body.collide_with_walls(self.box_dimensions, &self.wall_materials);
+            }
+        }
+    }
+}
@@ -0,0 +1,1107 @@
+//! Scene files describe the initial conditions of a run as TOML data: the
+//! domain, the fluid blocks to fill with particles, the physical parameters,
+//! gravity, obstacles, rigid bodies and attractors. See
+//! `scenes/dam_break.toml` for an annotated example of every section.
+
+use crate::fluid_simulation::boundary_material::{BoundaryMaterial, Friction, WallMaterials};
+use crate::fluid_simulation::equation_of_state::EquationOfStateType;
+use crate::fluid_simulation::grids::grid_type::GridType;
+use crate::fluid_simulation::integrators::integrator_type::IntegratorType;
+use crate::fluid_simulation::kernels::kernel_type::KernelType;
+use crate::fluid_simulation::obstacles::dam_obstacle::DamObstacle;
+use crate::fluid_simulation::obstacles::kinematic_obstacle::KinematicObstacle;
+use crate::fluid_simulation::obstacles::motions::keyframe_motion::{Keyframe, KeyframeMotion};
+use crate::fluid_simulation::obstacles::motions::motion_trait::Motion;
+use crate::fluid_simulation::obstacles::motions::oscillating_motion::OscillatingMotion;
+use crate::fluid_simulation::obstacles::motions::pose::{Pose, PoseVelocity};
+use crate::fluid_simulation::obstacles::motions::rotating_motion::RotatingMotion;
+use crate::fluid_simulation::obstacles::obstacle_trait::Obstacle;
+use crate::fluid_simulation::obstacles::shape_obstacle::ShapeObstacle;
+use crate::fluid_simulation::obstacles::shapes::capsule_shape::CapsuleShape;
+use crate::fluid_simulation::obstacles::shapes::circle_shape::CircleShape;
+use crate::fluid_simulation::obstacles::shapes::difference_shape::DifferenceShape;
+use crate::fluid_simulation::obstacles::shapes::polygon_shape::PolygonShape;
+use crate::fluid_simulation::obstacles::shapes::rounded_box_shape::RoundedBoxShape;
+use crate::fluid_simulation::obstacles::shapes::shape_trait::Shape;
+use crate::fluid_simulation::obstacles::shapes::union_shape::UnionShape;
+use crate::fluid_simulation::real::{vector_from_f32, Real};
+use crate::fluid_simulation::rigid_body::{RigidBody, RigidBodyError};
+use crate::fluid_simulation::smoothed_interaction::PressureGradient;
+use crate::fluid_simulation::solvers::solver_type::SolverType;
+use crate::fluid_simulation::vtk_writer::{VtkFormat, VtkWriter};
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use vector2d::Vector2D;
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Scene {
+    // Drives all randomness of a run, so equal seeds give identical runs.
+    #[serde(default)]
+    pub seed: u64,
+    pub domain: DomainDescription,
+    #[serde(default)]
+    pub parameters: ParametersDescription,
+    #[serde(default)]
+    pub pressure: PressureDescription,
+    #[serde(default)]
+    pub solver: SolverDescription,
+    #[serde(default)]
+    pub time_step: TimeStepDescription,
+    #[serde(default)]
+    pub neighbor_search: NeighborSearchDescription,
+    #[serde(default)]
+    pub kernels: KernelsDescription,
+    #[serde(default)]
+    pub gravity: GravityDescription,
+    #[serde(default)]
+    pub fluid_blocks: Vec<FluidBlockDescription>,
+    #[serde(default)]
+    pub obstacles: Vec<ObstacleEntry>,
+    #[serde(default)]
+    pub rigid_bodies: Vec<RigidBodyDescription>,
+    #[serde(default)]
+    pub attractors: Vec<AttractorDescription>,
+    pub output: Option<OutputDescription>,
+    pub recording: Option<RecordingDescription>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct DomainDescription {
+    pub width: usize,
+    pub height: usize,
+    #[serde(default = "default_walls")]
+    pub walls: bool,
+    #[serde(default)]
+    pub grid: GridType,
+    #[serde(default)]
+    pub wall_materials: WallMaterials,
+}
+
+fn default_walls() -> bool {
+    true
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct ParametersDescription {
+    pub pressure_multiplier: f32,
+    pub target_density: f32,
+    pub smoothing_radius: f32,
+    pub viscosity: f32,
+    pub particle_mass: f32,
+    pub updates_per_second: usize,
+}
+
+impl Default for ParametersDescription {
+    fn default() -> Self {
+        ParametersDescription {
+            pressure_multiplier: 800000.0,
+            target_density: 0.00003,
+            smoothing_radius: 14.0,
+            viscosity: 0.04,
+            particle_mass: 0.0008,
+            updates_per_second: 100,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct PressureDescription {
+    pub equation_of_state: EquationOfStateType,
+    pub speed_of_sound: f32,
+    pub gamma: f32,
+    pub clamp_negative_pressure: bool,
+    pub gradient: PressureGradient,
+}
+
+impl Default for PressureDescription {
+    fn default() -> Self {
+        PressureDescription {
+            equation_of_state: EquationOfStateType::Linear,
+            speed_of_sound: 900.0,
+            gamma: 7.0,
+            clamp_negative_pressure: false,
+            gradient: PressureGradient::Shared,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct SolverDescription {
+    #[serde(rename = "type")]
+    pub solver_type: SolverType,
+    pub density_tolerance: f32,
+    pub divergence_tolerance: f32,
+    pub max_iterations: usize,
+}
+
+impl Default for SolverDescription {
+    fn default() -> Self {
+        SolverDescription {
+            solver_type: SolverType::Explicit,
+            density_tolerance: 0.01,
+            divergence_tolerance: 0.01,
+            max_iterations: 50,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimeStepDescription {
+    pub integrator: IntegratorType,
+    pub adaptive: bool,
+    pub cfl_factor: f32,
+    pub force_factor: f32,
+    pub viscosity_factor: f32,
+    pub min_delta_time: f32,
+    pub max_delta_time: f32,
+}
+
+impl Default for TimeStepDescription {
+    fn default() -> Self {
+        TimeStepDescription {
+            integrator: IntegratorType::VelocityVerlet,
+            adaptive: true,
+            cfl_factor: 0.4,
+            force_factor: 0.25,
+            viscosity_factor: 0.125,
+            min_delta_time: 0.0001,
+            max_delta_time: 0.01,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct NeighborSearchDescription {
+    pub verlet_lists: bool,
+    pub skin: f32,
+}
+
+impl Default for NeighborSearchDescription {
+    fn default() -> Self {
+        NeighborSearchDescription {
+            verlet_lists: false,
+            skin: 4.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct KernelsDescription {
+    pub density: KernelType,
+    pub pressure: KernelType,
+    pub viscosity: KernelType,
+}
+
+impl Default for KernelsDescription {
+    fn default() -> Self {
+        KernelsDescription {
+            density: KernelType::Sb,
+            pressure: KernelType::Sb,
+            viscosity: KernelType::Viscosity,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct GravityDescription {
+    pub enabled: bool,
+    pub acceleration: [f32; 2],
+}
+
+impl Default for GravityDescription {
+    fn default() -> Self {
+        GravityDescription {
+            enabled: true,
+            acceleration: [0.0, 980.0],
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct FluidBlockDescription {
+    pub top_left: [f32; 2],
+    pub bottom_right: [f32; 2],
+    pub particle_count: usize,
+}
+
+// An obstacle of the scene with the material of its surface and, for moving
+// obstacles, their motion. The material and motion tables sit next to the
+// fields of the obstacle. Serde does not deny unknown fields next to a
+// flattened one, so the flattened descriptions reject them instead.
+#[derive(Deserialize, Clone, Debug)]
+pub struct ObstacleEntry {
+    #[serde(flatten)]
+    pub obstacle: ObstacleDescription,
+    #[serde(default)]
+    pub material: BoundaryMaterial,
+    pub motion: Option<MotionDescription>,
+}
+
+impl ObstacleEntry {
+    pub fn build<T: Real>(&self, box_dimensions: [usize; 2]) -> Box<dyn Obstacle<T> + Send + Sync> {
+        let mut obstacle = match &self.motion {
+            None => self.obstacle.build(box_dimensions),
+            Some(motion) => {
+                let shape = self
+                    .obstacle
+                    .shape()
+                    .expect("validated scenes have no moving dams");
+                // Moving obstacles turn about the center of their bounds
+                // unless a pivot is given.
+                let pivot = match motion.pivot {
+                    Some(pivot) => point(pivot),
+                    None => {
+                        let [top_left, bottom_right] = shape.bounds();
+                        (top_left + bottom_right) * T::from_f64(0.5)
+                    }
+                };
+                Box::new(KinematicObstacle::new(
+                    shape,
+                    pivot,
+                    motion.trajectory.build(),
+                ))
+            }
+        };
+        obstacle.set_material(self.material);
+        obstacle
+    }
+}
+
+impl From<ObstacleDescription> for ObstacleEntry {
+    fn from(obstacle: ObstacleDescription) -> Self {
+        ObstacleEntry {
+            obstacle,
+            material: BoundaryMaterial::default(),
+            motion: None,
+        }
+    }
+}
+
+// A body of any obstacle shape but a dam, moved by gravity and the fluid.
+// Either its density, in the units of parameters.target_density, or its mass
+// is given. The moment of inertia defaults to that of the mass spread evenly
+// over the shape.
+#[derive(Deserialize, Clone, Debug)]
+pub struct RigidBodyDescription {
+    #[serde(flatten)]
+    pub shape: ObstacleDescription,
+    pub density: Option<f32>,
+    pub mass: Option<f32>,
+    pub moment_of_inertia: Option<f32>,
+    #[serde(default)]
+    pub velocity: [f32; 2],
+    #[serde(default)]
+    pub angular_velocity: f32,
+    #[serde(default)]
+    pub material: BoundaryMaterial,
+}
+
+impl RigidBodyDescription {
+    pub fn build<T: Real>(&self) -> Result<RigidBody<T>, RigidBodyError> {
+        let shape = self
+            .shape
+            .shape()
+            .expect("validated scenes have no dam rigid bodies");
+        let mut rigid_body = match (self.density, self.mass) {
+            (Some(density), _) => RigidBody::with_density(shape, T::from_f32(density))?,
+            (None, mass) => RigidBody::new(
+                shape,
+                T::from_f32(mass.expect("validated rigid bodies have a density or a mass")),
+            )?,
+        };
+        if let Some(moment_of_inertia) = self.moment_of_inertia {
+            rigid_body = rigid_body.with_moment_of_inertia(T::from_f32(moment_of_inertia))?;
+        }
+        rigid_body.set_material(self.material);
+        rigid_body.set_state(
+            Pose::identity(),
+            PoseVelocity::new(point(self.velocity), T::from_f32(self.angular_velocity)),
+        );
+        Ok(rigid_body)
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct MotionDescription {
+    #[serde(flatten)]
+    pub trajectory: TrajectoryDescription,
+    pub pivot: Option<[f32; 2]>,
+}
+
+// Angles are in radians and frequencies in cycles per second.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum TrajectoryDescription {
+    Keyframes {
+        keyframes: Vec<KeyframeDescription>,
+        #[serde(default)]
+        looping: bool,
+    },
+    Oscillating {
+        #[serde(default)]
+        amplitude: [f32; 2],
+        #[serde(default)]
+        angular_amplitude: f32,
+        frequency: f32,
+        #[serde(default)]
+        phase: f32,
+    },
+    Rotating {
+        angular_velocity: f32,
+    },
+}
+
+impl TrajectoryDescription {
+    pub fn build<T: Real>(&self) -> Box<dyn Motion<T> + Send + Sync> {
+        match self {
+            TrajectoryDescription::Keyframes { keyframes, looping } => {
+                Box::new(KeyframeMotion::new(
+                    keyframes
+                        .iter()
+                        .map(|keyframe| Keyframe {
+                            time: T::from_f32(keyframe.time),
+                            pose: Pose::new(
+                                point(keyframe.translation),
+                                T::from_f32(keyframe.rotation),
+                            ),
+                        })
+                        .collect(),
+                    *looping,
+                ))
+            }
+            TrajectoryDescription::Oscillating {
+                amplitude,
+                angular_amplitude,
+                frequency,
+                phase,
+            } => Box::new(OscillatingMotion::new(
+                point(*amplitude),
+                T::from_f32(*angular_amplitude),
+                T::from_f32(*frequency),
+                T::from_f32(*phase),
+            )),
+            TrajectoryDescription::Rotating { angular_velocity } => {
+                Box::new(RotatingMotion::new(T::from_f32(*angular_velocity)))
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct KeyframeDescription {
+    pub time: f32,
+    #[serde(default)]
+    pub translation: [f32; 2],
+    #[serde(default)]
+    pub rotation: f32,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ObstacleDescription {
+    Dam {
+        x_position: f32,
+    },
+    Rectangle {
+        top_left: [f32; 2],
+        bottom_right: [f32; 2],
+    },
+    Circle {
+        center: [f32; 2],
+        radius: f32,
+    },
+    Capsule {
+        start: [f32; 2],
+        end: [f32; 2],
+        radius: f32,
+    },
+    Polygon {
+        vertices: Vec<[f32; 2]>,
+    },
+    RoundedBox {
+        top_left: [f32; 2],
+        bottom_right: [f32; 2],
+        corner_radius: f32,
+    },
+    // Shapes combined with union and difference may be any obstacle but a dam.
+    Union {
+        shapes: Vec<ObstacleDescription>,
+    },
+    Difference {
+        shape: Box<ObstacleDescription>,
+        subtract: Vec<ObstacleDescription>,
+    },
+}
+
+impl ObstacleDescription {
+    pub fn build<T: Real>(&self, box_dimensions: [usize; 2]) -> Box<dyn Obstacle<T> + Send + Sync> {
+        match self {
+            ObstacleDescription::Dam { x_position } => Box::new(DamObstacle::new(
+                T::from_f32(*x_position),
+                T::from_f64(box_dimensions[1] as f64),
+            )),
+            _ => Box::new(ShapeObstacle::new(
+                self.shape()
+                    .expect("validated scenes have no dams inside shapes"),
+            )),
+        }
+    }
+
+    // Signed distance shape of the obstacle, with rectangles as sharp boxes.
+    // Dams have none.
+    pub fn shape<T: Real>(&self) -> Option<Box<dyn Shape<T> + Send + Sync>> {
+        let shape: Box<dyn Shape<T> + Send + Sync> = match self {
+            ObstacleDescription::Dam { .. } => return None,
+            ObstacleDescription::Rectangle {
+                top_left,
+                bottom_right,
+            } => Box::new(RoundedBoxShape::new(
+                point(*top_left),
+                point(*bottom_right),
+                T::zero(),
+            )),
+            ObstacleDescription::Circle { center, radius } => {
+                Box::new(CircleShape::new(point(*center), T::from_f32(*radius)))
+            }
+            ObstacleDescription::Capsule { start, end, radius } => Box::new(CapsuleShape::new(
+                point(*start),
+                point(*end),
+                T::from_f32(*radius),
+            )),
+            ObstacleDescription::Polygon { vertices } => Box::new(PolygonShape::new(
+                vertices.iter().map(|&vertex| point(vertex)).collect(),
+            )),
+            ObstacleDescription::RoundedBox {
+                top_left,
+                bottom_right,
+                corner_radius,
+            } => Box::new(RoundedBoxShape::new(
+                point(*top_left),
+                point(*bottom_right),
+                T::from_f32(*corner_radius),
+            )),
+            ObstacleDescription::Union { shapes } => Box::new(UnionShape::new(
+                shapes
+                    .iter()
+                    .map(|shape| shape.shape())
+                    .collect::<Option<_>>()?,
+            )),
+            ObstacleDescription::Difference { shape, subtract } => {
+                let subtracted: Box<dyn Shape<T> + Send + Sync> = Box::new(UnionShape::new(
+                    subtract
+                        .iter()
+                        .map(|shape| shape.shape())
+                        .collect::<Option<_>>()?,
+                ));
+                Box::new(DifferenceShape::new(shape.shape()?, subtracted))
+            }
+        };
+        Some(shape)
+    }
+}
+
+fn point<T: Real>(coordinates: [f32; 2]) -> Vector2D<T> {
+    vector_from_f32(Vector2D::new(coordinates[0], coordinates[1]))
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AttractorDescription {
+    #[serde(default)]
+    pub position: [f32; 2],
+    #[serde(default = "default_attractor_radius")]
+    pub radius: f32,
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default)]
+    pub follows_pointer: bool,
+}
+
+fn default_attractor_radius() -> f32 {
+    80.0
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OutputDescription {
+    pub directory: PathBuf,
+    #[serde(default = "default_output_name")]
+    pub name: String,
+    #[serde(default = "default_output_interval")]
+    pub interval: usize,
+    #[serde(default = "default_output_formats")]
+    pub formats: Vec<VtkFormat>,
+}
+
+impl OutputDescription {
+    pub fn build(&self) -> VtkWriter {
+        VtkWriter::new(
+            self.directory.clone(),
+            self.name.clone(),
+            self.formats.clone(),
+            self.interval,
+        )
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RecordingDescription {
+    pub path: PathBuf,
+    #[serde(default = "default_recording_interval")]
+    pub interval: usize,
+    pub csv_path: Option<PathBuf>,
+}
+
+fn default_recording_interval() -> usize {
+    1
+}
+
+fn default_output_name() -> String {
+    "particles".to_string()
+}
+
+fn default_output_interval() -> usize {
+    10
+}
+
+fn default_output_formats() -> Vec<VtkFormat> {
+    vec![VtkFormat::Xml]
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(String, std::io::Error),
+    Parse(toml::de::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io(path, error) => {
+                write!(f, "could not read scene file {}: {}", path, error)
+            }
+            SceneError::Parse(error) => write!(f, "could not parse scene file: {}", error),
+            SceneError::Invalid(message) => write!(f, "invalid scene: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl Scene {
+    pub fn load(path: impl AsRef<Path>) -> Result<Scene, SceneError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .map_err(|error| SceneError::Io(path.display().to_string(), error))?;
+        Scene::from_toml_str(&contents)
+    }
+
+    pub fn from_toml_str(contents: &str) -> Result<Scene, SceneError> {
+        let scene: Scene = toml::from_str(contents).map_err(SceneError::Parse)?;
+        scene.validate()?;
+        Ok(scene)
+    }
+
+    pub fn dam_break(box_dimensions: [usize; 2], particle_count: usize) -> Scene {
+        Scene {
+            seed: 0,
+            domain: DomainDescription {
+                width: box_dimensions[0],
+                height: box_dimensions[1],
+                walls: default_walls(),
+                grid: GridType::Bounded,
+                wall_materials: WallMaterials::default(),
+            },
+            parameters: ParametersDescription::default(),
+            pressure: PressureDescription::default(),
+            solver: SolverDescription::default(),
+            time_step: TimeStepDescription::default(),
+            neighbor_search: NeighborSearchDescription::default(),
+            kernels: KernelsDescription::default(),
+            gravity: GravityDescription::default(),
+            fluid_blocks: vec![FluidBlockDescription {
+                top_left: [0.0, 0.0],
+                bottom_right: [600.0, box_dimensions[1] as f32],
+                particle_count,
+            }],
+            obstacles: vec![
+                ObstacleDescription::Dam { x_position: 600.0 }.into(),
+                ObstacleDescription::Rectangle {
+                    top_left: [100.0, 400.0],
+                    bottom_right: [200.0, 750.0],
+                }
+                .into(),
+            ],
+            rigid_bodies: Vec::new(),
+            attractors: vec![AttractorDescription {
+                position: [0.0, 0.0],
+                radius: default_attractor_radius(),
+                active: false,
+                follows_pointer: true,
+            }],
+            output: None,
+            recording: None,
+        }
+    }
+
+    pub fn box_dimensions(&self) -> [usize; 2] {
+        [self.domain.width, self.domain.height]
+    }
+
+    pub fn particle_count(&self) -> usize {
+        self.fluid_blocks
+            .iter()
+            .map(|block| block.particle_count)
+            .sum()
+    }
+
+    // Spreads particle_count over the fluid blocks in proportion to the
+    // particles they hold now.
+    pub fn set_particle_count(&mut self, particle_count: usize) -> Result<(), SceneError> {
+        let block_count = self.fluid_blocks.len();
+        if block_count == 0 {
+            return invalid(
+                "the particle count cannot be set in a scene without fluid blocks".to_string(),
+            );
+        }
+        let current_count = self.particle_count();
+        let mut remaining_count = particle_count;
+        for (index, block) in self.fluid_blocks.iter_mut().enumerate() {
+            block.particle_count = if index + 1 == block_count {
+                remaining_count
+            } else if current_count == 0 {
+                particle_count / block_count
+            } else {
+                (particle_count as u128 * block.particle_count as u128 / current_count as u128)
+                    as usize
+            };
+            remaining_count -= block.particle_count;
+        }
+        Ok(())
+    }
+
+    // Writes the particle output to directory, with the default name, interval
+    // and formats if the scene has no output section.
+    pub fn set_output_directory(&mut self, directory: PathBuf) {
+        match self.output.as_mut() {
+            Some(output) => output.directory = directory,
+            None => {
+                self.output = Some(OutputDescription {
+                    directory,
+                    name: default_output_name(),
+                    interval: default_output_interval(),
+                    formats: default_output_formats(),
+                })
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), SceneError> {
+        if self.domain.width == 0 || self.domain.height == 0 {
+            return invalid(format!(
+                "domain must have a positive width and height, got {}x{}",
+                self.domain.width, self.domain.height
+            ));
+        }
+        if !self.domain.walls && self.domain.grid == GridType::Bounded {
+            return invalid(
+                "domain.walls = false lets particles leave the domain and needs domain.grid = \"hashed\""
+                    .to_string(),
+            );
+        }
+        let parameters = &self.parameters;
+        ensure_positive(
+            "parameters.pressure_multiplier",
+            parameters.pressure_multiplier,
+        )?;
+        ensure_positive("parameters.target_density", parameters.target_density)?;
+        ensure_positive("parameters.smoothing_radius", parameters.smoothing_radius)?;
+        ensure_positive("parameters.particle_mass", parameters.particle_mass)?;
+        if !(parameters.viscosity >= 0.0 && parameters.viscosity.is_finite()) {
+            return invalid(format!(
+                "parameters.viscosity must be zero or positive, got {}",
+                parameters.viscosity
+            ));
+        }
+        if parameters.updates_per_second == 0 {
+            return invalid("parameters.updates_per_second must be positive".to_string());
+        }
+        ensure_positive("pressure.speed_of_sound", self.pressure.speed_of_sound)?;
+        if !(self.pressure.gamma >= 1.0 && self.pressure.gamma.is_finite()) {
+            return invalid(format!(
+                "pressure.gamma must be at least 1, got {}",
+                self.pressure.gamma
+            ));
+        }
+        ensure_positive("solver.density_tolerance", self.solver.density_tolerance)?;
+        ensure_positive(
+            "solver.divergence_tolerance",
+            self.solver.divergence_tolerance,
+        )?;
+        if self.solver.max_iterations == 0 {
+            return invalid("solver.max_iterations must be positive".to_string());
+        }
+        // PCISPH scales its pressure corrections by the kernel gradients of a
+        // prototype particle's neighbors at rest, which it has none of when
+        // the rest spacing reaches the pressure kernel's support.
+        if self.solver.solver_type == SolverType::Pcisph {
+            let spacing = (parameters.particle_mass / parameters.target_density).sqrt();
+            let support_radius = self
+                .kernels
+                .pressure
+                .build::<f32>()
+                .support_radius(parameters.smoothing_radius);
+            if spacing >= support_radius {
+                return invalid(format!(
+                    "solver.type = \"pcisph\" needs a rest spacing below the pressure kernel support {}, but parameters.particle_mass and parameters.target_density give {}",
+                    support_radius, spacing
+                ));
+            }
+        }
+        let time_step = &self.time_step;
+        ensure_positive("time_step.cfl_factor", time_step.cfl_factor)?;
+        ensure_positive("time_step.force_factor", time_step.force_factor)?;
+        ensure_positive("time_step.viscosity_factor", time_step.viscosity_factor)?;
+        ensure_positive("time_step.min_delta_time", time_step.min_delta_time)?;
+        ensure_positive("time_step.max_delta_time", time_step.max_delta_time)?;
+        if time_step.min_delta_time > time_step.max_delta_time {
+            return invalid(format!(
+                "time_step.min_delta_time {} must not exceed time_step.max_delta_time {}",
+                time_step.min_delta_time, time_step.max_delta_time
+            ));
+        }
+        ensure_positive("neighbor_search.skin", self.neighbor_search.skin)?;
+        if !self
+            .gravity
+            .acceleration
+            .iter()
+            .all(|value| value.is_finite())
+        {
+            return invalid("gravity.acceleration must be finite".to_string());
+        }
+
+        for (index, block) in self.fluid_blocks.iter().enumerate() {
+            let name = format!("fluid_blocks[{}]", index);
+            self.ensure_valid_rectangle(&name, block.top_left, block.bottom_right)?;
+        }
+        let walls = &self.domain.wall_materials;
+        ensure_valid_material("domain.wall_materials.left", &walls.left)?;
+        ensure_valid_material("domain.wall_materials.right", &walls.right)?;
+        ensure_valid_material("domain.wall_materials.top", &walls.top)?;
+        ensure_valid_material("domain.wall_materials.bottom", &walls.bottom)?;
+        for (index, entry) in self.obstacles.iter().enumerate() {
+            let name = format!("obstacles[{}]", index);
+            ensure_valid_material(&format!("{}.material", name), &entry.material)?;
+            if let Some(motion) = &entry.motion {
+                if let ObstacleDescription::Dam { .. } = entry.obstacle {
+                    return invalid(format!("{}: a dam cannot move", name));
+                }
+                ensure_valid_motion(&format!("{}.motion", name), motion)?;
+            }
+            match &entry.obstacle {
+                ObstacleDescription::Dam { x_position } => {
+                    if !(*x_position >= 0.0 && *x_position <= self.domain.width as f32) {
+                        return invalid(format!(
+                            "{}: dam x_position {} is outside the domain width {}",
+                            name, x_position, self.domain.width
+                        ));
+                    }
+                }
+                ObstacleDescription::Rectangle {
+                    top_left,
+                    bottom_right,
+                } => self.ensure_valid_rectangle(&name, *top_left, *bottom_right)?,
+                shape => ensure_valid_shape(&name, shape)?,
+            }
+        }
+        for (index, rigid_body) in self.rigid_bodies.iter().enumerate() {
+            ensure_valid_rigid_body(&format!("rigid_bodies[{}]", index), rigid_body)?;
+        }
+        for (index, attractor) in self.attractors.iter().enumerate() {
+            ensure_positive(&format!("attractors[{}].radius", index), attractor.radius)?;
+            if !attractor.position.iter().all(|value| value.is_finite()) {
+                return invalid(format!("attractors[{}].position must be finite", index));
+            }
+        }
+        if let Some(output) = &self.output {
+            if output.interval == 0 {
+                return invalid("output.interval must be positive".to_string());
+            }
+            if output.formats.is_empty() {
+                return invalid("output.formats must name at least one format".to_string());
+            }
+            if output.name.is_empty() {
+                return invalid("output.name must not be empty".to_string());
+            }
+        }
+        if let Some(recording) = &self.recording {
+            if recording.interval == 0 {
+                return invalid("recording.interval must be positive".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn ensure_valid_rectangle(
+        &self,
+        name: &str,
+        top_left: [f32; 2],
+        bottom_right: [f32; 2],
+    ) -> Result<(), SceneError> {
+        ensure_ordered_corners(name, top_left, bottom_right)?;
+        let width = self.domain.width as f32;
+        let height = self.domain.height as f32;
+        if top_left[0] < 0.0
+            || top_left[1] < 0.0
+            || bottom_right[0] > width
+            || bottom_right[1] > height
+        {
+            return invalid(format!(
+                "{}: rectangle {:?}-{:?} is outside the {}x{} domain",
+                name, top_left, bottom_right, self.domain.width, self.domain.height
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Shapes may reach past the domain, only their dimensions are checked.
+fn ensure_valid_shape(name: &str, shape: &ObstacleDescription) -> Result<(), SceneError> {
+    match shape {
+        ObstacleDescription::Dam { .. } => {
+            invalid(format!("{}: a dam cannot be part of a shape", name))
+        }
+        ObstacleDescription::Rectangle {
+            top_left,
+            bottom_right,
+        } => ensure_ordered_corners(name, *top_left, *bottom_right),
+        ObstacleDescription::Circle { center, radius } => {
+            ensure_finite_point(&format!("{}.center", name), *center)?;
+            ensure_positive(&format!("{}.radius", name), *radius)
+        }
+        ObstacleDescription::Capsule { start, end, radius } => {
+            ensure_finite_point(&format!("{}.start", name), *start)?;
+            ensure_finite_point(&format!("{}.end", name), *end)?;
+            ensure_positive(&format!("{}.radius", name), *radius)
+        }
+        ObstacleDescription::Polygon { vertices } => {
+            if vertices.len() < 3 {
+                return invalid(format!(
+                    "{}: a polygon needs at least 3 vertices, got {}",
+                    name,
+                    vertices.len()
+                ));
+            }
+            for (index, vertex) in vertices.iter().enumerate() {
+                ensure_finite_point(&format!("{}.vertices[{}]", name, index), *vertex)?;
+                if *vertex == vertices[(index + 1) % vertices.len()] {
+                    return invalid(format!(
+                        "{}: vertices[{}] repeats the next vertex {:?}",
+                        name, index, vertex
+                    ));
+                }
+            }
+            // Twice the signed area, by the shoelace formula.
+            let doubled_area: f32 = vertices
+                .iter()
+                .zip(vertices.iter().cycle().skip(1))
+                .map(|(vertex, next)| vertex[0] * next[1] - next[0] * vertex[1])
+                .sum();
+            if doubled_area == 0.0 {
+                return invalid(format!("{}: the polygon vertices enclose no area", name));
+            }
+            Ok(())
+        }
+        ObstacleDescription::RoundedBox {
+            top_left,
+            bottom_right,
+            corner_radius,
+        } => {
+            ensure_ordered_corners(name, *top_left, *bottom_right)?;
+            let max_corner_radius =
+                0.5 * (bottom_right[0] - top_left[0]).min(bottom_right[1] - top_left[1]);
+            if !(*corner_radius >= 0.0 && *corner_radius <= max_corner_radius) {
+                return invalid(format!(
+                    "{}: corner_radius {} must be between 0 and half the shorter side {}",
+                    name, corner_radius, max_corner_radius
+                ));
+            }
+            Ok(())
+        }
+        ObstacleDescription::Union { shapes } => {
+            if shapes.is_empty() {
+                return invalid(format!("{}: a union needs at least one shape", name));
+            }
+            for (index, shape) in shapes.iter().enumerate() {
+                ensure_valid_shape(&format!("{}.shapes[{}]", name, index), shape)?;
+            }
+            Ok(())
+        }
+        ObstacleDescription::Difference { shape, subtract } => {
+            ensure_valid_shape(&format!("{}.shape", name), shape)?;
+            if subtract.is_empty() {
+                return invalid(format!(
+                    "{}: a difference needs at least one shape to subtract",
+                    name
+                ));
+            }
+            for (index, shape) in subtract.iter().enumerate() {
+                ensure_valid_shape(&format!("{}.subtract[{}]", name, index), shape)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn ensure_valid_material(name: &str, material: &BoundaryMaterial) -> Result<(), SceneError> {
+    if !(material.restitution >= 0.0 && material.restitution <= 1.0) {
+        return invalid(format!(
+            "{}.restitution must be between 0 and 1, got {}",
+            name, material.restitution
+        ));
+    }
+    if let Friction::Coulomb(coefficient) = material.friction {
+        if !(coefficient >= 0.0 && coefficient.is_finite()) {
+            return invalid(format!(
+                "{}.friction coefficient must be zero or positive, got {}",
+                name, coefficient
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn ensure_valid_rigid_body(
+    name: &str,
+    rigid_body: &RigidBodyDescription,
+) -> Result<(), SceneError> {
+    if let ObstacleDescription::Dam { .. } = rigid_body.shape {
+        return invalid(format!("{}: a dam cannot be a rigid body", name));
+    }
+    ensure_valid_shape(name, &rigid_body.shape)?;
+    match (rigid_body.density, rigid_body.mass) {
+        (Some(density), None) => ensure_positive(&format!("{}.density", name), density)?,
+        (None, Some(mass)) => ensure_positive(&format!("{}.mass", name), mass)?,
+        _ => return invalid(format!("{}: give either a density or a mass", name)),
+    }
+    if let Some(moment_of_inertia) = rigid_body.moment_of_inertia {
+        ensure_positive(&format!("{}.moment_of_inertia", name), moment_of_inertia)?;
+    }
+    ensure_finite_point(&format!("{}.velocity", name), rigid_body.velocity)?;
+    if !rigid_body.angular_velocity.is_finite() {
+        return invalid(format!("{}.angular_velocity must be finite", name));
+    }
+    ensure_valid_material(&format!("{}.material", name), &rigid_body.material)?;
+    // The mass is spread over the sampled area, so shapes that cover no
+    // sample, such as a difference that removes all of its shape, are
+    // rejected when the body is built.
+    match rigid_body.build::<f32>() {
+        Ok(_) => Ok(()),
+        Err(error) => invalid(format!("{}: {}", name, error)),
+    }
+}
+
+fn ensure_valid_motion(name: &str, motion: &MotionDescription) -> Result<(), SceneError> {
+    if let Some(pivot) = motion.pivot {
+        ensure_finite_point(&format!("{}.pivot", name), pivot)?;
+    }
+    match &motion.trajectory {
+        TrajectoryDescription::Keyframes { keyframes, looping } => {
+            if keyframes.is_empty() {
+                return invalid(format!("{}: keyframes must not be empty", name));
+            }
+            for (index, keyframe) in keyframes.iter().enumerate() {
+                let keyframe_name = format!("{}.keyframes[{}]", name, index);
+                ensure_finite_point(
+                    &format!("{}.translation", keyframe_name),
+                    keyframe.translation,
+                )?;
+                if !(keyframe.time.is_finite() && keyframe.rotation.is_finite()) {
+                    return invalid(format!(
+                        "{}: time and rotation must be finite",
+                        keyframe_name
+                    ));
+                }
+                if index > 0 && keyframe.time <= keyframes[index - 1].time {
+                    return invalid(format!(
+                        "{}: time {} must be later than the previous keyframe",
+                        keyframe_name, keyframe.time
+                    ));
+                }
+            }
+            if *looping && keyframes.len() < 2 {
+                return invalid(format!("{}: a looping motion needs two keyframes", name));
+            }
+            Ok(())
+        }
+        TrajectoryDescription::Oscillating {
+            amplitude,
+            angular_amplitude,
+            frequency,
+            phase,
+        } => {
+            ensure_finite_point(&format!("{}.amplitude", name), *amplitude)?;
+            ensure_positive(&format!("{}.frequency", name), *frequency)?;
+            if !(angular_amplitude.is_finite() && phase.is_finite()) {
+                return invalid(format!(
+                    "{}: angular_amplitude and phase must be finite",
+                    name
+                ));
+            }
+            Ok(())
+        }
+        TrajectoryDescription::Rotating { angular_velocity } => {
+            if !angular_velocity.is_finite() {
+                return invalid(format!("{}.angular_velocity must be finite", name));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn ensure_ordered_corners(
+    name: &str,
+    top_left: [f32; 2],
+    bottom_right: [f32; 2],
+) -> Result<(), SceneError> {
+    if !(top_left[0] < bottom_right[0] && top_left[1] < bottom_right[1]) {
+        return invalid(format!(
+            "{}: top_left {:?} must be above and to the left of bottom_right {:?}",
+            name, top_left, bottom_right
+        ));
+    }
+    Ok(())
+}
+
+fn ensure_finite_point(name: &str, point: [f32; 2]) -> Result<(), SceneError> {
+    if !point.iter().all(|value| value.is_finite()) {
+        return invalid(format!("{} must be finite", name));
+    }
+    Ok(())
+}
+
+fn ensure_positive(name: &str, value: f32) -> Result<(), SceneError> {
+    if value > 0.0 && value.is_finite() {
+        return Ok(());
+    }
+    invalid(format!("{} must be positive, got {}", name, value))
+}
+
+fn invalid(message: String) -> Result<(), SceneError> {
+    Err(SceneError::Invalid(message))
+}
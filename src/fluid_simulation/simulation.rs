@@ -0,0 +1,614 @@
+use super::obstacles::obstacle_trait::{Obstacle, Outline};
+use crate::fluid_simulation::cell_manager::{CellManager, NeighborStatistics};
+use crate::fluid_simulation::checkpoint::{
+    AttractorState, CheckpointError, CheckpointState, RigidBodyState,
+};
+use crate::fluid_simulation::config::{Accelerations, Densities, Obstacles, Particles, Pressures};
+use crate::fluid_simulation::equation_of_state::{
+    EquationOfState, EquationOfStateType, PressureLaw,
+};
+use crate::fluid_simulation::external_attractor::ExternalAttractor;
+use crate::fluid_simulation::obstacle_collision_manager::ObstacleCollisionManager;
+use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::particle_dynamics_manager::ParticleDynamicsManager;
+use crate::fluid_simulation::particle_store::ParticleStore;
+use crate::fluid_simulation::real::{vector_from_f32, Real, RealVector};
+use crate::fluid_simulation::rigid_body::RigidBody;
+use crate::fluid_simulation::rigid_body_manager::RigidBodyManager;
+use crate::fluid_simulation::scene::Scene;
+use crate::fluid_simulation::smoothed_interaction::SmoothedInteraction;
+use crate::fluid_simulation::solvers::solver_trait::{Solver, SolverContext, SolverStatistics};
+use crate::fluid_simulation::time_step_manager::TimeStepManager;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use vector2d::Vector2D;
+
+// The simulation is computed in T, f32 by default. Simulation::new builds an
+// f32 simulation; Simulation::<f64>::from_scene runs the same scene in double
+// precision, e.g. to validate the f32 results. Runs are deterministic: the
+// scene seed drives all randomness, and parallel passes either compute each
+// particle on its own or reduce with exact maxima, so equal seeds give
+// bit-identical states.
+pub struct Simulation<T: Real = f32> {
+    pub particles: Particles<T>,
+    dynamics_manager: ParticleDynamicsManager<T>,
+    time_step_manager: TimeStepManager<T>,
+    smoothed_interaction: SmoothedInteraction<T>,
+    external_attractors: Vec<ExternalAttractor<T>>,
+    collision_manager: ObstacleCollisionManager<T>,
+    rigid_body_manager: RigidBodyManager<T>,
+    cell_manager: CellManager<T>,
+    pub ups: usize,
+    densities: Densities<T>,
+    pressures: Pressures<T>,
+    accelerations: Accelerations<T>,
+    non_pressure_accelerations: Accelerations<T>,
+    pressure_accelerations: Accelerations<T>,
+    solver: Box<dyn Solver<T>>,
+    solver_statistics: SolverStatistics,
+    time: T,
+    delta_time: T,
+    sub_steps: usize,
+    obstacles: Obstacles<T>,
+}
+
+impl Simulation {
+    pub fn new(scene: &Scene) -> Self {
+        Simulation::from_scene(scene)
+    }
+}
+
+impl<T: Real> Simulation<T> {
+    pub fn from_scene(scene: &Scene) -> Self {
+        let mut rng = StdRng::seed_from_u64(scene.seed);
+        let box_dimensions = scene.box_dimensions();
+        let parameters = &scene.parameters;
+        let ups: usize = parameters.updates_per_second;
+        let frame_time = T::one() / T::from_f64(ups as f64);
+        let particle_mass = T::from_f32(parameters.particle_mass);
+        let smoothing_radius = T::from_f32(parameters.smoothing_radius);
+        let particle_count = scene.particle_count();
+        let particles: Vec<Particle<T>> = scene
+            .fluid_blocks
+            .iter()
+            .flat_map(|block| (0..block.particle_count).map(move |_| block))
+            .enumerate()
+            .map(|(index, block)| Particle {
+                mass: particle_mass,
+                ..Particle::new(
+                    index,
+                    Vector2D::new(
+                        T::from_f32(rng.gen_range(block.top_left[0]..block.bottom_right[0])),
+                        T::from_f32(rng.gen_range(block.top_left[1]..block.bottom_right[1])),
+                    ),
+                )
+            })
+            .collect();
+        let neighbor_skin = if scene.neighbor_search.verlet_lists {
+            T::from_f32(scene.neighbor_search.skin)
+        } else {
+            T::zero()
+        };
+        let densities: Densities<T> = vec![T::from_f64(0.001); particle_count];
+        let pressures: Pressures<T> = vec![T::zero(); particle_count];
+        let accelerations: Accelerations<T> =
+            vec![Vector2D::new(T::zero(), T::zero()); particle_count];
+        let gravity = vector_from_f32(Vector2D::new(
+            scene.gravity.acceleration[0],
+            scene.gravity.acceleration[1],
+        ));
+        let dynamics_manager = ParticleDynamicsManager::new(
+            gravity,
+            scene.gravity.enabled,
+            scene.time_step.integrator.build(),
+        );
+        let obstacles: Obstacles<T> = scene
+            .obstacles
+            .iter()
+            .map(|obstacle| obstacle.build(box_dimensions))
+            .collect();
+        let external_attractors: Vec<ExternalAttractor<T>> = scene
+            .attractors
+            .iter()
+            .map(|attractor| {
+                ExternalAttractor::new(
+                    vector_from_f32(Vector2D::new(attractor.position[0], attractor.position[1])),
+                    T::from_f32(attractor.radius),
+                    attractor.active,
+                    attractor.follows_pointer,
+                )
+            })
+            .collect();
+        let pressure_law = match scene.pressure.equation_of_state {
+            EquationOfStateType::Linear => PressureLaw::Linear {
+                pressure_multiplier: T::from_f32(parameters.pressure_multiplier),
+            },
+            EquationOfStateType::Tait => PressureLaw::Tait {
+                speed_of_sound: T::from_f32(scene.pressure.speed_of_sound),
+                gamma: T::from_f32(scene.pressure.gamma),
+            },
+        };
+        let equation_of_state = EquationOfState::new(
+            pressure_law,
+            T::from_f32(parameters.target_density),
+            scene.pressure.clamp_negative_pressure,
+        );
+        let smoothed_interaction = SmoothedInteraction::new(
+            equation_of_state,
+            scene.pressure.gradient,
+            smoothing_radius,
+            T::from_f32(parameters.viscosity),
+            scene.kernels.density.build(),
+            scene.kernels.pressure.build(),
+            scene.kernels.viscosity.build(),
+        )
+        .with_seed(scene.seed);
+        let solver = scene.solver.solver_type.build(
+            scene.solver.density_tolerance,
+            scene.solver.divergence_tolerance,
+            scene.solver.max_iterations,
+            particle_mass,
+            &smoothed_interaction,
+        );
+        Simulation {
+            particles: ParticleStore::from_particles(&particles),
+            ups,
+            densities,
+            pressures,
+            non_pressure_accelerations: accelerations.clone(),
+            pressure_accelerations: accelerations,
+            accelerations: vec![dynamics_manager.get_gravity(); particle_count],
+            solver,
+            solver_statistics: SolverStatistics::default(),
+            time: T::zero(),
+            delta_time: frame_time,
+            sub_steps: 0,
+            dynamics_manager,
+            time_step_manager: TimeStepManager::new(
+                frame_time,
+                scene.time_step.adaptive,
+                T::from_f32(scene.time_step.cfl_factor),
+                T::from_f32(scene.time_step.force_factor),
+                T::from_f32(scene.time_step.viscosity_factor),
+                T::from_f32(scene.time_step.min_delta_time),
+                T::from_f32(scene.time_step.max_delta_time),
+            ),
+            smoothed_interaction,
+            external_attractors,
+            collision_manager: ObstacleCollisionManager::new(
+                box_dimensions,
+                scene.domain.walls,
+                scene.domain.wall_materials,
+            ),
+            rigid_body_manager: RigidBodyManager::new(
+                scene
+                    .rigid_bodies
+                    .iter()
+                    .map(|rigid_body| {
+                        rigid_body
+                            .build()
+                            .expect("validated scenes build their rigid bodies")
+                    })
+                    .collect(),
+                box_dimensions,
+                scene.domain.walls,
+                scene.domain.wall_materials,
+            ),
+            cell_manager: CellManager::with_grid(
+                particle_count as i32,
+                scene.domain.grid.build(
+                    box_dimensions,
+                    particle_count,
+                    T::from_f64(2.0) * smoothing_radius + neighbor_skin,
+                ),
+                neighbor_skin,
+            ),
+            obstacles,
+        }
+    }
+
+    // Advances the simulation by one frame of 1 / ups seconds, split into as
+    // many sub-steps as the adaptive timestep requires.
+    pub fn update(&mut self) {
+        let mut remaining_time = self.time_step_manager.frame_time();
+        self.sub_steps = 0;
+        while remaining_time > T::zero() {
+            let stable_delta_time = self.calculate_stable_delta_time();
+            self.delta_time = self
+                .time_step_manager
+                .calculate_sub_step(stable_delta_time, remaining_time);
+            self.step(self.delta_time);
+            remaining_time -= self.delta_time;
+            self.sub_steps += 1;
+        }
+    }
+
+    fn calculate_stable_delta_time(&self) -> T {
+        let gravity = self.dynamics_manager.get_gravity();
+        let max_speed = self
+            .particles
+            .par_iter()
+            .map(|particle| particle.speed())
+            .reduce(T::zero, T::max);
+        // Accelerations already include gravity, the non-pressure ones do not.
+        let max_acceleration = if self.solver.limits_timestep() {
+            self.accelerations
+                .par_iter()
+                .map(|acceleration| acceleration.length())
+                .reduce(T::zero, T::max)
+        } else {
+            self.non_pressure_accelerations
+                .par_iter()
+                .map(|&acceleration| (acceleration + gravity).length())
+                .reduce(T::zero, T::max)
+        };
+        let kinematic_viscosity =
+            self.smoothed_interaction.viscosity() / self.smoothed_interaction.target_density();
+        self.time_step_manager.calculate_delta_time(
+            max_speed,
+            max_acceleration,
+            self.smoothed_interaction.smoothing_radius(),
+            kinematic_viscosity,
+        )
+    }
+
+    fn step(&mut self, delta_time: T) {
+        // Particles are predicted to the end of the step, so they collide
+        // with the obstacles where those are by then.
+        self.time += delta_time;
+        for obstacle in self.obstacles.iter_mut() {
+            obstacle.update(self.time);
+        }
+        let collision_manager = &self.collision_manager;
+        let obstacles = &self.obstacles;
+        let apply_boundaries = |particle: &mut Particle<T>| {
+            collision_manager.apply_boundary_conditions(particle);
+            for obstacle in obstacles.iter().filter(|obstacle| obstacle.is_active()) {
+                obstacle.apply_obstruction_boundary(particle);
+            }
+        };
+
+        self.dynamics_manager
+            .predict(&mut self.particles, &self.accelerations, delta_time);
+        self.particles
+            .par_update(|_, particle| apply_boundaries(particle));
+        self.rigid_body_manager.collide(&mut self.particles);
+
+        if self.cell_manager.update_store(&mut self.particles) {
+            self.dynamics_manager
+                .reorder(self.cell_manager.permutation());
+        }
+
+        self.densities
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(index, density)| {
+                *density = self.smoothed_interaction.calculate_density(
+                    index,
+                    self.cell_manager.get_neighbors(index),
+                    &self.particles,
+                )
+            });
+
+        self.non_pressure_accelerations
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(index, acceleration)| {
+                let mut new_acceleration =
+                    self.smoothed_interaction.calculate_viscosity_acceleration(
+                        index,
+                        self.cell_manager.get_neighbors(index),
+                        &self.particles,
+                        &self.densities,
+                    );
+                for external_attractor in self.external_attractors.iter() {
+                    new_acceleration += external_attractor.get_external_attraction_acceleration(
+                        &self.particles.get(index),
+                        self.densities[index],
+                    );
+                }
+                *acceleration = new_acceleration;
+            });
+
+        self.solver_statistics = self.solver.solve(SolverContext {
+            particles: &mut self.particles,
+            densities: &mut self.densities,
+            pressures: &mut self.pressures,
+            non_pressure_accelerations: &self.non_pressure_accelerations,
+            pressure_accelerations: &mut self.pressure_accelerations,
+            cell_manager: &self.cell_manager,
+            smoothed_interaction: &self.smoothed_interaction,
+            gravity: self.dynamics_manager.get_gravity(),
+            delta_time,
+            apply_boundaries: &apply_boundaries,
+        });
+
+        let gravity = self.dynamics_manager.get_gravity();
+        self.accelerations
+            .par_iter_mut()
+            .zip(self.non_pressure_accelerations.par_iter())
+            .zip(self.pressure_accelerations.par_iter())
+            .for_each(
+                |((acceleration, &non_pressure_acceleration), &pressure_acceleration)| {
+                    *acceleration = non_pressure_acceleration + pressure_acceleration + gravity;
+                },
+            );
+        self.rigid_body_manager.apply_fluid_forces(
+            &self.particles,
+            &self.densities,
+            &self.pressures,
+            &self.smoothed_interaction,
+            &mut self.accelerations,
+        );
+        self.dynamics_manager
+            .correct(&mut self.particles, &self.accelerations, delta_time);
+        self.particles
+            .par_update(|_, particle| apply_boundaries(particle));
+        self.rigid_body_manager.collide(&mut self.particles);
+        self.rigid_body_manager
+            .integrate(gravity, delta_time, &self.obstacles);
+    }
+
+    pub fn solver_statistics(&self) -> SolverStatistics {
+        self.solver_statistics
+    }
+
+    pub fn neighbor_statistics(&self) -> NeighborStatistics {
+        self.cell_manager.statistics()
+    }
+
+    // Simulated time since the start of the scene, in seconds.
+    pub fn time(&self) -> T {
+        self.time
+    }
+
+    pub fn delta_time(&self) -> T {
+        self.delta_time
+    }
+
+    pub fn sub_steps(&self) -> usize {
+        self.sub_steps
+    }
+
+    pub fn kinetic_energy(&self) -> T {
+        self.particles
+            .par_iter()
+            .map(|particle| T::from_f64(0.5) * particle.mass * particle.velocity.length_squared())
+            .sum()
+    }
+
+    // Relative to the origin, so only its changes are meaningful.
+    pub fn potential_energy(&self) -> T {
+        let gravity = self.dynamics_manager.get_gravity();
+        self.particles
+            .par_iter()
+            .map(|particle| -particle.mass * Vector2D::dot(gravity, particle.position))
+            .sum()
+    }
+
+    // Particles are kept in cell order, so their index changes between
+    // updates while their id stays the same.
+    pub fn particle_index(&self, particle_id: usize) -> usize {
+        self.cell_manager.get_particle_index(particle_id)
+    }
+
+    pub fn densities(&self) -> &[T] {
+        &self.densities
+    }
+
+    pub fn pressures(&self) -> &[T] {
+        &self.pressures
+    }
+
+    pub fn save_checkpoint(&self, path: impl AsRef<Path>) -> Result<(), CheckpointError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_checkpoint(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    // The simulation must be built from the scene the checkpoint was saved
+    // from. With Verlet lists the neighbor lists are rebuilt on the next
+    // update, so only runs without them continue bit-identically.
+    pub fn load_checkpoint(&mut self, path: impl AsRef<Path>) -> Result<(), CheckpointError> {
+        self.read_checkpoint(&mut BufReader::new(File::open(path)?))
+    }
+
+    pub fn write_checkpoint(&self, writer: &mut impl Write) -> Result<(), CheckpointError> {
+        CheckpointState {
+            particles: self.particles.to_particles(),
+            densities: self.densities.clone(),
+            pressures: self.pressures.clone(),
+            accelerations: self.accelerations.clone(),
+            non_pressure_accelerations: self.non_pressure_accelerations.clone(),
+            pressure_accelerations: self.pressure_accelerations.clone(),
+            time: self.time,
+            delta_time: self.delta_time,
+            is_gravity_on: self.dynamics_manager.is_gravity_on(),
+            active_dams: self
+                .dam_obstacles()
+                .map(|dam_obstacle| dam_obstacle.is_active())
+                .collect(),
+            attractors: self
+                .external_attractors
+                .iter()
+                .map(|external_attractor| AttractorState {
+                    position: external_attractor.position,
+                    active: external_attractor.active,
+                })
+                .collect(),
+            rigid_bodies: self
+                .rigid_bodies()
+                .iter()
+                .map(|rigid_body| RigidBodyState {
+                    pose: rigid_body.pose(),
+                    velocity: rigid_body.velocity(),
+                })
+                .collect(),
+        }
+        .write(writer)
+    }
+
+    // Leaves the simulation unchanged if the checkpoint cannot be read.
+    pub fn read_checkpoint(&mut self, reader: &mut impl Read) -> Result<(), CheckpointError> {
+        let state = CheckpointState::read(reader)?;
+        if state.particles.len() != self.particles.len() {
+            return Err(CheckpointError::Incompatible(format!(
+                "{} particles, the scene has {}",
+                state.particles.len(),
+                self.particles.len()
+            )));
+        }
+        let dam_count = self.dam_obstacles().count();
+        if state.active_dams.len() != dam_count {
+            return Err(CheckpointError::Incompatible(format!(
+                "{} dams, the scene has {}",
+                state.active_dams.len(),
+                dam_count
+            )));
+        }
+        if state.attractors.len() != self.external_attractors.len() {
+            return Err(CheckpointError::Incompatible(format!(
+                "{} attractors, the scene has {}",
+                state.attractors.len(),
+                self.external_attractors.len()
+            )));
+        }
+        if state.rigid_bodies.len() != self.rigid_bodies().len() {
+            return Err(CheckpointError::Incompatible(format!(
+                "{} rigid bodies, the scene has {}",
+                state.rigid_bodies.len(),
+                self.rigid_bodies().len()
+            )));
+        }
+        self.particles.update(&state.particles);
+        self.densities = state.densities;
+        self.pressures = state.pressures;
+        self.accelerations = state.accelerations;
+        self.non_pressure_accelerations = state.non_pressure_accelerations;
+        self.pressure_accelerations = state.pressure_accelerations;
+        self.time = state.time;
+        self.delta_time = state.delta_time;
+        for obstacle in self.obstacles.iter_mut() {
+            obstacle.update(self.time);
+        }
+        self.dynamics_manager.set_gravity_on(state.is_gravity_on);
+        for (dam_obstacle, is_dam_active) in self.dam_obstacles_mut().zip(state.active_dams) {
+            dam_obstacle.set_active(is_dam_active);
+        }
+        for (external_attractor, attractor) in
+            self.external_attractors.iter_mut().zip(state.attractors)
+        {
+            external_attractor.position = attractor.position;
+            external_attractor.active = attractor.active;
+        }
+        for (rigid_body, rigid_body_state) in self
+            .rigid_body_manager
+            .rigid_bodies_mut()
+            .iter_mut()
+            .zip(state.rigid_bodies)
+        {
+            rigid_body.set_state(rigid_body_state.pose, rigid_body_state.velocity);
+        }
+        self.cell_manager.reset(&self.particles);
+        Ok(())
+    }
+
+    pub fn toggle_gravity(&mut self) {
+        self.dynamics_manager.toggle_gravity();
+    }
+
+    pub fn break_dam(&mut self) {
+        for dam_obstacle in self.dam_obstacles_mut() {
+            dam_obstacle.set_active(false);
+        }
+    }
+
+    pub fn obstacles(&self) -> &[Box<dyn Obstacle<T> + Send + Sync>] {
+        &self.obstacles
+    }
+
+    pub fn obstacles_mut(&mut self) -> &mut [Box<dyn Obstacle<T> + Send + Sync>] {
+        &mut self.obstacles
+    }
+
+    // Returns the index of the obstacle, which is applied from the next step.
+    pub fn add_obstacle(&mut self, obstacle: Box<dyn Obstacle<T> + Send + Sync>) -> usize {
+        self.obstacles.push(obstacle);
+        self.obstacles.len() - 1
+    }
+
+    // Later obstacles move down one index.
+    pub fn remove_obstacle(&mut self, index: usize) -> Option<Box<dyn Obstacle<T> + Send + Sync>> {
+        (index < self.obstacles.len()).then(|| self.obstacles.remove(index))
+    }
+
+    pub fn rigid_bodies(&self) -> &[RigidBody<T>] {
+        self.rigid_body_manager.rigid_bodies()
+    }
+
+    pub fn rigid_bodies_mut(&mut self) -> &mut [RigidBody<T>] {
+        self.rigid_body_manager.rigid_bodies_mut()
+    }
+
+    // Returns the index of the body, which moves from the next step.
+    pub fn add_rigid_body(&mut self, rigid_body: RigidBody<T>) -> usize {
+        self.rigid_body_manager.add(rigid_body)
+    }
+
+    // Outlines of the active obstacles and the rigid bodies, to draw them.
+    pub fn outlines(&self) -> Vec<Outline<T>> {
+        self.obstacles
+            .iter()
+            .filter(|obstacle| obstacle.is_active())
+            .map(|obstacle| obstacle.outline())
+            .chain(
+                self.rigid_bodies()
+                    .iter()
+                    .map(|rigid_body| rigid_body.outline()),
+            )
+            .collect()
+    }
+
+    fn dam_obstacles(&self) -> impl Iterator<Item = &Box<dyn Obstacle<T> + Send + Sync>> {
+        self.obstacles
+            .iter()
+            .filter(|obstacle| obstacle.is_breakable())
+    }
+
+    fn dam_obstacles_mut(
+        &mut self,
+    ) -> impl Iterator<Item = &mut Box<dyn Obstacle<T> + Send + Sync>> {
+        self.obstacles
+            .iter_mut()
+            .filter(|obstacle| obstacle.is_breakable())
+    }
+
+    pub fn move_attractor(&mut self, position: Vector2D<T>) {
+        for external_attractor in self.pointer_attractors() {
+            external_attractor.position = position;
+        }
+    }
+
+    pub fn activate_attractor(&mut self) {
+        for external_attractor in self.pointer_attractors() {
+            external_attractor.active = true;
+        }
+    }
+
+    pub fn deactivate_attractor(&mut self) {
+        for external_attractor in self.pointer_attractors() {
+            external_attractor.active = false;
+        }
+    }
+
+    fn pointer_attractors(&mut self) -> impl Iterator<Item = &mut ExternalAttractor<T>> {
+        self.external_attractors
+            .iter_mut()
+            .filter(|external_attractor| external_attractor.follows_pointer)
+    }
+}
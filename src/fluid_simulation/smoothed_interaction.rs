@@ -1,73 +1,289 @@
-use super::smothing_kernels::sb_smoothing_kernel;
-use super::smothing_kernels::sb_smoothing_kernel_derivative;
-use crate::fluid_simulation::config::{Densities, Particles};
-use crate::fluid_simulation::smothing_kernels::spiky_smoothing_kernel;
-use crate::fluid_simulation::smothing_kernels::viscosity_smoothing_kernel_second_derivative;
-use rand::Rng;
+use super::kernels::kernel_trait::Kernel;
+use crate::fluid_simulation::equation_of_state::EquationOfState;
+use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::particle_store::ParticleStore;
+use crate::fluid_simulation::real::{Real, RealVector};
+use serde::Deserialize;
 use vector2d::Vector2D;
 
-pub struct SmoothedInteraction {
-    pressure_multiplier: f32,
-    target_density: f32,
-    smoothing_radius: f32,
-    viscosity: f32,
+// Neighbors are processed in batches of this size by the struct-of-arrays
+// loops, so the offsets and kernel terms fit in stack arrays.
+const NEIGHBOR_BATCH_SIZE: usize = 64;
+
+// Shared averages the two pressures and divides by both densities, as in
+// Müller et al. Symmetric uses p_i / rho_i^2 + p_j / rho_j^2, which makes the
+// pair forces equal and opposite so linear momentum is conserved.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PressureGradient {
+    Shared,
+    Symmetric,
+}
+
+impl PressureGradient {
+    // Shared applies about half the acceleration of Symmetric for the same
+    // pressures, which iterative solvers compensate for.
+    pub fn relative_stiffness<T: Real>(&self) -> T {
+        match self {
+            PressureGradient::Shared => T::from_f64(0.5),
+            PressureGradient::Symmetric => T::one(),
+        }
+    }
 }
 
-impl SmoothedInteraction {
+pub struct SmoothedInteraction<T: Real = f32> {
+    equation_of_state: EquationOfState<T>,
+    pressure_gradient: PressureGradient,
+    smoothing_radius: T,
+    viscosity: T,
+    density_kernel: Box<dyn Kernel<T>>,
+    pressure_kernel: Box<dyn Kernel<T>>,
+    viscosity_kernel: Box<dyn Kernel<T>>,
+    seed: u64,
+}
+
+impl<T: Real> SmoothedInteraction<T> {
     pub fn new(
-        pressure_multiplier: f32,
-        target_density: f32,
-        smoothing_radius: f32,
-        viscosity: f32,
+        equation_of_state: EquationOfState<T>,
+        pressure_gradient: PressureGradient,
+        smoothing_radius: T,
+        viscosity: T,
+        density_kernel: Box<dyn Kernel<T>>,
+        pressure_kernel: Box<dyn Kernel<T>>,
+        viscosity_kernel: Box<dyn Kernel<T>>,
     ) -> Self {
         SmoothedInteraction {
-            pressure_multiplier,
-            target_density,
+            equation_of_state,
+            pressure_gradient,
             smoothing_radius,
             viscosity,
+            density_kernel,
+            pressure_kernel,
+            viscosity_kernel,
+            seed: 0,
+        }
+    }
+
+    // The seed picks the directions that separate coincident particles.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn calculate_density(
+        &self,
+        particle_index: usize,
+        adjacent_particle_indices: &[usize],
+        store: &ParticleStore<T>,
+    ) -> T {
+        let mut offsets_x = [T::zero(); NEIGHBOR_BATCH_SIZE];
+        let mut offsets_y = [T::zero(); NEIGHBOR_BATCH_SIZE];
+        let mut influences = [T::zero(); NEIGHBOR_BATCH_SIZE];
+        let mut density = T::zero();
+        for batch in adjacent_particle_indices.chunks(NEIGHBOR_BATCH_SIZE) {
+            let influences = &mut influences[..batch.len()];
+            gather_offsets(
+                particle_index,
+                batch,
+                store,
+                &mut offsets_x[..batch.len()],
+                &mut offsets_y[..batch.len()],
+                influences,
+            );
+            self.density_kernel
+                .values(influences, self.smoothing_radius);
+            for (&influence, &iter_particle_index) in influences.iter().zip(batch) {
+                density += store.masses[iter_particle_index] * influence;
+            }
+        }
+        density
+    }
+
+    pub fn calculate_pressure_acceleration(
+        &self,
+        particle_index: usize,
+        adjacent_particle_indices: &[usize],
+        store: &ParticleStore<T>,
+        densities: &[T],
+        pressures: &[T],
+    ) -> Vector2D<T> {
+        let mut offsets_x = [T::zero(); NEIGHBOR_BATCH_SIZE];
+        let mut offsets_y = [T::zero(); NEIGHBOR_BATCH_SIZE];
+        let mut distances = [T::zero(); NEIGHBOR_BATCH_SIZE];
+        let mut slopes = [T::zero(); NEIGHBOR_BATCH_SIZE];
+        let mut acceleration = Vector2D::new(T::zero(), T::zero());
+        for batch in adjacent_particle_indices.chunks(NEIGHBOR_BATCH_SIZE) {
+            let count = batch.len();
+            gather_offsets(
+                particle_index,
+                batch,
+                store,
+                &mut offsets_x[..count],
+                &mut offsets_y[..count],
+                &mut distances[..count],
+            );
+            slopes[..count].copy_from_slice(&distances[..count]);
+            self.pressure_kernel
+                .gradients(&mut slopes[..count], self.smoothing_radius);
+            for (neighbor, &iter_particle_index) in batch.iter().enumerate() {
+                let slope = slopes[neighbor];
+                if iter_particle_index == particle_index || slope == T::zero() {
+                    continue;
+                }
+                let direction = if distances[neighbor] == T::zero() {
+                    coincident_direction(
+                        self.seed,
+                        store.ids[particle_index],
+                        store.ids[iter_particle_index],
+                    )
+                } else {
+                    Vector2D::new(offsets_x[neighbor], offsets_y[neighbor]) / distances[neighbor]
+                };
+                let pressure_term = self.calculate_pressure_term(
+                    pressures[particle_index],
+                    pressures[iter_particle_index],
+                    densities[particle_index],
+                    densities[iter_particle_index],
+                );
+                acceleration -=
+                    direction * pressure_term * slope * store.masses[iter_particle_index];
+            }
+        }
+        acceleration
+    }
+
+    pub fn calculate_viscosity_acceleration(
+        &self,
+        particle_index: usize,
+        adjacent_particle_indices: &[usize],
+        store: &ParticleStore<T>,
+        densities: &[T],
+    ) -> Vector2D<T> {
+        let mut offsets_x = [T::zero(); NEIGHBOR_BATCH_SIZE];
+        let mut offsets_y = [T::zero(); NEIGHBOR_BATCH_SIZE];
+        let mut influences = [T::zero(); NEIGHBOR_BATCH_SIZE];
+        let velocity_x = store.velocity_x[particle_index];
+        let velocity_y = store.velocity_y[particle_index];
+        let mut acceleration_x = T::zero();
+        let mut acceleration_y = T::zero();
+        for batch in adjacent_particle_indices.chunks(NEIGHBOR_BATCH_SIZE) {
+            let influences = &mut influences[..batch.len()];
+            gather_offsets(
+                particle_index,
+                batch,
+                store,
+                &mut offsets_x[..batch.len()],
+                &mut offsets_y[..batch.len()],
+                influences,
+            );
+            self.viscosity_kernel
+                .laplacians(influences, self.smoothing_radius);
+            for (&influence, &iter_particle_index) in influences.iter().zip(batch) {
+                if iter_particle_index == particle_index || influence == T::zero() {
+                    continue;
+                }
+                let weight = self.viscosity * store.masses[iter_particle_index] * influence
+                    / densities[iter_particle_index];
+                acceleration_x += (store.velocity_x[iter_particle_index] - velocity_x) * weight;
+                acceleration_y += (store.velocity_y[iter_particle_index] - velocity_y) * weight;
+            }
         }
+        Vector2D::new(acceleration_x, acceleration_y) / densities[particle_index]
+    }
+
+    // Pressure and viscous acceleration from a solid surface at the given
+    // distance along the normal, moving with the given velocity. The surface
+    // acts as a mirror image of the particle, which only pushes.
+    pub fn calculate_boundary_acceleration(
+        &self,
+        particle: &Particle<T>,
+        density: T,
+        pressure: T,
+        distance: T,
+        normal: Vector2D<T>,
+        boundary_velocity: Vector2D<T>,
+    ) -> Vector2D<T> {
+        let mirror_distance = T::from_f64(2.0) * distance;
+        let pressure = pressure.max(T::zero());
+        let pressure_term = self.calculate_pressure_term(pressure, pressure, density, density);
+        let slope = self
+            .pressure_kernel
+            .gradient(mirror_distance, self.smoothing_radius);
+        let influence = self
+            .viscosity_kernel
+            .laplacian(mirror_distance, self.smoothing_radius);
+        -normal * pressure_term * slope * particle.mass
+            + (boundary_velocity - particle.velocity) * self.viscosity * particle.mass * influence
+                / (density * density)
     }
 
-    pub fn calculate_acceleration(
+    // The _from_particles forms compute the same over particles kept as
+    // structs, as the reference the layout benchmark compares with.
+    pub fn calculate_pressure_acceleration_from_particles(
         &self,
         particle_index: usize,
         adjacent_particle_indices: impl Iterator<Item = usize>,
-        particles: &Particles,
-        densities: &Densities,
-    ) -> Vector2D<f32> {
-        let mut acceleration = Vector2D::new(0.0, 0.0);
+        particles: &[Particle<T>],
+        densities: &[T],
+        pressures: &[T],
+    ) -> Vector2D<T> {
+        let mut acceleration = Vector2D::new(T::zero(), T::zero());
         for iter_particle_index in adjacent_particle_indices {
             if particles[particle_index].id == particles[iter_particle_index].id {
                 continue;
             }
-            let mut relative_position =
+            let relative_position =
                 particles[particle_index].position - particles[iter_particle_index].position;
             let distance = relative_position.length();
-            if distance == 0.0 {
-                let mut rng = rand::thread_rng();
-                relative_position =
-                    Vector2D::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
-            }
-            // pressure
-            let slope = sb_smoothing_kernel_derivative(distance, self.smoothing_radius);
-            if slope == 0.0 {
+            let direction = if distance == T::zero() {
+                coincident_direction(
+                    self.seed,
+                    particles[particle_index].id,
+                    particles[iter_particle_index].id,
+                )
+            } else {
+                relative_position / distance
+            };
+            let slope = self
+                .pressure_kernel
+                .gradient(distance, self.smoothing_radius);
+            if slope == T::zero() {
                 continue;
             }
-            let shared_pressure = self.calculate_shared_pressure(
+            let pressure_term = self.calculate_pressure_term(
+                pressures[particle_index],
+                pressures[iter_particle_index],
                 densities[particle_index],
                 densities[iter_particle_index],
             );
-            acceleration += relative_position.normalise()
-                * shared_pressure
-                * slope
-                * particles[iter_particle_index].mass
-                / densities[iter_particle_index];
+            acceleration -= direction * pressure_term * slope * particles[iter_particle_index].mass;
+        }
+        acceleration
+    }
 
-            // vicosity
+    pub fn calculate_viscosity_acceleration_from_particles(
+        &self,
+        particle_index: usize,
+        adjacent_particle_indices: impl Iterator<Item = usize>,
+        particles: &[Particle<T>],
+        densities: &[T],
+    ) -> Vector2D<T> {
+        let mut acceleration = Vector2D::new(T::zero(), T::zero());
+        for iter_particle_index in adjacent_particle_indices {
+            if particles[particle_index].id == particles[iter_particle_index].id {
+                continue;
+            }
+            let distance = (particles[particle_index].position
+                - particles[iter_particle_index].position)
+                .length();
+            let influence = self
+                .viscosity_kernel
+                .laplacian(distance, self.smoothing_radius);
+            if influence == T::zero() {
+                continue;
+            }
             let relative_speed =
                 particles[iter_particle_index].velocity - particles[particle_index].velocity;
-            let influence =
-                viscosity_smoothing_kernel_second_derivative(distance, self.smoothing_radius);
             acceleration +=
                 relative_speed * self.viscosity * particles[iter_particle_index].mass * influence
                     / densities[iter_particle_index];
@@ -75,20 +291,19 @@ impl SmoothedInteraction {
         acceleration / densities[particle_index]
     }
 
-    pub fn calculate_density(
+    pub fn calculate_density_from_particles(
         &self,
         particle_index: usize,
         adjacent_particle_indices: impl Iterator<Item = usize>,
-        particles: &Particles,
-    ) -> f32 {
-        let mut density =
-            particles[particle_index].mass * spiky_smoothing_kernel(0.0, self.smoothing_radius);
+        particles: &[Particle<T>],
+    ) -> T {
+        let mut density = T::zero();
         for iter_particle_index in adjacent_particle_indices {
             let relative_position =
                 particles[particle_index].position - particles[iter_particle_index].position;
             let distance = relative_position.length();
-            let influence = sb_smoothing_kernel(distance, self.smoothing_radius);
-            if influence == 0.0 {
+            let influence = self.density_kernel.value(distance, self.smoothing_radius);
+            if influence == T::zero() {
                 continue;
             }
             density += particles[iter_particle_index].mass * influence;
@@ -96,12 +311,94 @@ impl SmoothedInteraction {
         density
     }
 
-    fn calculate_shared_pressure(&self, density_a: f32, density_b: f32) -> f32 {
-        (self.convert_density_to_pressure(density_a) + self.convert_density_to_pressure(density_b))
-            / 2.0
+    pub fn convert_density_to_pressure(&self, density: T) -> T {
+        self.equation_of_state.pressure(density)
+    }
+
+    pub fn target_density(&self) -> T {
+        self.equation_of_state.target_density
+    }
+
+    pub fn smoothing_radius(&self) -> T {
+        self.smoothing_radius
     }
 
-    fn convert_density_to_pressure(&self, density: f32) -> f32 {
-        -self.pressure_multiplier * (density - self.target_density)
+    pub fn viscosity(&self) -> T {
+        self.viscosity
+    }
+
+    pub fn pressure_kernel(&self) -> &dyn Kernel<T> {
+        self.pressure_kernel.as_ref()
+    }
+
+    pub fn pressure_gradient(&self) -> PressureGradient {
+        self.pressure_gradient
+    }
+
+    fn calculate_pressure_term(
+        &self,
+        pressure_a: T,
+        pressure_b: T,
+        density_a: T,
+        density_b: T,
+    ) -> T {
+        match self.pressure_gradient {
+            PressureGradient::Shared => {
+                (pressure_a + pressure_b) / (T::from_f64(2.0) * density_a * density_b)
+            }
+            PressureGradient::Symmetric => {
+                pressure_a / density_a.powi(2) + pressure_b / density_b.powi(2)
+            }
+        }
+    }
+}
+
+// Unit vector pushing a particle away from another one at the same position.
+// It only depends on the seed and the two ids, so it is the same on every run
+// and thread, and it is reversed for the other particle of the pair.
+fn coincident_direction<T: Real>(seed: u64, particle_id: usize, other_id: usize) -> Vector2D<T> {
+    let (low_id, high_id) = if particle_id < other_id {
+        (particle_id, other_id)
+    } else {
+        (other_id, particle_id)
+    };
+    let hash = mix_bits(mix_bits(seed ^ low_id as u64) ^ high_id as u64);
+    let angle = (hash >> 11) as f64 / (1u64 << 53) as f64 * std::f64::consts::TAU;
+    let direction = Vector2D::new(T::from_f64(angle.cos()), T::from_f64(angle.sin()));
+    if particle_id < other_id {
+        direction
+    } else {
+        -direction
+    }
+}
+
+// SplitMix64 finalizer.
+fn mix_bits(value: u64) -> u64 {
+    let mut value = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^ (value >> 31)
+}
+
+// Offsets from the neighbors in batch to the particle, and their lengths.
+fn gather_offsets<T: Real>(
+    particle_index: usize,
+    batch: &[usize],
+    store: &ParticleStore<T>,
+    offsets_x: &mut [T],
+    offsets_y: &mut [T],
+    distances: &mut [T],
+) {
+    let position_x = store.position_x[particle_index];
+    let position_y = store.position_y[particle_index];
+    for (((offset_x, offset_y), distance), &iter_particle_index) in offsets_x
+        .iter_mut()
+        .zip(offsets_y.iter_mut())
+        .zip(distances.iter_mut())
+        .zip(batch)
+    {
+        *offset_x = position_x - store.position_x[iter_particle_index];
+        *offset_y = position_y - store.position_y[iter_particle_index];
+        *distance = (*offset_x * *offset_x + *offset_y * *offset_y).sqrt();
     }
 }
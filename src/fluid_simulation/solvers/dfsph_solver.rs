@@ -0,0 +1,328 @@
+use super::solver_trait::{
+    calculate_average_density_error, Solver, SolverContext, SolverStatistics,
+};
+use crate::fluid_simulation::cell_manager::CellManager;
+use crate::fluid_simulation::particle_store::ParticleStore;
+use crate::fluid_simulation::real::{Real, RealVector};
+use crate::fluid_simulation::smoothed_interaction::SmoothedInteraction;
+use rayon::prelude::*;
+use vector2d::Vector2D;
+
+const MINIMUM_DIVERGENCE_ITERATIONS: usize = 1;
+const MINIMUM_DENSITY_ITERATIONS: usize = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Correction {
+    Divergence,
+    Density,
+}
+
+// Divergence-free SPH (Bender & Koschier, 2015). The divergence solve removes
+// the compressing part of the current velocities, then the density solve
+// corrects velocities predicted from the non-pressure accelerations until the
+// predicted compression error is below the tolerance. Both solves derive
+// their pressures from per-particle stiffness factors of the current
+// neighborhood instead of the equation of state.
+pub struct DfsphSolver<T: Real = f32> {
+    density_tolerance: f32,
+    divergence_tolerance: f32,
+    max_iterations: usize,
+    factors: Vec<T>,
+    predicted_particles: ParticleStore<T>,
+    predicted_densities: Vec<T>,
+    pressures: Vec<T>,
+    corrections: Vec<Vector2D<T>>,
+    accumulated_pressures: Vec<T>,
+    accumulated_accelerations: Vec<Vector2D<T>>,
+}
+
+impl<T: Real> DfsphSolver<T> {
+    pub fn new(density_tolerance: f32, divergence_tolerance: f32, max_iterations: usize) -> Self {
+        DfsphSolver {
+            density_tolerance,
+            divergence_tolerance,
+            max_iterations,
+            factors: Vec::new(),
+            predicted_particles: ParticleStore::new(0),
+            predicted_densities: Vec::new(),
+            pressures: Vec::new(),
+            corrections: Vec::new(),
+            accumulated_pressures: Vec::new(),
+            accumulated_accelerations: Vec::new(),
+        }
+    }
+
+    fn resize_buffers(&mut self, particle_count: usize) {
+        let zero_vector = Vector2D::new(T::zero(), T::zero());
+        self.factors.resize(particle_count, T::zero());
+        self.predicted_densities.resize(particle_count, T::zero());
+        self.pressures.resize(particle_count, T::zero());
+        self.corrections.resize(particle_count, zero_vector);
+        self.accumulated_pressures.resize(particle_count, T::zero());
+        self.accumulated_accelerations
+            .resize(particle_count, zero_vector);
+    }
+
+    // Applies pressure corrections to the particle velocities until the
+    // density error predicted from them is within tolerance. The pressures and
+    // accelerations of all iterations are summed in the accumulated buffers.
+    fn correct_velocities(
+        &mut self,
+        correction: Correction,
+        particles: &mut ParticleStore<T>,
+        densities: &[T],
+        cell_manager: &CellManager<T>,
+        smoothed_interaction: &SmoothedInteraction<T>,
+        delta_time: T,
+    ) -> (usize, f32) {
+        let target_density = smoothed_interaction.target_density();
+        let stiffness_scale = smoothed_interaction
+            .pressure_gradient()
+            .relative_stiffness::<T>();
+        let (tolerance, minimum_iterations) = match correction {
+            Correction::Divergence => (self.divergence_tolerance, MINIMUM_DIVERGENCE_ITERATIONS),
+            Correction::Density => (self.density_tolerance, MINIMUM_DENSITY_ITERATIONS),
+        };
+        self.accumulated_pressures.fill(T::zero());
+        self.accumulated_accelerations
+            .fill(Vector2D::new(T::zero(), T::zero()));
+
+        let mut iterations = 0;
+        loop {
+            let current_particles = &*particles;
+            self.predicted_densities
+                .par_iter_mut()
+                .enumerate()
+                .for_each(|(index, predicted_density)| {
+                    let density_change = calculate_density_change(
+                        index,
+                        cell_manager.get_neighbors(index),
+                        current_particles,
+                        smoothed_interaction,
+                    );
+                    *predicted_density = match correction {
+                        // Under-dense particles, e.g. at the free surface, may
+                        // still compress until they reach the target density.
+                        Correction::Divergence if densities[index] < target_density => {
+                            target_density
+                        }
+                        Correction::Divergence => target_density + density_change * delta_time,
+                        Correction::Density => densities[index] + density_change * delta_time,
+                    };
+                });
+            let error = calculate_average_density_error(&self.predicted_densities, target_density);
+            if (iterations >= minimum_iterations && error <= tolerance)
+                || iterations >= self.max_iterations
+            {
+                return (iterations, error);
+            }
+
+            self.pressures
+                .par_iter_mut()
+                .enumerate()
+                .for_each(|(index, pressure)| {
+                    let stiffness = (self.predicted_densities[index] - target_density)
+                        .max(T::zero())
+                        * self.factors[index]
+                        / delta_time.powi(2);
+                    *pressure = stiffness * densities[index] / stiffness_scale;
+                });
+
+            let pressures = &self.pressures;
+            self.corrections
+                .par_iter_mut()
+                .enumerate()
+                .for_each(|(index, acceleration)| {
+                    *acceleration = smoothed_interaction.calculate_pressure_acceleration(
+                        index,
+                        cell_manager.get_neighbors(index),
+                        current_particles,
+                        densities,
+                        pressures,
+                    );
+                });
+
+            let corrections = &self.corrections;
+            add_velocity_changes(particles, corrections, delta_time);
+            self.accumulated_pressures
+                .par_iter_mut()
+                .zip(pressures.par_iter())
+                .for_each(|(accumulated_pressure, &pressure)| *accumulated_pressure += pressure);
+            self.accumulated_accelerations
+                .par_iter_mut()
+                .zip(corrections.par_iter())
+                .for_each(|(accumulated_acceleration, &acceleration)| {
+                    *accumulated_acceleration += acceleration
+                });
+            iterations += 1;
+        }
+    }
+}
+
+impl<T: Real> Solver<T> for DfsphSolver<T> {
+    fn solve(&mut self, context: SolverContext<T>) -> SolverStatistics {
+        let smoothed_interaction = context.smoothed_interaction;
+        let cell_manager = context.cell_manager;
+        let densities = &*context.densities;
+        let non_pressure_accelerations = context.non_pressure_accelerations;
+        let gravity = context.gravity;
+        let delta_time = context.delta_time;
+        self.resize_buffers(context.particles.len());
+
+        let particles = &*context.particles;
+        self.factors
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(index, factor)| {
+                *factor = calculate_stiffness_factor(
+                    index,
+                    cell_manager.get_neighbors(index),
+                    particles,
+                    densities[index],
+                    smoothed_interaction,
+                );
+            });
+
+        let (divergence_iterations, divergence_error) = self.correct_velocities(
+            Correction::Divergence,
+            context.particles,
+            densities,
+            cell_manager,
+            smoothed_interaction,
+            delta_time,
+        );
+
+        let mut predicted_particles =
+            std::mem::replace(&mut self.predicted_particles, ParticleStore::new(0));
+        predicted_particles.clone_from(context.particles);
+        // The corrections buffer is free until the density solve.
+        self.corrections
+            .par_iter_mut()
+            .zip(non_pressure_accelerations.par_iter())
+            .for_each(|(acceleration, &non_pressure_acceleration)| {
+                *acceleration = non_pressure_acceleration + gravity
+            });
+        add_velocity_changes(&mut predicted_particles, &self.corrections, delta_time);
+        let (iterations, density_error) = self.correct_velocities(
+            Correction::Density,
+            &mut predicted_particles,
+            densities,
+            cell_manager,
+            smoothed_interaction,
+            delta_time,
+        );
+        self.predicted_particles = predicted_particles;
+
+        context
+            .pressures
+            .copy_from_slice(&self.accumulated_pressures);
+        context
+            .pressure_accelerations
+            .copy_from_slice(&self.accumulated_accelerations);
+        SolverStatistics {
+            iterations,
+            density_error,
+            divergence_iterations,
+            divergence_error,
+        }
+    }
+
+    fn limits_timestep(&self) -> bool {
+        false
+    }
+}
+
+fn add_velocity_changes<T: Real>(
+    particles: &mut ParticleStore<T>,
+    accelerations: &[Vector2D<T>],
+    delta_time: T,
+) {
+    particles
+        .velocity_x
+        .par_iter_mut()
+        .zip(particles.velocity_y.par_iter_mut())
+        .zip(accelerations.par_iter())
+        .for_each(|((velocity_x, velocity_y), &acceleration)| {
+            *velocity_x += acceleration.x * delta_time;
+            *velocity_y += acceleration.y * delta_time;
+        });
+}
+
+// Material derivative of the density, sum_j m_j (v_i - v_j) . grad W_ij.
+fn calculate_density_change<T: Real>(
+    particle_index: usize,
+    adjacent_particle_indices: &[usize],
+    particles: &ParticleStore<T>,
+    smoothed_interaction: &SmoothedInteraction<T>,
+) -> T {
+    let velocity = particles.velocity(particle_index);
+    let mut density_change = T::zero();
+    for &iter_particle_index in adjacent_particle_indices {
+        if let Some(gradient) = calculate_kernel_gradient(
+            particle_index,
+            iter_particle_index,
+            particles,
+            smoothed_interaction,
+        ) {
+            let relative_velocity = velocity - particles.velocity(iter_particle_index);
+            density_change +=
+                particles.masses[iter_particle_index] * Vector2D::dot(relative_velocity, gradient);
+        }
+    }
+    density_change
+}
+
+// The DFSPH factor alpha_i = rho_i / (|sum_j m_j grad W_ij|^2 + sum_j |m_j grad W_ij|^2).
+// Particles without neighbors get no pressure.
+fn calculate_stiffness_factor<T: Real>(
+    particle_index: usize,
+    adjacent_particle_indices: &[usize],
+    particles: &ParticleStore<T>,
+    density: T,
+    smoothed_interaction: &SmoothedInteraction<T>,
+) -> T {
+    let mut gradient_sum = Vector2D::new(T::zero(), T::zero());
+    let mut gradient_dot_sum = T::zero();
+    for &iter_particle_index in adjacent_particle_indices {
+        if let Some(gradient) = calculate_kernel_gradient(
+            particle_index,
+            iter_particle_index,
+            particles,
+            smoothed_interaction,
+        ) {
+            let weighted_gradient = gradient * particles.masses[iter_particle_index];
+            gradient_sum += weighted_gradient;
+            gradient_dot_sum += Vector2D::dot(weighted_gradient, weighted_gradient);
+        }
+    }
+    let denominator = Vector2D::dot(gradient_sum, gradient_sum) + gradient_dot_sum;
+    if denominator > T::zero() {
+        density / denominator
+    } else {
+        T::zero()
+    }
+}
+
+fn calculate_kernel_gradient<T: Real>(
+    particle_index: usize,
+    other_particle_index: usize,
+    particles: &ParticleStore<T>,
+    smoothed_interaction: &SmoothedInteraction<T>,
+) -> Option<Vector2D<T>> {
+    if particle_index == other_particle_index {
+        return None;
+    }
+    let relative_position =
+        particles.position(particle_index) - particles.position(other_particle_index);
+    let distance = relative_position.length();
+    if distance == T::zero() {
+        return None;
+    }
+    let slope = smoothed_interaction
+        .pressure_kernel()
+        .gradient(distance, smoothed_interaction.smoothing_radius());
+    if slope == T::zero() {
+        return None;
+    }
+    Some(relative_position.normalise() * slope)
+}
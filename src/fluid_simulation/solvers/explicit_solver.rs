@@ -0,0 +1,49 @@
+use super::solver_trait::{
+    calculate_average_density_error, Solver, SolverContext, SolverStatistics,
+};
+use crate::fluid_simulation::real::Real;
+use rayon::prelude::*;
+
+// Single pass: pressures follow directly from the equation of state.
+pub struct ExplicitSolver {}
+
+impl<T: Real> Solver<T> for ExplicitSolver {
+    fn solve(&mut self, context: SolverContext<T>) -> SolverStatistics {
+        let smoothed_interaction = context.smoothed_interaction;
+        let cell_manager = context.cell_manager;
+        let particles = &*context.particles;
+        let densities = &*context.densities;
+
+        context
+            .pressures
+            .par_iter_mut()
+            .zip(densities.par_iter())
+            .for_each(|(pressure, &density)| {
+                *pressure = smoothed_interaction.convert_density_to_pressure(density)
+            });
+
+        let pressures = &*context.pressures;
+        context
+            .pressure_accelerations
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(index, acceleration)| {
+                *acceleration = smoothed_interaction.calculate_pressure_acceleration(
+                    index,
+                    cell_manager.get_neighbors(index),
+                    particles,
+                    densities,
+                    pressures,
+                );
+            });
+
+        SolverStatistics {
+            iterations: 1,
+            density_error: calculate_average_density_error(
+                densities,
+                smoothed_interaction.target_density(),
+            ),
+            ..SolverStatistics::default()
+        }
+    }
+}
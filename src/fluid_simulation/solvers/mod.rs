@@ -0,0 +1,5 @@
+pub mod dfsph_solver;
+pub mod explicit_solver;
+pub mod pcisph_solver;
+pub mod solver_trait;
+pub mod solver_type;
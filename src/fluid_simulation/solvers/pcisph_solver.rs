@@ -0,0 +1,175 @@
+use super::solver_trait::{
+    calculate_average_density_error, Solver, SolverContext, SolverStatistics,
+};
+use crate::fluid_simulation::particle_store::ParticleStore;
+use crate::fluid_simulation::real::{Real, RealVector};
+use crate::fluid_simulation::smoothed_interaction::SmoothedInteraction;
+use rayon::prelude::*;
+use vector2d::Vector2D;
+
+const MINIMUM_ITERATIONS: usize = 3;
+
+// Predictive-corrective incompressible SPH (Solenthaler & Pajarola, 2009).
+// Pressures are corrected from the density error of predicted positions until
+// the average error drops below the tolerance.
+pub struct PcisphSolver<T: Real = f32> {
+    density_tolerance: f32,
+    max_iterations: usize,
+    particle_mass: T,
+    gradient_sum_squared: T,
+    predicted_particles: ParticleStore<T>,
+    predicted_densities: Vec<T>,
+}
+
+impl<T: Real> PcisphSolver<T> {
+    pub fn new(
+        density_tolerance: f32,
+        max_iterations: usize,
+        particle_mass: T,
+        smoothed_interaction: &SmoothedInteraction<T>,
+    ) -> Self {
+        PcisphSolver {
+            density_tolerance,
+            max_iterations,
+            particle_mass,
+            gradient_sum_squared: calculate_prototype_gradient_sum_squared(
+                particle_mass,
+                smoothed_interaction,
+            ),
+            predicted_particles: ParticleStore::new(0),
+            predicted_densities: Vec::new(),
+        }
+    }
+
+    fn calculate_pressure_coefficient(
+        &self,
+        smoothed_interaction: &SmoothedInteraction<T>,
+        delta_time: T,
+    ) -> T {
+        let stiffness_scale = smoothed_interaction
+            .pressure_gradient()
+            .relative_stiffness::<T>();
+        let beta = T::from_f64(2.0) * stiffness_scale * (delta_time * self.particle_mass).powi(2)
+            / smoothed_interaction.target_density().powi(2);
+        T::one() / (beta * self.gradient_sum_squared)
+    }
+}
+
+impl<T: Real> Solver<T> for PcisphSolver<T> {
+    fn solve(&mut self, context: SolverContext<T>) -> SolverStatistics {
+        let smoothed_interaction = context.smoothed_interaction;
+        let cell_manager = context.cell_manager;
+        let particles = &*context.particles;
+        let non_pressure_accelerations = context.non_pressure_accelerations;
+        let gravity = context.gravity;
+        let delta_time = context.delta_time;
+        let apply_boundaries = context.apply_boundaries;
+        let target_density = smoothed_interaction.target_density();
+        let pressure_coefficient =
+            self.calculate_pressure_coefficient(smoothed_interaction, delta_time);
+
+        self.predicted_particles.clone_from(particles);
+        self.predicted_densities
+            .resize(particles.len(), target_density);
+        context.pressures.fill(T::zero());
+        context
+            .pressure_accelerations
+            .fill(Vector2D::new(T::zero(), T::zero()));
+
+        let mut statistics = SolverStatistics::default();
+        while statistics.iterations < self.max_iterations {
+            let pressure_accelerations = &*context.pressure_accelerations;
+            self.predicted_particles
+                .par_update(|index, predicted_particle| {
+                    let acceleration =
+                        non_pressure_accelerations[index] + pressure_accelerations[index] + gravity;
+                    predicted_particle.velocity =
+                        particles.velocity(index) + acceleration * delta_time;
+                    predicted_particle.position =
+                        particles.position(index) + predicted_particle.velocity * delta_time;
+                    apply_boundaries(predicted_particle);
+                });
+
+            let predicted_particles = &self.predicted_particles;
+            self.predicted_densities
+                .par_iter_mut()
+                .enumerate()
+                .for_each(|(index, density)| {
+                    *density = smoothed_interaction.calculate_density(
+                        index,
+                        cell_manager.get_neighbors(index),
+                        predicted_particles,
+                    )
+                });
+            let predicted_densities = &self.predicted_densities;
+
+            context
+                .pressures
+                .par_iter_mut()
+                .zip(predicted_densities.par_iter())
+                .for_each(|(pressure, &density)| {
+                    *pressure = (*pressure + pressure_coefficient * (density - target_density))
+                        .max(T::zero());
+                });
+
+            let pressures = &*context.pressures;
+            context
+                .pressure_accelerations
+                .par_iter_mut()
+                .enumerate()
+                .for_each(|(index, acceleration)| {
+                    *acceleration = smoothed_interaction.calculate_pressure_acceleration(
+                        index,
+                        cell_manager.get_neighbors(index),
+                        particles,
+                        predicted_densities,
+                        pressures,
+                    );
+                });
+
+            statistics.iterations += 1;
+            statistics.density_error =
+                calculate_average_density_error(predicted_densities, target_density);
+            if statistics.iterations >= MINIMUM_ITERATIONS
+                && statistics.density_error <= self.density_tolerance
+            {
+                break;
+            }
+        }
+        statistics
+    }
+
+    fn limits_timestep(&self) -> bool {
+        false
+    }
+}
+
+// Sums of kernel gradients over a prototype particle with a filled
+// neighborhood on a square lattice at rest spacing.
+fn calculate_prototype_gradient_sum_squared<T: Real>(
+    particle_mass: T,
+    smoothed_interaction: &SmoothedInteraction<T>,
+) -> T {
+    let smoothing_radius = smoothed_interaction.smoothing_radius();
+    let kernel = smoothed_interaction.pressure_kernel();
+    let spacing = (particle_mass / smoothed_interaction.target_density()).sqrt();
+    let support_radius = kernel.support_radius(smoothing_radius);
+    let steps = (support_radius / spacing).ceil().as_f64() as i32;
+    let mut gradient_sum = Vector2D::new(T::zero(), T::zero());
+    let mut gradient_dot_sum = T::zero();
+    for column in -steps..=steps {
+        for row in -steps..=steps {
+            let neighbor_position =
+                Vector2D::new(T::from_f64(column as f64), T::from_f64(row as f64)) * spacing;
+            let distance = neighbor_position.length();
+            if distance == T::zero() || distance >= support_radius {
+                continue;
+            }
+            let gradient =
+                -neighbor_position.normalise() * kernel.gradient(distance, smoothing_radius);
+            gradient_sum += gradient;
+            gradient_dot_sum += Vector2D::dot(gradient, gradient);
+        }
+    }
+    Vector2D::dot(gradient_sum, gradient_sum) + gradient_dot_sum
+}
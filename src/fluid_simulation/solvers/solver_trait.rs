@@ -0,0 +1,56 @@
+use crate::fluid_simulation::cell_manager::CellManager;
+use crate::fluid_simulation::particle::Particle;
+use crate::fluid_simulation::particle_store::ParticleStore;
+use crate::fluid_simulation::real::Real;
+use crate::fluid_simulation::smoothed_interaction::SmoothedInteraction;
+use vector2d::Vector2D;
+
+pub struct SolverContext<'a, T: Real = f32> {
+    pub particles: &'a mut ParticleStore<T>,
+    pub densities: &'a mut [T],
+    pub pressures: &'a mut [T],
+    pub non_pressure_accelerations: &'a [Vector2D<T>],
+    pub pressure_accelerations: &'a mut [Vector2D<T>],
+    pub cell_manager: &'a CellManager<T>,
+    pub smoothed_interaction: &'a SmoothedInteraction<T>,
+    pub gravity: Vector2D<T>,
+    pub delta_time: T,
+    pub apply_boundaries: &'a (dyn Fn(&mut Particle<T>) + Sync),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SolverStatistics {
+    pub iterations: usize,
+    pub density_error: f32,
+    pub divergence_iterations: usize,
+    pub divergence_error: f32,
+}
+
+// A pressure solver turns the densities of the current step into pressures and
+// pressure accelerations. Non-pressure accelerations (viscosity, attractors)
+// and gravity are given so that predictive solvers can advance particles, and
+// apply_boundaries keeps those predictions inside the walls and obstacles.
+// Statistics are reported in f32 whatever the precision of the simulation.
+pub trait Solver<T: Real = f32>: Send + Sync {
+    fn solve(&mut self, context: SolverContext<T>) -> SolverStatistics;
+
+    // Iterative solvers pick pressures for the timestep they are given, so
+    // their pressure accelerations grow as the timestep shrinks and must not
+    // feed back into the adaptive timestep.
+    fn limits_timestep(&self) -> bool {
+        true
+    }
+}
+
+// Only compression counts as error: particles near the free surface are
+// naturally under-dense.
+pub fn calculate_average_density_error<T: Real>(densities: &[T], target_density: T) -> f32 {
+    if densities.is_empty() {
+        return 0.0;
+    }
+    let total_error: T = densities
+        .iter()
+        .map(|&density| (density - target_density).max(T::zero()))
+        .sum();
+    (total_error / (T::from_f64(densities.len() as f64) * target_density)).as_f32()
+}
@@ -0,0 +1,41 @@
+use super::dfsph_solver::DfsphSolver;
+use super::explicit_solver::ExplicitSolver;
+use super::pcisph_solver::PcisphSolver;
+use super::solver_trait::Solver;
+use crate::fluid_simulation::real::Real;
+use crate::fluid_simulation::smoothed_interaction::SmoothedInteraction;
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SolverType {
+    Explicit,
+    Pcisph,
+    Dfsph,
+}
+
+impl SolverType {
+    pub fn build<T: Real>(
+        &self,
+        density_tolerance: f32,
+        divergence_tolerance: f32,
+        max_iterations: usize,
+        particle_mass: T,
+        smoothed_interaction: &SmoothedInteraction<T>,
+    ) -> Box<dyn Solver<T>> {
+        match self {
+            SolverType::Explicit => Box::new(ExplicitSolver {}),
+            SolverType::Pcisph => Box::new(PcisphSolver::new(
+                density_tolerance,
+                max_iterations,
+                particle_mass,
+                smoothed_interaction,
+            )),
+            SolverType::Dfsph => Box::new(DfsphSolver::new(
+                density_tolerance,
+                divergence_tolerance,
+                max_iterations,
+            )),
+        }
+    }
+}
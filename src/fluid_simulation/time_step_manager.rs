@@ -0,0 +1,76 @@
+use crate::fluid_simulation::real::Real;
+
+pub struct TimeStepManager<T: Real = f32> {
+    frame_time: T,
+    is_adaptive: bool,
+    cfl_factor: T,
+    force_factor: T,
+    viscosity_factor: T,
+    min_delta_time: T,
+    max_delta_time: T,
+}
+
+impl<T: Real> TimeStepManager<T> {
+    pub fn new(
+        frame_time: T,
+        is_adaptive: bool,
+        cfl_factor: T,
+        force_factor: T,
+        viscosity_factor: T,
+        min_delta_time: T,
+        max_delta_time: T,
+    ) -> Self {
+        TimeStepManager {
+            frame_time,
+            is_adaptive,
+            cfl_factor,
+            force_factor,
+            viscosity_factor,
+            min_delta_time,
+            max_delta_time,
+        }
+    }
+
+    pub fn frame_time(&self) -> T {
+        self.frame_time
+    }
+
+    // Largest stable timestep for the current state: particles travel at most
+    // cfl_factor * h per step, the fastest acceleration acts over
+    // force_factor * sqrt(h / a), and viscous diffusion over
+    // viscosity_factor * h^2 / nu. The result is bounded by the min/max dt.
+    pub fn calculate_delta_time(
+        &self,
+        max_speed: T,
+        max_acceleration: T,
+        smoothing_radius: T,
+        kinematic_viscosity: T,
+    ) -> T {
+        if !self.is_adaptive {
+            return self.frame_time;
+        }
+        let mut delta_time = self.max_delta_time;
+        if max_speed > T::zero() {
+            delta_time = delta_time.min(self.cfl_factor * smoothing_radius / max_speed);
+        }
+        if max_acceleration > T::zero() {
+            delta_time =
+                delta_time.min(self.force_factor * (smoothing_radius / max_acceleration).sqrt());
+        }
+        if kinematic_viscosity > T::zero() {
+            delta_time = delta_time
+                .min(self.viscosity_factor * smoothing_radius.powi(2) / kinematic_viscosity);
+        }
+        delta_time.max(self.min_delta_time)
+    }
+
+    // Fits the next sub-step into what is left of the frame. A step that
+    // would leave less than min_delta_time is stretched to end the frame.
+    pub fn calculate_sub_step(&self, stable_delta_time: T, remaining_time: T) -> T {
+        if remaining_time - stable_delta_time < self.min_delta_time {
+            remaining_time
+        } else {
+            stable_delta_time
+        }
+    }
+}
@@ -0,0 +1,294 @@
+//! Particle output for ParaView. Every interval-th step is written as one
+//! file per format, with the particles as vertex cells and their velocity,
+//! density, pressure, speed and id as point data. A `.pvd` collection next to
+//! the files indexes them by simulated time, so the run can be opened as one
+//! time series. Positions are in simulation coordinates, with y pointing down.
+
+use crate::fluid_simulation::real::Real;
+use crate::fluid_simulation::recording::Frame;
+use crate::fluid_simulation::simulation::Simulation;
+use serde::Deserialize;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use vector2d::Vector2D;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VtkFormat {
+    // Legacy ASCII .vtk file.
+    Legacy,
+    // XML unstructured grid .vtu file.
+    Xml,
+}
+
+impl VtkFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            VtkFormat::Legacy => "vtk",
+            VtkFormat::Xml => "vtu",
+        }
+    }
+}
+
+pub struct VtkWriter {
+    directory: PathBuf,
+    name: String,
+    formats: Vec<VtkFormat>,
+    interval: usize,
+    // Time and file name of every step in the collection.
+    collection: Vec<(f64, String)>,
+}
+
+impl VtkWriter {
+    pub fn new(directory: PathBuf, name: String, formats: Vec<VtkFormat>, interval: usize) -> Self {
+        VtkWriter {
+            directory,
+            name,
+            formats,
+            interval: interval.max(1),
+            collection: Vec::new(),
+        }
+    }
+
+    // Writes the simulation if step, the number of updates so far, is a
+    // multiple of the interval, and returns whether it did.
+    pub fn write_step<T: Real>(
+        &mut self,
+        simulation: &Simulation<T>,
+        step: usize,
+    ) -> io::Result<bool> {
+        if !step.is_multiple_of(self.interval) {
+            return Ok(false);
+        }
+        let time = step as f64 / simulation.ups as f64;
+        self.write(&PointData::from_simulation(simulation), step, time)?;
+        Ok(true)
+    }
+
+    // Writes a recorded frame regardless of the interval. Recordings hold no
+    // pressures, so the files have velocity, density, speed and id.
+    pub fn write_frame(&mut self, frame: &Frame) -> io::Result<()> {
+        self.write(&PointData::from_frame(frame), frame.step, frame.time)
+    }
+
+    fn write<T: Real>(&mut self, points: &PointData<T>, step: usize, time: f64) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        let mut file_names = Vec::with_capacity(self.formats.len());
+        for format in self.formats.iter() {
+            let file_name = format!("{}_{:06}.{}", self.name, step, format.extension());
+            let contents = match format {
+                VtkFormat::Legacy => legacy_file(points, step, time),
+                VtkFormat::Xml => xml_file(points),
+            };
+            fs::write(self.directory.join(&file_name), contents)?;
+            file_names.push(file_name);
+        }
+        // The collection prefers the XML files, which ParaView reads fastest.
+        let indexed_format = self
+            .formats
+            .iter()
+            .position(|&format| format == VtkFormat::Xml)
+            .unwrap_or(0);
+        self.collection
+            .push((time, file_names.swap_remove(indexed_format)));
+        // Rewritten every time, so it stays valid if the run is interrupted.
+        fs::write(
+            self.directory.join(format!("{}.pvd", self.name)),
+            collection_file(&self.collection),
+        )
+    }
+}
+
+// The particles of one step in the order they are written.
+struct PointData<T: Real> {
+    ids: Vec<usize>,
+    positions: Vec<Vector2D<T>>,
+    velocities: Vec<Vector2D<T>>,
+    scalars: Vec<(&'static str, Vec<T>)>,
+}
+
+impl<T: Real> PointData<T> {
+    fn from_simulation(simulation: &Simulation<T>) -> Self {
+        let particles = &simulation.particles;
+        PointData {
+            ids: particles.ids.clone(),
+            positions: particles.iter().map(|particle| particle.position).collect(),
+            velocities: particles.iter().map(|particle| particle.velocity).collect(),
+            scalars: vec![
+                ("density", simulation.densities().to_vec()),
+                ("pressure", simulation.pressures().to_vec()),
+                (
+                    "speed",
+                    particles.iter().map(|particle| particle.speed()).collect(),
+                ),
+            ],
+        }
+    }
+}
+
+impl PointData<f32> {
+    fn from_frame(frame: &Frame) -> Self {
+        PointData {
+            ids: (0..frame.positions.len()).collect(),
+            positions: frame.positions.clone(),
+            velocities: frame.velocities.clone(),
+            scalars: vec![
+                ("density", frame.densities.clone()),
+                (
+                    "speed",
+                    frame
+                        .velocities
+                        .iter()
+                        .map(|velocity| velocity.length())
+                        .collect(),
+                ),
+            ],
+        }
+    }
+}
+
+fn legacy_file<T: Real>(points: &PointData<T>, step: usize, time: f64) -> String {
+    let count = points.ids.len();
+    let real = real_type_name::<T>(VtkFormat::Legacy);
+    let mut contents = String::new();
+    contents.push_str("# vtk DataFile Version 3.0\n");
+    let _ = writeln!(contents, "SPH particles, step {} at time {}", step, time);
+    contents.push_str("ASCII\nDATASET UNSTRUCTURED_GRID\n");
+    let _ = writeln!(contents, "POINTS {} {}", count, real);
+    for position in points.positions.iter() {
+        let _ = writeln!(contents, "{:?} {:?} 0", position.x, position.y);
+    }
+    let _ = writeln!(contents, "CELLS {} {}", count, 2 * count);
+    for index in 0..count {
+        let _ = writeln!(contents, "1 {}", index);
+    }
+    let _ = writeln!(contents, "CELL_TYPES {}", count);
+    for _ in 0..count {
+        contents.push_str("1\n");
+    }
+    let _ = writeln!(contents, "POINT_DATA {}", count);
+    let _ = writeln!(contents, "VECTORS velocity {}", real);
+    for velocity in points.velocities.iter() {
+        let _ = writeln!(contents, "{:?} {:?} 0", velocity.x, velocity.y);
+    }
+    for (name, values) in points.scalars.iter() {
+        let _ = writeln!(
+            contents,
+            "SCALARS {} {} 1\nLOOKUP_TABLE default",
+            name, real
+        );
+        for value in values.iter() {
+            let _ = writeln!(contents, "{:?}", value);
+        }
+    }
+    contents.push_str("SCALARS id int 1\nLOOKUP_TABLE default\n");
+    for id in points.ids.iter() {
+        let _ = writeln!(contents, "{}", id);
+    }
+    contents
+}
+
+fn xml_file<T: Real>(points: &PointData<T>) -> String {
+    let count = points.ids.len();
+    let real = real_type_name::<T>(VtkFormat::Xml);
+    let mut contents = String::new();
+    contents.push_str("<?xml version=\"1.0\"?>\n");
+    contents.push_str(
+        "<VTKFile type=\"UnstructuredGrid\" version=\"0.1\" byte_order=\"LittleEndian\">\n",
+    );
+    contents.push_str("  <UnstructuredGrid>\n");
+    let _ = writeln!(
+        contents,
+        "    <Piece NumberOfPoints=\"{}\" NumberOfCells=\"{}\">",
+        count, count
+    );
+    contents.push_str("      <PointData Scalars=\"density\" Vectors=\"velocity\">\n");
+    let velocities = points
+        .velocities
+        .iter()
+        .map(|velocity| format!("{:?} {:?} 0", velocity.x, velocity.y));
+    push_data_array(&mut contents, real, "velocity", 3, velocities);
+    for (name, values) in points.scalars.iter() {
+        let values = values.iter().map(|value| format!("{:?}", value));
+        push_data_array(&mut contents, real, name, 1, values);
+    }
+    let ids = points.ids.iter().map(|id| id.to_string());
+    push_data_array(&mut contents, "Int64", "id", 1, ids);
+    contents.push_str("      </PointData>\n      <Points>\n");
+    let positions = points
+        .positions
+        .iter()
+        .map(|position| format!("{:?} {:?} 0", position.x, position.y));
+    push_data_array(&mut contents, real, "position", 3, positions);
+    contents.push_str("      </Points>\n      <Cells>\n");
+    push_data_array(
+        &mut contents,
+        "Int64",
+        "connectivity",
+        1,
+        (0..count).map(|index| index.to_string()),
+    );
+    push_data_array(
+        &mut contents,
+        "Int64",
+        "offsets",
+        1,
+        (1..=count).map(|offset| offset.to_string()),
+    );
+    push_data_array(
+        &mut contents,
+        "UInt8",
+        "types",
+        1,
+        (0..count).map(|_| "1".to_string()),
+    );
+    contents.push_str("      </Cells>\n    </Piece>\n  </UnstructuredGrid>\n</VTKFile>\n");
+    contents
+}
+
+fn push_data_array(
+    contents: &mut String,
+    data_type: &str,
+    name: &str,
+    components: usize,
+    values: impl IntoIterator<Item = String>,
+) {
+    let _ = writeln!(
+        contents,
+        "        <DataArray type=\"{}\" Name=\"{}\" NumberOfComponents=\"{}\" format=\"ascii\">",
+        data_type, name, components
+    );
+    for value in values {
+        let _ = writeln!(contents, "          {}", value);
+    }
+    contents.push_str("        </DataArray>\n");
+}
+
+fn collection_file(collection: &[(f64, String)]) -> String {
+    let mut contents = String::new();
+    contents.push_str("<?xml version=\"1.0\"?>\n");
+    contents
+        .push_str("<VTKFile type=\"Collection\" version=\"0.1\" byte_order=\"LittleEndian\">\n");
+    contents.push_str("  <Collection>\n");
+    for (time, file_name) in collection.iter() {
+        let _ = writeln!(
+            contents,
+            "    <DataSet timestep=\"{:?}\" group=\"\" part=\"0\" file=\"{}\"/>",
+            time, file_name
+        );
+    }
+    contents.push_str("  </Collection>\n</VTKFile>\n");
+    contents
+}
+
+fn real_type_name<T: Real>(format: VtkFormat) -> &'static str {
+    let is_double = std::mem::size_of::<T>() == 8;
+    match (format, is_double) {
+        (VtkFormat::Legacy, false) => "float",
+        (VtkFormat::Legacy, true) => "double",
+        (VtkFormat::Xml, false) => "Float32",
+        (VtkFormat::Xml, true) => "Float64",
+    }
+}
@@ -0,0 +1,66 @@
+use crate::fluid_simulation::replay_player::ReplayPlayer;
+use crate::fluid_simulation::simulation::Simulation;
+use piston::{Button, Event, Input, Key, Motion, MouseButton, PressEvent, ReleaseEvent};
+use vector2d::Vector2D;
+
+// S saves a checkpoint of the simulation and L restores it.
+const CHECKPOINT_PATH: &str = "simulation.checkpoint";
+
+#[derive(Default)]
+pub struct InputManager {}
+
+impl InputManager {
+    pub fn new() -> Self {
+        InputManager {}
+    }
+
+    pub fn handle_event(&self, event: &Event, simulation: &mut Simulation) {
+        if let Some(Button::Keyboard(Key::G)) = event.press_args() {
+            simulation.toggle_gravity();
+        }
+        if let Some(Button::Keyboard(Key::D)) = event.press_args() {
+            simulation.break_dam();
+        }
+        if let Some(Button::Keyboard(Key::S)) = event.press_args() {
+            match simulation.save_checkpoint(CHECKPOINT_PATH) {
+                Ok(()) => println!("Saved checkpoint to {}", CHECKPOINT_PATH),
+                Err(error) => eprintln!("{}", error),
+            }
+        }
+        if let Some(Button::Keyboard(Key::L)) = event.press_args() {
+            match simulation.load_checkpoint(CHECKPOINT_PATH) {
+                Ok(()) => println!("Loaded checkpoint from {}", CHECKPOINT_PATH),
+                Err(error) => eprintln!("{}", error),
+            }
+        }
+        if let Some(Button::Mouse(MouseButton::Left)) = event.press_args() {
+            simulation.activate_attractor();
+        }
+
+        if let Event::Input(Input::Move(Motion::MouseCursor(pos)), _) = event {
+            simulation.move_attractor(Vector2D::new(pos[0] as f32, pos[1] as f32));
+        }
+        if let Some(Button::Mouse(MouseButton::Left)) = event.release_args() {
+            simulation.deactivate_attractor();
+        }
+    }
+
+    // Space pauses, the left and right arrows step one frame, page up and
+    // page down jump ten, the up and down arrows change the speed and Home
+    // rewinds.
+    pub fn handle_replay_event(&self, event: &Event, player: &mut ReplayPlayer) {
+        if let Some(Button::Keyboard(key)) = event.press_args() {
+            match key {
+                Key::Space => player.toggle_pause(),
+                Key::Left => player.seek(-1),
+                Key::Right => player.seek(1),
+                Key::PageDown => player.seek(-10),
+                Key::PageUp => player.seek(10),
+                Key::Up => player.faster(),
+                Key::Down => player.slower(),
+                Key::Home => player.seek_to(0),
+                _ => {}
+            }
+        }
+    }
+}
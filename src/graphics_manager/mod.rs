@@ -1 +1,3 @@
+pub mod input_manager;
 pub mod render_manager;
+pub mod viewer;
@@ -1,5 +1,5 @@
-use crate::fluid_simulation::config::Particles;
-use graphics::rectangle::rectangle_by_corners;
+use crate::fluid_simulation::obstacles::obstacle_trait::Outline;
+use crate::fluid_simulation::particle::Particle;
 use graphics::{
     math::{Matrix2d, Vec2d},
     triangulation::{tx, ty},
@@ -52,23 +52,39 @@ impl RenderManager {
         RenderManager { gl }
     }
 
-    pub fn render_obstacles(&mut self, args: &RenderArgs) {
-        self.gl.draw(args.viewport(), |c, gl| {
-            let dims = rectangle_by_corners(50.0, 300.0, 100.0, 500.0);
-            rectangle([0.3, 0.3, 0.3, 1.0], dims, c.transform, gl);
-        })
-    }
-
-    pub fn render(&mut self, args: &RenderArgs, particles: &Particles) {
+    // Draws the outlines of obstacles and rigid bodies under the particles.
+    pub fn render(&mut self, args: &RenderArgs, particles: &[Particle], outlines: &[Outline]) {
         const BLACK_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+        const OBSTACLE_COLOR: [f32; 4] = [0.3, 0.3, 0.3, 1.0];
         self.gl.draw(args.viewport(), |c, gl| {
-            let dims = rectangle_by_corners(100.0, 400.0, 200.0, 750.0);
-            rectangle([0.3, 0.3, 0.3, 1.0], dims, c.transform, gl);
-
             clear(BLACK_COLOR, gl);
+            for outline in outlines {
+                match outline {
+                    Outline::Polygon(points) if points.len() > 2 => {
+                        let points: Vec<[f64; 2]> = points
+                            .iter()
+                            .map(|point| [point.x as f64, point.y as f64])
+                            .collect();
+                        polygon(OBSTACLE_COLOR, &points, c.transform, gl)
+                    }
+                    Outline::Polygon(_) => {}
+                    Outline::Segments(segments) => {
+                        for &[start, end] in segments {
+                            line(
+                                OBSTACLE_COLOR,
+                                1.5,
+                                [start.x as f64, start.y as f64, end.x as f64, end.y as f64],
+                                c.transform,
+                                gl,
+                            );
+                        }
+                    }
+                }
+            }
+
             let verts = particles
                 .iter()
-                .map(|particle| {
+                .flat_map(|particle| {
                     [
                         [particle.position.x as f64, particle.position.y as f64 + 3.0],
                         [
@@ -81,16 +97,14 @@ impl RenderManager {
                         ],
                     ]
                 })
-                .flatten()
                 .collect::<Vec<_>>();
 
             let colors = particles
                 .iter()
-                .map(|particle| {
+                .flat_map(|particle| {
                     let speed = speed_to_color_gradient(particle.speed());
                     [speed, speed, speed]
                 })
-                .flatten()
                 .collect::<Vec<_>>();
 
             gl.tri_list_c(&DrawState::default(), |f| {
@@ -107,7 +121,7 @@ const INVERSE_255: f32 = 1.0 / 255.0;
 fn speed_to_color_gradient(speed: f32) -> [f32; 4] {
     let ratio: f32 = speed * INVERSED_MAX_SPEED;
     let normalized = (ratio * 1024.0) as i32;
-    let region = (normalized / 256) as i32;
+    let region = normalized / 256;
     let x = (normalized % 256) as f32;
     match region {
         3 => [1.0, f32::max(255.0 - x, 0.0) * INVERSE_255, 0.0, 1.0],
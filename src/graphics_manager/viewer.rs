@@ -0,0 +1,129 @@
+use crate::cli::arguments::{OpenGlVersion, ViewOptions};
+use crate::cli::commands::CommandError;
+use crate::fluid_simulation::output_manager::OutputManager;
+use crate::fluid_simulation::recording::{Frame, Recording};
+use crate::fluid_simulation::replay_player::ReplayPlayer;
+use crate::fluid_simulation::scene::Scene;
+use crate::fluid_simulation::simulation::Simulation;
+use crate::graphics_manager::input_manager::InputManager;
+use crate::graphics_manager::render_manager::RenderManager;
+use glutin_window::GlutinWindow as Window;
+use opengl_graphics::{GlGraphics, OpenGL};
+use piston::event_loop::{EventSettings, Events};
+use piston::input::{RenderEvent, UpdateEvent};
+use piston::window::WindowSettings;
+use std::path::Path;
+
+// Runs the scene of the options in a window, or plays their recording back.
+pub fn view(options: &ViewOptions) -> Result<(), CommandError> {
+    let opengl = opengl(options.opengl_version);
+    match &options.replay_path {
+        Some(replay_path) => replay(replay_path, opengl, options.max_fps),
+        None => simulate(&options.scene.scene()?, opengl, options.max_fps),
+    }
+}
+
+fn opengl(version: OpenGlVersion) -> OpenGL {
+    match (version.major, version.minor) {
+        (2, 0) => OpenGL::V2_0,
+        (2, 1) => OpenGL::V2_1,
+        (3, 0) => OpenGL::V3_0,
+        (3, 1) => OpenGL::V3_1,
+        (3, 3) => OpenGL::V3_3,
+        (4, 0) => OpenGL::V4_0,
+        (4, 1) => OpenGL::V4_1,
+        (4, 2) => OpenGL::V4_2,
+        (4, 3) => OpenGL::V4_3,
+        (4, 4) => OpenGL::V4_4,
+        (4, 5) => OpenGL::V4_5,
+        _ => OpenGL::V3_2,
+    }
+}
+
+fn create_window(title: &str, [width, height]: [usize; 2], opengl: OpenGL) -> Window {
+    WindowSettings::new(title, [width as f64, height as f64])
+        .graphics_api(opengl)
+        .exit_on_esc(true)
+        .build()
+        .unwrap()
+}
+
+fn event_settings(max_fps: u64, ups: u64) -> EventSettings {
+    EventSettings {
+        max_fps,
+        ups,
+        swap_buffers: true,
+        bench_mode: false,
+        lazy: false,
+        ups_reset: 2,
+    }
+}
+
+fn simulate(scene: &Scene, opengl: OpenGL, max_fps: u64) -> Result<(), CommandError> {
+    let mut window = create_window("Particle Simulation", scene.box_dimensions(), opengl);
+
+    let mut simulation = Simulation::new(scene);
+    let mut output_manager = OutputManager::new(scene, &simulation)?;
+    let mut updates = 0;
+    // Output errors switch that output off but leave the window running.
+    if let Err(error) = output_manager.write_step(&simulation, updates) {
+        eprintln!("{}", error);
+    }
+    let mut renderer = RenderManager::new(GlGraphics::new(opengl));
+    let input_manager = InputManager::new();
+    let mut events = Events::new(event_settings(max_fps, simulation.ups as u64));
+
+    while let Some(e) = events.next(&mut window) {
+        if let Some(args) = e.render_args() {
+            renderer.render(
+                &args,
+                &simulation.particles.to_particles(),
+                &simulation.outlines(),
+            );
+        }
+
+        if e.update_args().is_some() {
+            simulation.update();
+            updates += 1;
+            if let Err(error) = output_manager.write_step(&simulation, updates) {
+                eprintln!("{}", error);
+            }
+        }
+
+        input_manager.handle_event(&e, &mut simulation);
+    }
+    output_manager.flush()?;
+    Ok(())
+}
+
+// Plays a recording back without running the physics.
+fn replay(recording_path: &Path, opengl: OpenGL, max_fps: u64) -> Result<(), CommandError> {
+    let mut recording = Recording::open(recording_path)?;
+    let mut window = create_window("Particle Replay", recording.box_dimensions(), opengl);
+    let mut renderer = RenderManager::new(GlGraphics::new(opengl));
+    let input_manager = InputManager::new();
+    let mut player = ReplayPlayer::new(recording.frame_count(), recording.frame_rate());
+    let mut frame = Frame::default();
+    let mut particles = Vec::new();
+    let mut shown_frame = None;
+    let mut events = Events::new(event_settings(max_fps, 60));
+
+    while let Some(e) = events.next(&mut window) {
+        if let Some(args) = e.update_args() {
+            player.advance(args.dt);
+        }
+        input_manager.handle_replay_event(&e, &mut player);
+
+        let current_frame = player.current_frame();
+        if player.frame_count() > 0 && shown_frame != Some(current_frame) {
+            recording.read_frame_into(current_frame, &mut frame)?;
+            particles = frame.particles();
+            shown_frame = Some(current_frame);
+        }
+
+        if let Some(args) = e.render_args() {
+            renderer.render(&args, &particles, &[]);
+        }
+    }
+    Ok(())
+}
@@ -1,6 +1,4 @@
+pub mod cli;
 pub mod fluid_simulation;
+#[cfg(feature = "viewer")]
 pub mod graphics_manager;
-
-extern crate glutin_window;
-extern crate opengl_graphics;
-extern crate piston;
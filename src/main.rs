@@ -1,71 +1,45 @@
-pub mod fluid_simulation;
-pub mod graphics_manager;
-
-use fluid_simulation::fluid_simulation_app::FluidSimulationApp;
-use glutin_window::GlutinWindow as Window;
-use graphics_manager::render_manager::RenderManager;
-use opengl_graphics::{GlGraphics, OpenGL};
-use piston::event_loop::{EventSettings, Events};
-use piston::input::{RenderEvent, UpdateEvent};
-use piston::window::WindowSettings;
-use std::time::{Duration, Instant};
-
-extern crate glutin_window;
-extern crate opengl_graphics;
-extern crate piston;
+use coding_challenges::cli::arguments::{self, Command, ViewOptions, USAGE};
+use coding_challenges::cli::commands::{self, CommandError};
 
 fn main() {
-    // Change this to OpenGL::V2_1 if not working.
-    let opengl = OpenGL::V3_2;
-    const WINDOW_WIDTH: usize = 1000;
-    const WINDOW_HEIGHT: usize = 800;
-
-    // Create a Glutin window.
-    let mut window: Window = WindowSettings::new(
-        "Particle Simulation",
-        [WINDOW_WIDTH as f64, WINDOW_HEIGHT as f64],
-    )
-    .graphics_api(opengl)
-    .exit_on_esc(true)
-    .build()
-    .unwrap();
-
-    // Create a new game and run it.
-    let mut simulation = FluidSimulationApp::new([WINDOW_WIDTH, WINDOW_HEIGHT]);
-    let mut renderer = RenderManager::new(GlGraphics::new(opengl));
-
-    let mut events = Events::new(EventSettings {
-        max_fps: 60,
-        ups: simulation.ups as u64,
-        swap_buffers: true,
-        bench_mode: false,
-        lazy: false,
-        ups_reset: 2,
+    let command = arguments::parse(std::env::args().skip(1)).unwrap_or_else(|error| {
+        eprintln!("{}, see coding-challenges help", error);
+        std::process::exit(2);
     });
-
-    let num_executions = 600;
-    let mut counter = 0;
-    let mut total_elapsed_time = Duration::from_secs(0);
-
-    while let Some(e) = events.next(&mut window) {
-        if let Some(args) = e.render_args() {
-            renderer.render(&args, &simulation.particles);
-        }
-
-        if let Some(_) = e.update_args() {
-            let start = Instant::now();
-            simulation.update();
-            total_elapsed_time += start.elapsed();
-            counter += 1;
+    if let Some(threads) = command.threads() {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .unwrap();
+    }
+    let result = match command {
+        Command::View(options) => view(&options),
+        Command::Run(options) => commands::run(&options).map(|summary| println!("{}", summary)),
+        Command::Bench(options) => commands::bench(&options).map(|report| println!("{}", report)),
+        Command::Convert(options) => commands::convert(&options).map(|summary| {
+            println!(
+                "Converted {} frames to {}",
+                summary.frame_count,
+                summary.output_path.display()
+            )
+        }),
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
         }
+    };
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+}
 
-        simulation.handle_event(e, &window);
+#[cfg(feature = "viewer")]
+fn view(options: &ViewOptions) -> Result<(), CommandError> {
+    coding_challenges::graphics_manager::viewer::view(options)
+}
 
-        if counter >= num_executions {
-            let average_time = total_elapsed_time / num_executions as u32;
-            println!("Average Time: {:?}", average_time);
-            counter = 0;
-            total_elapsed_time = Duration::from_secs(0);
-        }
-    }
+#[cfg(not(feature = "viewer"))]
+fn view(_options: &ViewOptions) -> Result<(), CommandError> {
+    Err(CommandError::ViewerUnavailable)
 }
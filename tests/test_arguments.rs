@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::cli::arguments::{
+        parse, ArgumentError, Command, ConvertFormat, OpenGlVersion, RunLength, SceneOptions,
+    };
+    use std::path::PathBuf;
+
+    fn parse_arguments(arguments: &[&str]) -> Result<Command, ArgumentError> {
+        parse(arguments.iter().map(|argument| argument.to_string()))
+    }
+
+    #[test]
+    fn test_view_without_arguments() {
+        // ACT
+        let command = parse_arguments(&[]).unwrap();
+
+        // ASSERT
+        let Command::View(options) = command else {
+            panic!("expected view, got {:?}", command);
+        };
+        assert_eq!(options.scene, SceneOptions::default());
+        assert_eq!(options.replay_path, None);
+        assert_eq!(options.opengl_version, OpenGlVersion { major: 3, minor: 2 });
+        assert_eq!(options.max_fps, 60);
+    }
+
+    #[test]
+    fn test_run_with_scene_options() {
+        // ACT
+        let command = parse_arguments(&[
+            "run",
+            "--particles",
+            "800",
+            "scenes/dam_break.toml",
+            "--seed=7",
+            "--threads",
+            "2",
+            "--output-dir",
+            "out",
+            "--seconds",
+            "1.5",
+        ])
+        .unwrap();
+
+        // ASSERT
+        assert_eq!(command.threads(), Some(2));
+        let Command::Run(options) = command else {
+            panic!("expected run, got {:?}", command);
+        };
+        assert_eq!(
+            options.scene,
+            SceneOptions {
+                scene_path: Some(PathBuf::from("scenes/dam_break.toml")),
+                particle_count: Some(800),
+                seed: Some(7),
+                output_directory: Some(PathBuf::from("out")),
+            }
+        );
+        assert_eq!(options.length, RunLength::Seconds(1.5));
+    }
+
+    #[test]
+    fn test_bench_and_convert_defaults() {
+        // ACT
+        let bench = parse_arguments(&["bench", "--repeats", "3"]).unwrap();
+        let convert = parse_arguments(&["convert", "run.rec", "--to", "vtu"]).unwrap();
+        let help = parse_arguments(&["view", "--help"]).unwrap();
+
+        // ASSERT
+        let Command::Bench(bench) = bench else {
+            panic!("expected bench, got {:?}", bench);
+        };
+        assert_eq!(bench.steps, 200);
+        assert_eq!(bench.warm_up_steps, 20);
+        assert_eq!(bench.repeats, 3);
+        let Command::Convert(convert) = convert else {
+            panic!("expected convert, got {:?}", convert);
+        };
+        assert_eq!(convert.recording_path, PathBuf::from("run.rec"));
+        assert_eq!(convert.format, ConvertFormat::Vtu);
+        assert_eq!(convert.output_directory, None);
+        assert_eq!(help, Command::Help);
+    }
+
+    #[test]
+    fn test_reject_invalid_arguments() {
+        // ACT
+        let unknown_command = parse_arguments(&["simulate"]);
+        let unknown_option = parse_arguments(&["convert", "run.rec", "--seed", "1"]);
+        let missing_value = parse_arguments(&["bench", "--steps"]);
+        let zero_threads = parse_arguments(&["bench", "--threads", "0"]);
+        let unsupported_opengl = parse_arguments(&["view", "--opengl", "5.0"]);
+        let missing_length = parse_arguments(&["run"]);
+        let both_lengths = parse_arguments(&["run", "--steps", "5", "--seconds", "1"]);
+        let replay_with_scene = parse_arguments(&["view", "scene.toml", "--replay", "run.rec"]);
+        let two_scenes = parse_arguments(&["run", "a.toml", "b.toml", "--steps", "5"]);
+
+        // ASSERT
+        assert_eq!(
+            unknown_command,
+            Err(ArgumentError::UnknownCommand("simulate".to_string()))
+        );
+        assert_eq!(
+            unknown_option,
+            Err(ArgumentError::UnknownOption {
+                command: "convert".to_string(),
+                option: "--seed".to_string()
+            })
+        );
+        assert_eq!(
+            missing_value,
+            Err(ArgumentError::MissingValue("--steps".to_string()))
+        );
+        assert!(matches!(
+            zero_threads,
+            Err(ArgumentError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            unsupported_opengl,
+            Err(ArgumentError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            missing_length,
+            Err(ArgumentError::MissingArgument(_))
+        ));
+        assert!(matches!(both_lengths, Err(ArgumentError::Conflict(_))));
+        assert!(matches!(replay_with_scene, Err(ArgumentError::Conflict(_))));
+        assert_eq!(
+            two_scenes,
+            Err(ArgumentError::UnexpectedArgument("b.toml".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_scene_options_override_scene() {
+        // ARRANGE
+        let options = SceneOptions {
+            scene_path: Some(PathBuf::from("scenes/falling_drop.toml")),
+            particle_count: Some(301),
+            seed: Some(9),
+            output_directory: Some(PathBuf::from("out")),
+        };
+
+        // ACT
+        let scene = options.scene().unwrap();
+
+        // ASSERT
+        assert_eq!(scene.particle_count(), 301);
+        assert_eq!(scene.seed, 9);
+        assert_eq!(scene.output.unwrap().directory, PathBuf::from("out"));
+    }
+}
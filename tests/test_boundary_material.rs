@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::boundary_material::{
+        BoundaryMaterial, Friction, WallMaterials,
+    };
+    use coding_challenges::fluid_simulation::obstacle_collision_manager::ObstacleCollisionManager;
+    use coding_challenges::fluid_simulation::obstacles::dam_obstacle::DamObstacle;
+    use coding_challenges::fluid_simulation::obstacles::obstacle_trait::Obstacle;
+    use coding_challenges::fluid_simulation::particle::Particle;
+    use vector2d::Vector2D;
+
+    const TOLERANCE: f32 = 1e-4;
+    const FLOOR_NORMAL: Vector2D<f32> = Vector2D { x: 0.0, y: -1.0 };
+
+    fn assert_close(actual: Vector2D<f32>, expected: Vector2D<f32>) {
+        assert!(
+            (actual - expected).length() < TOLERANCE,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_restitution_scales_normal_velocity() {
+        // ARRANGE
+        let elastic = BoundaryMaterial::default();
+        let damped = BoundaryMaterial {
+            restitution: 0.5,
+            ..BoundaryMaterial::default()
+        };
+        let velocity = Vector2D::new(3.0, 10.0);
+
+        // ACT
+        let elastic_velocity = elastic.collide(velocity, FLOOR_NORMAL);
+        let damped_velocity = damped.collide(velocity, FLOOR_NORMAL);
+        let leaving_velocity = damped.collide(Vector2D::new(3.0, -10.0), FLOOR_NORMAL);
+
+        // ASSERT
+        assert_close(elastic_velocity, Vector2D::new(3.0, -10.0));
+        assert_close(damped_velocity, Vector2D::new(3.0, -5.0));
+        assert_close(leaving_velocity, Vector2D::new(3.0, -10.0));
+    }
+
+    #[test]
+    fn test_friction_modes_reduce_tangential_velocity() {
+        // ARRANGE
+        let material = |friction| BoundaryMaterial {
+            restitution: 0.0,
+            friction,
+        };
+        let velocity = Vector2D::new(8.0, 10.0);
+
+        // ACT
+        let free_slip = material(Friction::FreeSlip).collide(velocity, FLOOR_NORMAL);
+        let no_slip = material(Friction::NoSlip).collide(velocity, FLOOR_NORMAL);
+        let sliding = material(Friction::Coulomb(0.5)).collide(velocity, FLOOR_NORMAL);
+        let sticking = material(Friction::Coulomb(1.0)).collide(velocity, FLOOR_NORMAL);
+
+        // ASSERT
+        assert_close(free_slip, Vector2D::new(8.0, 0.0));
+        assert_close(no_slip, Vector2D::new(0.0, 0.0));
+        assert_close(sliding, Vector2D::new(3.0, 0.0));
+        assert_close(sticking, Vector2D::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_wall_and_obstacle_materials_apply_on_contact() {
+        // ARRANGE
+        let sticky = BoundaryMaterial {
+            restitution: 0.0,
+            friction: Friction::NoSlip,
+        };
+        let collision_manager = ObstacleCollisionManager::new(
+            [100, 100],
+            true,
+            WallMaterials {
+                bottom: sticky,
+                ..WallMaterials::default()
+            },
+        );
+        let mut dam = DamObstacle::new(50.0, 100.0);
+        dam.set_material(BoundaryMaterial {
+            restitution: 0.5,
+            ..BoundaryMaterial::default()
+        });
+        let mut falling = Particle {
+            velocity: Vector2D::new(4.0, 20.0),
+            ..Particle::new(0, Vector2D::new(30.0, 99.0))
+        };
+        let mut rising = Particle {
+            velocity: Vector2D::new(4.0, -20.0),
+            ..Particle::new(1, Vector2D::new(30.0, 1.0))
+        };
+        let mut crossing = Particle {
+            velocity: Vector2D::new(10.0, 2.0),
+            ..Particle::new(2, Vector2D::new(55.0, 50.0))
+        };
+
+        // ACT
+        collision_manager.apply_boundary_conditions(&mut falling);
+        collision_manager.apply_boundary_conditions(&mut rising);
+        dam.apply_obstruction_boundary(&mut crossing);
+
+        // ASSERT
+        assert_close(falling.velocity, Vector2D::new(0.0, 0.0));
+        assert_close(rising.velocity, Vector2D::new(4.0, 20.0));
+        assert_close(crossing.position, Vector2D::new(50.0, 50.0));
+        assert_close(crossing.velocity, Vector2D::new(-5.0, 2.0));
+        assert_eq!(dam.material().restitution, 0.5);
+    }
+}
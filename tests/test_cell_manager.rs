@@ -1,8 +1,21 @@
 #[cfg(test)]
 mod tests {
+    use coding_challenges::fluid_simulation::grids::bounded_grid::BoundedGrid;
+    use coding_challenges::fluid_simulation::grids::hashed_grid::HashedGrid;
     use coding_challenges::fluid_simulation::{cell_manager::CellManager, particle::Particle};
     use vector2d::Vector2D;
 
+    // 100 particles spread over a 10x10 square whose top left is at offset.
+    fn scattered_particles(offset: Vector2D<f32>) -> Vec<Particle> {
+        (0..100)
+            .map(|index| {
+                let x = (index * 37 % 100) as f32 / 10.0;
+                let y = (index * 61 % 100) as f32 / 10.0;
+                Particle::new(index, Vector2D::new(x, y) + offset)
+            })
+            .collect()
+    }
+
     #[test]
     fn test_update_cell_keys() {
         // ARRANGE
@@ -61,4 +74,149 @@ mod tests {
         let adjacent_particles_indices: Vec<usize> = adjacent_particles_indices_iterator.collect();
         assert_eq!(adjacent_particles_indices.len(), 9);
     }
+
+    #[test]
+    fn test_get_adjacent_particles_without_particles() {
+        // ARRANGE
+        let box_dimensions: [usize; 2] = [3, 3];
+        let smoothing_radius: f32 = 0.5;
+        let particles: &mut [Particle] = &mut [];
+        let mut cell_manager = CellManager::new(0, box_dimensions, smoothing_radius);
+        cell_manager.update(particles);
+
+        // ACT
+        let adjacent_particles_indices_iterator =
+            cell_manager.get_adjacent_particles_indices(Vector2D::new(1.5, 1.5));
+
+        // ASSERT
+        assert_eq!(adjacent_particles_indices_iterator.count(), 0);
+    }
+
+    #[test]
+    fn test_get_neighbors_matches_brute_force() {
+        // ARRANGE
+        let box_dimensions: [usize; 2] = [10, 10];
+        let smoothing_radius: f32 = 1.0;
+        let mut particles = scattered_particles(Vector2D::new(0.0, 0.0));
+        let mut cell_manager =
+            CellManager::new(particles.len() as i32, box_dimensions, smoothing_radius);
+
+        // ACT
+        cell_manager.update(&mut particles);
+
+        // ASSERT
+        let search_radius = 2.0 * smoothing_radius;
+        for (index, particle) in particles.iter().enumerate() {
+            let mut neighbors = cell_manager.get_neighbors(index).to_vec();
+            neighbors.sort();
+            let expected_neighbors: Vec<usize> = particles
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| (other.position - particle.position).length() < search_radius)
+                .map(|(other_index, _)| other_index)
+                .collect();
+            assert_eq!(neighbors, expected_neighbors);
+        }
+    }
+
+    #[test]
+    fn test_update_sorts_particles_by_cell_and_keeps_ids() {
+        // ARRANGE
+        let box_dimensions: [usize; 2] = [10, 10];
+        let smoothing_radius: f32 = 1.0;
+        let mut particles = scattered_particles(Vector2D::new(0.0, 0.0));
+        let previous_particles = particles.clone();
+        let mut cell_manager =
+            CellManager::new(particles.len() as i32, box_dimensions, smoothing_radius);
+
+        // ACT
+        cell_manager.update(&mut particles);
+
+        // ASSERT
+        for pair in particles.windows(2) {
+            assert!(pair[0].cell_key <= pair[1].cell_key);
+            if pair[0].cell_key == pair[1].cell_key {
+                assert!(pair[0].id < pair[1].id);
+            }
+        }
+        for (index, particle) in particles.iter().enumerate() {
+            let previous_index = cell_manager.permutation()[index];
+            assert_eq!(previous_particles[previous_index].id, particle.id);
+            assert_eq!(cell_manager.get_particle_index(particle.id), index);
+        }
+    }
+
+    #[test]
+    fn test_hashed_grid_neighbors_match_brute_force_outside_domain() {
+        // ARRANGE
+        let smoothing_radius: f32 = 1.0;
+        let mut particles = scattered_particles(Vector2D::new(-50.0, 1.0e6));
+        particles.push(Particle::new(100, Vector2D::new(f32::MAX, -f32::MAX)));
+        particles.push(Particle::new(101, Vector2D::new(f32::NAN, 0.0)));
+        let mut cell_manager = CellManager::with_grid(
+            particles.len() as i32,
+            Box::new(HashedGrid::new(particles.len(), 2.0 * smoothing_radius)),
+            0.0,
+        );
+
+        // ACT
+        cell_manager.update(&mut particles);
+
+        // ASSERT
+        let search_radius = 2.0 * smoothing_radius;
+        for (index, particle) in particles.iter().enumerate() {
+            if !particle.position.x.is_finite() {
+                continue;
+            }
+            let mut neighbors = cell_manager.get_neighbors(index).to_vec();
+            neighbors.sort();
+            let expected_neighbors: Vec<usize> = particles
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| (other.position - particle.position).length() < search_radius)
+                .map(|(other_index, _)| other_index)
+                .collect();
+            assert_eq!(neighbors, expected_neighbors);
+        }
+    }
+
+    #[test]
+    fn test_verlet_lists_rebuild_after_half_skin_displacement() {
+        // ARRANGE
+        let box_dimensions: [usize; 2] = [10, 10];
+        let smoothing_radius: f32 = 1.0;
+        let skin: f32 = 0.5;
+        let mut particles = scattered_particles(Vector2D::new(0.0, 0.0));
+        let mut cell_manager = CellManager::with_grid(
+            particles.len() as i32,
+            Box::new(BoundedGrid::new(
+                box_dimensions,
+                2.0 * smoothing_radius + skin,
+            )),
+            skin,
+        );
+        cell_manager.update(&mut particles);
+
+        // ACT
+        particles[0].position.x += 0.2;
+        let is_first_update_rebuilt = cell_manager.update(&mut particles);
+        particles[0].position.x += 0.1;
+        let is_second_update_rebuilt = cell_manager.update(&mut particles);
+
+        // ASSERT
+        assert!(!is_first_update_rebuilt);
+        assert!(is_second_update_rebuilt);
+        let statistics = cell_manager.statistics();
+        assert_eq!(statistics.updates, 3);
+        assert_eq!(statistics.rebuilds, 2);
+        let search_radius = 2.0 * smoothing_radius;
+        for (index, particle) in particles.iter().enumerate() {
+            let neighbors = cell_manager.get_neighbors(index);
+            for (other_index, other) in particles.iter().enumerate() {
+                if (other.position - particle.position).length() < search_radius {
+                    assert!(neighbors.contains(&other_index));
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,159 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::checkpoint::{CheckpointError, CHECKPOINT_VERSION};
+    use coding_challenges::fluid_simulation::scene::Scene;
+    use coding_challenges::fluid_simulation::simulation::Simulation;
+
+    fn particle_bits(simulation: &Simulation) -> Vec<[u32; 4]> {
+        simulation
+            .particles
+            .iter()
+            .map(|particle| {
+                [
+                    particle.position.x.to_bits(),
+                    particle.position.y.to_bits(),
+                    particle.velocity.x.to_bits(),
+                    particle.velocity.y.to_bits(),
+                ]
+            })
+            .collect()
+    }
+
+    fn run_and_save(scene: &Scene) -> (Simulation, Vec<u8>) {
+        let mut simulation = Simulation::new(scene);
+        for _ in 0..10 {
+            simulation.update();
+        }
+        simulation.break_dam();
+        simulation.toggle_gravity();
+        let mut checkpoint = Vec::new();
+        simulation.write_checkpoint(&mut checkpoint).unwrap();
+        (simulation, checkpoint)
+    }
+
+    #[test]
+    fn test_restored_run_continues_identically() {
+        // ARRANGE
+        let scene = Scene::dam_break([1000, 800], 300);
+        let (mut simulation, checkpoint) = run_and_save(&scene);
+        let mut other_scene = scene.clone();
+        other_scene.seed = 1;
+        let mut restored_simulation = Simulation::new(&other_scene);
+
+        // ACT
+        restored_simulation
+            .read_checkpoint(&mut checkpoint.as_slice())
+            .unwrap();
+        for _ in 0..10 {
+            simulation.update();
+            restored_simulation.update();
+        }
+
+        // ASSERT
+        assert_eq!(
+            particle_bits(&simulation),
+            particle_bits(&restored_simulation)
+        );
+        assert_eq!(
+            simulation.delta_time().to_bits(),
+            restored_simulation.delta_time().to_bits()
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_file_round_trip() {
+        // ARRANGE
+        let scene = Scene::dam_break([1000, 800], 100);
+        let (simulation, _) = run_and_save(&scene);
+        let path = std::env::temp_dir().join("test_checkpoint_file_round_trip.checkpoint");
+        let mut restored_simulation = Simulation::new(&scene);
+
+        // ACT
+        simulation.save_checkpoint(&path).unwrap();
+        let result = restored_simulation.load_checkpoint(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        // ASSERT
+        result.unwrap();
+        assert_eq!(
+            particle_bits(&simulation),
+            particle_bits(&restored_simulation)
+        );
+    }
+
+    #[test]
+    fn test_restore_single_precision_checkpoint_in_double_precision() {
+        // ARRANGE
+        let scene = Scene::dam_break([1000, 800], 100);
+        let (simulation, checkpoint) = run_and_save(&scene);
+        let mut restored_simulation = Simulation::<f64>::from_scene(&scene);
+
+        // ACT
+        restored_simulation
+            .read_checkpoint(&mut checkpoint.as_slice())
+            .unwrap();
+
+        // ASSERT
+        for (particle, restored_particle) in simulation
+            .particles
+            .iter()
+            .zip(restored_simulation.particles.iter())
+        {
+            assert_eq!(particle.id, restored_particle.id);
+            assert_eq!(particle.position.x as f64, restored_particle.position.x);
+            assert_eq!(particle.velocity.y as f64, restored_particle.velocity.y);
+        }
+    }
+
+    #[test]
+    fn test_reject_other_version() {
+        // ARRANGE
+        let scene = Scene::dam_break([1000, 800], 100);
+        let (_, mut checkpoint) = run_and_save(&scene);
+        checkpoint[8..12].copy_from_slice(&(CHECKPOINT_VERSION + 1).to_le_bytes());
+        let mut simulation = Simulation::new(&scene);
+
+        // ACT
+        let result = simulation.read_checkpoint(&mut checkpoint.as_slice());
+
+        // ASSERT
+        assert!(matches!(
+            result,
+            Err(CheckpointError::UnsupportedVersion(version)) if version == CHECKPOINT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn test_reject_invalid_data() {
+        // ARRANGE
+        let scene = Scene::dam_break([1000, 800], 100);
+        let (_, checkpoint) = run_and_save(&scene);
+        let mut simulation = Simulation::new(&scene);
+        let particles_before = particle_bits(&simulation);
+
+        // ACT
+        let not_a_checkpoint = simulation.read_checkpoint(&mut &b"not a checkpoint"[..]);
+        let truncated = simulation.read_checkpoint(&mut &checkpoint[..checkpoint.len() - 1]);
+
+        // ASSERT
+        assert!(matches!(
+            not_a_checkpoint,
+            Err(CheckpointError::NotACheckpoint)
+        ));
+        assert!(matches!(truncated, Err(CheckpointError::Io(_))));
+        assert_eq!(particles_before, particle_bits(&simulation));
+    }
+
+    #[test]
+    fn test_reject_checkpoint_of_other_scene() {
+        // ARRANGE
+        let (_, checkpoint) = run_and_save(&Scene::dam_break([1000, 800], 100));
+        let mut simulation = Simulation::new(&Scene::dam_break([1000, 800], 120));
+
+        // ACT
+        let result = simulation.read_checkpoint(&mut checkpoint.as_slice());
+
+        // ASSERT
+        assert!(matches!(result, Err(CheckpointError::Incompatible(_))));
+    }
+}
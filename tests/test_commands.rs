@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::cli::arguments::{
+        BenchOptions, ConvertFormat, ConvertOptions, RunLength, RunOptions, SceneOptions,
+    };
+    use coding_challenges::cli::commands::{bench, convert, run};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn output_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    fn scene_options(particle_count: usize, output_directory: Option<PathBuf>) -> SceneOptions {
+        SceneOptions {
+            scene_path: None,
+            particle_count: Some(particle_count),
+            seed: None,
+            output_directory,
+        }
+    }
+
+    #[test]
+    fn test_run_writes_output() {
+        // ARRANGE
+        let directory = output_directory("test_commands_run");
+        let options = RunOptions {
+            scene: scene_options(40, Some(directory.clone())),
+            threads: None,
+            length: RunLength::Seconds(0.2),
+        };
+
+        // ACT
+        let summary = run(&options).unwrap();
+
+        // ASSERT
+        assert_eq!(summary.particle_count, 40);
+        assert_eq!(summary.steps, 20);
+        assert!((summary.simulated_time - 0.2).abs() < 1e-4);
+        let collection = fs::read_to_string(directory.join("particles.pvd")).unwrap();
+        assert_eq!(collection.matches("<DataSet").count(), 3);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_bench_times_every_update() {
+        // ARRANGE
+        let directory = output_directory("test_commands_bench");
+        let options = BenchOptions {
+            scene: scene_options(40, Some(directory.clone())),
+            threads: None,
+            steps: 4,
+            warm_up_steps: 1,
+            repeats: 2,
+        };
+
+        // ACT
+        let report = bench(&options).unwrap();
+
+        // ASSERT
+        assert_eq!(report.particle_count, 40);
+        assert_eq!(report.update_times.len(), 2);
+        assert!(report.update_times.iter().all(|times| times.len() == 4));
+        assert!(report.percentile(0.0) <= report.mean());
+        assert!(report.mean() <= report.percentile(1.0));
+        let timings = fs::read_to_string(directory.join("bench.csv")).unwrap();
+        assert_eq!(timings.lines().count(), 1 + 2 * 4);
+        assert!(!directory.join("particles.pvd").exists());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_convert_recording() {
+        // ARRANGE
+        let directory = output_directory("test_commands_convert");
+        let recording_path = directory.join("run.rec");
+        let mut scene_file = fs::read_to_string("scenes/falling_drop.toml").unwrap();
+        scene_file.push_str(&format!(
+            "\n[recording]\npath = {:?}\ninterval = 5\n",
+            recording_path
+        ));
+        fs::create_dir_all(&directory).unwrap();
+        let scene_path = directory.join("scene.toml");
+        fs::write(&scene_path, scene_file).unwrap();
+        run(&RunOptions {
+            scene: SceneOptions {
+                scene_path: Some(scene_path),
+                particle_count: Some(30),
+                seed: None,
+                output_directory: None,
+            },
+            threads: None,
+            length: RunLength::Steps(10),
+        })
+        .unwrap();
+
+        // ACT
+        let csv = convert(&ConvertOptions {
+            recording_path: recording_path.clone(),
+            format: ConvertFormat::Csv,
+            output_directory: None,
+        })
+        .unwrap();
+        let vtk = convert(&ConvertOptions {
+            recording_path,
+            format: ConvertFormat::Vtk,
+            output_directory: Some(directory.join("vtk")),
+        })
+        .unwrap();
+
+        // ASSERT
+        assert_eq!(csv.frame_count, 3);
+        assert_eq!(csv.output_path, directory.join("run.csv"));
+        let rows = fs::read_to_string(&csv.output_path).unwrap();
+        assert_eq!(rows.lines().count(), 1 + 3 * 30);
+        assert_eq!(vtk.output_path, directory.join("vtk").join("run.pvd"));
+        let legacy_file = fs::read_to_string(directory.join("vtk").join("run_000010.vtk")).unwrap();
+        assert!(legacy_file.contains("POINTS 30 float"));
+        assert!(legacy_file.contains("SCALARS speed float 1"));
+        assert!(!legacy_file.contains("pressure"));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
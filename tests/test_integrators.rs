@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::integrators::integrator_type::IntegratorType;
+    use coding_challenges::fluid_simulation::particle::Particle;
+    use coding_challenges::fluid_simulation::particle_store::ParticleStore;
+    use vector2d::Vector2D;
+
+    const INTEGRATOR_TYPES: [IntegratorType; 4] = [
+        IntegratorType::SymplecticEuler,
+        IntegratorType::Leapfrog,
+        IntegratorType::VelocityVerlet,
+        IntegratorType::PredictorCorrector,
+    ];
+
+    // Integrates one particle of unit mass through the predict, evaluate and
+    // correct sequence of a simulation step.
+    fn integrate(
+        integrator_type: IntegratorType,
+        steps: usize,
+        delta_time: f32,
+        calculate_acceleration: impl Fn(&Particle) -> Vector2D<f32>,
+    ) -> Particle {
+        let mut integrator = integrator_type.build();
+        let mut particles = ParticleStore::from_particles(&[Particle {
+            velocity: Vector2D::new(0.0, 1.0),
+            mass: 1.0,
+            ..Particle::new(0, Vector2D::new(1.0, 0.0))
+        }]);
+        let mut accelerations = vec![calculate_acceleration(&particles.get(0))];
+        for _ in 0..steps {
+            integrator.predict(&mut particles, &accelerations, delta_time);
+            accelerations[0] = calculate_acceleration(&particles.get(0));
+            integrator.correct(&mut particles, &accelerations, delta_time);
+        }
+        particles.get(0)
+    }
+
+    #[test]
+    fn test_constant_acceleration() {
+        for integrator_type in INTEGRATOR_TYPES {
+            // ARRANGE
+            let gravity = Vector2D::new(0.0, 10.0);
+
+            // ACT
+            let particle = integrate(integrator_type, 100, 0.01, |_| gravity);
+
+            // ASSERT
+            let expected_position = 1.0 + 0.5 * 10.0;
+            let tolerance = match integrator_type {
+                // First order: overshoots by a * dt * t / 2.
+                IntegratorType::SymplecticEuler => 0.06,
+                _ => 1e-3,
+            };
+            assert!(
+                (particle.position.y - expected_position).abs() < tolerance,
+                "{:?}: y = {}",
+                integrator_type,
+                particle.position.y
+            );
+            assert!((particle.velocity.y - 11.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_harmonic_oscillator_energy_drift() {
+        for integrator_type in INTEGRATOR_TYPES {
+            // ARRANGE
+            let calculate_energy = |particle: &Particle| {
+                0.5 * (particle.velocity.length_squared() + particle.position.length_squared())
+            };
+
+            // ACT
+            let particle = integrate(integrator_type, 10000, 0.01, |particle| -particle.position);
+
+            // ASSERT
+            let energy_drift = (calculate_energy(&particle) - 1.0).abs();
+            assert!(
+                energy_drift < 0.01,
+                "{:?}: energy drift {}",
+                integrator_type,
+                energy_drift
+            );
+        }
+    }
+}
@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::kernels::kernel_type::KernelType;
+    use std::f32::consts::PI;
+
+    const ALL_KERNELS: [KernelType; 8] = [
+        KernelType::CubicSpline,
+        KernelType::Spiky,
+        KernelType::Poly6,
+        KernelType::Sb,
+        KernelType::Viscosity,
+        KernelType::WendlandC2,
+        KernelType::WendlandC4,
+        KernelType::QuinticSpline,
+    ];
+
+    #[test]
+    fn test_kernels_are_normalized_in_2d() {
+        let smoothing_radius: f32 = 2.0;
+        for kernel_type in ALL_KERNELS {
+            // ARRANGE
+            let kernel = kernel_type.build();
+            let support_radius = kernel.support_radius(smoothing_radius);
+            let steps = 20000;
+            let step = support_radius / steps as f32;
+
+            // ACT
+            let integral: f32 = (0..steps)
+                .map(|index| {
+                    let distance = (index as f32 + 0.5) * step;
+                    2.0 * PI * distance * kernel.value(distance, smoothing_radius) * step
+                })
+                .sum();
+
+            // ASSERT
+            assert!(
+                (integral - 1.0).abs() < 1e-2,
+                "{:?} integrates to {}",
+                kernel_type,
+                integral
+            );
+        }
+    }
+
+    #[test]
+    fn test_kernels_vanish_outside_support() {
+        let smoothing_radius: f32 = 2.0;
+        for kernel_type in ALL_KERNELS {
+            // ARRANGE
+            let kernel = kernel_type.build();
+            let distance = kernel.support_radius(smoothing_radius) * 1.01;
+
+            // ASSERT
+            assert!(kernel.support_radius(smoothing_radius) <= 2.0 * smoothing_radius);
+            assert_eq!(kernel.value(distance, smoothing_radius), 0.0);
+            assert_eq!(kernel.gradient(distance, smoothing_radius), 0.0);
+            assert_eq!(kernel.laplacian(distance, smoothing_radius), 0.0);
+        }
+    }
+
+    #[test]
+    fn test_gradient_and_laplacian_match_finite_differences() {
+        let smoothing_radius: f32 = 2.0;
+        let epsilon: f32 = 1e-2;
+        for kernel_type in ALL_KERNELS {
+            // ARRANGE
+            let kernel = kernel_type.build();
+            let support_radius = kernel.support_radius(smoothing_radius);
+            let scale = kernel.normalization(smoothing_radius);
+
+            for fraction in [0.2, 0.45, 0.7, 0.9] {
+                let distance = fraction * support_radius;
+                let value_at = |distance: f32| kernel.value(distance, smoothing_radius);
+
+                // ACT
+                let gradient = kernel.gradient(distance, smoothing_radius);
+                let numerical_gradient =
+                    (value_at(distance + epsilon) - value_at(distance - epsilon)) / (2.0 * epsilon);
+                let numerical_laplacian = (value_at(distance + epsilon) - 2.0 * value_at(distance)
+                    + value_at(distance - epsilon))
+                    / epsilon.powi(2)
+                    + numerical_gradient / distance;
+
+                // ASSERT
+                assert!(
+                    (gradient - numerical_gradient).abs() < 1e-2 * (gradient.abs() + scale),
+                    "{:?} gradient at {}: {} vs {}",
+                    kernel_type,
+                    distance,
+                    gradient,
+                    numerical_gradient
+                );
+                if kernel_type != KernelType::Viscosity {
+                    let laplacian = kernel.laplacian(distance, smoothing_radius);
+                    assert!(
+                        (laplacian - numerical_laplacian).abs() < 2e-2 * (laplacian.abs() + scale),
+                        "{:?} laplacian at {}: {} vs {}",
+                        kernel_type,
+                        distance,
+                        laplacian,
+                        numerical_laplacian
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::obstacles::kinematic_obstacle::KinematicObstacle;
+    use coding_challenges::fluid_simulation::obstacles::motions::function_motion::FunctionMotion;
+    use coding_challenges::fluid_simulation::obstacles::motions::keyframe_motion::{
+        Keyframe, KeyframeMotion,
+    };
+    use coding_challenges::fluid_simulation::obstacles::motions::motion_trait::Motion;
+    use coding_challenges::fluid_simulation::obstacles::motions::oscillating_motion::OscillatingMotion;
+    use coding_challenges::fluid_simulation::obstacles::motions::pose::Pose;
+    use coding_challenges::fluid_simulation::obstacles::motions::rotating_motion::RotatingMotion;
+    use coding_challenges::fluid_simulation::obstacles::obstacle_trait::{Obstacle, Outline};
+    use coding_challenges::fluid_simulation::obstacles::shapes::rounded_box_shape::RoundedBoxShape;
+    use coding_challenges::fluid_simulation::particle::Particle;
+    use coding_challenges::fluid_simulation::scene::Scene;
+    use coding_challenges::fluid_simulation::simulation::Simulation;
+    use std::f64::consts::PI;
+    use vector2d::Vector2D;
+
+    const TOLERANCE: f64 = 1e-6;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < TOLERANCE,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    fn keyframe(time: f64, x: f64, rotation: f64) -> Keyframe<f64> {
+        Keyframe {
+            time,
+            pose: Pose::new(Vector2D::new(x, 0.0), rotation),
+        }
+    }
+
+    #[test]
+    fn test_keyframe_motion_interpolates_and_loops() {
+        // ARRANGE
+        let keyframes = vec![
+            keyframe(1.0, 0.0, 0.0),
+            keyframe(2.0, 10.0, 1.0),
+            keyframe(4.0, 0.0, 0.0),
+        ];
+        let once = KeyframeMotion::new(keyframes.clone(), false);
+        let looping = KeyframeMotion::new(keyframes, true);
+
+        // ACT
+        let before = once.pose(0.0);
+        let between = once.pose(1.5);
+        let falling = once.velocity(3.0);
+        let after = once.pose(10.0);
+        let wrapped = looping.pose(5.5);
+
+        // ASSERT
+        assert_eq!(before, Pose::new(Vector2D::new(0.0, 0.0), 0.0));
+        assert_close(between.translation.x, 5.0);
+        assert_close(between.rotation, 0.5);
+        assert_close(falling.linear.x, -5.0);
+        assert_close(falling.angular, -0.5);
+        assert_eq!(after, Pose::new(Vector2D::new(0.0, 0.0), 0.0));
+        assert_eq!(once.velocity(10.0).linear, Vector2D::new(0.0, 0.0));
+        assert_close(wrapped.translation.x, 7.5);
+    }
+
+    #[test]
+    fn test_function_defined_motions_report_their_velocity() {
+        // ARRANGE
+        let oscillating = OscillatingMotion::new(Vector2D::new(20.0, 0.0), 0.5, 0.25, 0.0);
+        let rotating = RotatingMotion::new(2.0);
+        let function = FunctionMotion::new(|time: f64| {
+            Pose::new(Vector2D::new(time * time, 3.0 * time), time)
+        });
+
+        // ACT
+        let peak = oscillating.pose(1.0);
+        let start_velocity = oscillating.velocity(0.0);
+        let turned = rotating.pose(1.5);
+        let function_velocity = function.velocity(2.0);
+
+        // ASSERT
+        assert_close(peak.translation.x, 20.0);
+        assert_close(peak.rotation, 0.5);
+        assert_close(start_velocity.linear.x, 20.0 * 2.0 * PI * 0.25);
+        assert_close(turned.rotation, 3.0);
+        assert_close(rotating.velocity(7.0).angular, 2.0);
+        assert_close(function_velocity.linear.x, 4.0);
+        assert_close(function_velocity.linear.y, 3.0);
+        assert_close(function_velocity.angular, 1.0);
+    }
+
+    #[test]
+    fn test_moving_obstacle_transfers_surface_velocity() {
+        // ARRANGE
+        // A 20 by 20 box moving right at 100 units per second.
+        let mut obstacle = KinematicObstacle::new(
+            Box::new(RoundedBoxShape::new(
+                Vector2D::new(0.0, 0.0),
+                Vector2D::new(20.0, 20.0),
+                0.0,
+            )),
+            Vector2D::new(10.0, 10.0),
+            Box::new(KeyframeMotion::new(
+                vec![keyframe(0.0, 0.0, 0.0), keyframe(1.0, 100.0, 0.0)],
+                false,
+            )),
+        );
+        let mut particle: Particle<f64> = Particle::new(0, Vector2D::new(69.0, 10.0));
+
+        // ACT
+        obstacle.update(0.5);
+        obstacle.apply_obstruction_boundary(&mut particle);
+
+        // ASSERT
+        assert_close(particle.position.x, 70.0);
+        assert_close(particle.position.y, 10.0);
+        assert_close(particle.velocity.x, 200.0);
+        assert_close(particle.velocity.y, 0.0);
+        assert!(!obstacle.is_breakable());
+    }
+
+    #[test]
+    fn test_rotating_obstacle_moves_outline_about_pivot() {
+        // ARRANGE
+        let mut obstacle = KinematicObstacle::new(
+            Box::new(RoundedBoxShape::new(
+                Vector2D::new(0.0, -2.0),
+                Vector2D::new(40.0, 2.0),
+                0.0,
+            )),
+            Vector2D::new(0.0, 0.0),
+            Box::new(RotatingMotion::new(PI / 2.0)),
+        );
+
+        // ACT
+        obstacle.update(1.0);
+        let surface_velocity = obstacle.surface_velocity(Vector2D::new(0.0, 30.0));
+
+        // ASSERT
+        // After a quarter turn the paddle points down the y axis.
+        let Outline::Segments(segments) = obstacle.outline() else {
+            panic!("moving shape outlines are segments");
+        };
+        for point in segments.iter().flatten() {
+            assert!(point.x.abs() < 3.0);
+            assert!(point.y > -3.0 && point.y < 43.0);
+        }
+        assert_close(surface_velocity.x, -30.0 * PI / 2.0);
+        assert_close(surface_velocity.y, 0.0);
+    }
+
+    #[test]
+    fn test_simulation_moves_obstacles_with_time() {
+        // ARRANGE
+        let contents = r#"
+            [domain]
+            width = 400
+            height = 300
+
+            [[fluid_blocks]]
+            top_left = [0.0, 200.0]
+            bottom_right = [100.0, 300.0]
+            particle_count = 50
+
+            [[obstacles]]
+            type = "circle"
+            center = [200.0, 100.0]
+            radius = 20.0
+
+            [obstacles.motion]
+            type = "keyframes"
+            keyframes = [
+                { time = 0.0 },
+                { time = 1.0, translation = [100.0, 0.0] },
+            ]
+        "#;
+        let scene = Scene::from_toml_str(contents).unwrap();
+        let mut simulation = Simulation::new(&scene);
+        let mut checkpoint = Vec::new();
+
+        // ACT
+        for _ in 0..50 {
+            simulation.update();
+        }
+        simulation.write_checkpoint(&mut checkpoint).unwrap();
+        let mut restored_simulation = Simulation::new(&scene);
+        restored_simulation
+            .read_checkpoint(&mut checkpoint.as_slice())
+            .unwrap();
+
+        // ASSERT
+        assert!((simulation.time() - 0.5).abs() < 1e-4);
+        assert_eq!(restored_simulation.time(), simulation.time());
+        for simulation in [&simulation, &restored_simulation] {
+            let Outline::Segments(segments) = simulation.obstacles()[0].outline() else {
+                panic!("moving shape outlines are segments");
+            };
+            for point in segments.iter().flatten() {
+                assert!((point.x - 250.0).abs() < 22.0);
+            }
+        }
+    }
+}
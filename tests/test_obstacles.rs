@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::obstacles::dam_obstacle::DamObstacle;
+    use coding_challenges::fluid_simulation::obstacles::obstacle_trait::{Obstacle, Outline};
+    use coding_challenges::fluid_simulation::particle::Particle;
+    use coding_challenges::fluid_simulation::scene::Scene;
+    use coding_challenges::fluid_simulation::simulation::Simulation;
+    use vector2d::Vector2D;
+
+    #[test]
+    fn test_scene_rectangle_pushes_particle_out_through_closest_side() {
+        // ARRANGE
+        let contents = r#"
+            [domain]
+            width = 400
+            height = 300
+
+            [[obstacles]]
+            type = "rectangle"
+            top_left = [150.0, 200.0]
+            bottom_right = [180.0, 300.0]
+        "#;
+        let scene = Scene::from_toml_str(contents).unwrap();
+        let rectangle = scene.obstacles[0].build::<f32>([400, 300]);
+        let mut particle = Particle {
+            velocity: Vector2D::new(-10.0, 0.0),
+            ..Particle::new(0, Vector2D::new(175.0, 210.0))
+        };
+
+        // ACT
+        rectangle.apply_obstruction_boundary(&mut particle);
+
+        // ASSERT
+        // The right side is 5 units away and the top 10.
+        assert!((particle.position.x - 180.0).abs() < 1e-3);
+        assert!((particle.position.y - 210.0).abs() < 1e-3);
+        assert!((particle.velocity.x - 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_dam_spans_domain_height_until_deactivated() {
+        // ARRANGE
+        let mut dam: DamObstacle = DamObstacle::new(600.0, 800.0);
+
+        // ACT
+        dam.set_active(false);
+
+        // ASSERT
+        assert_eq!(
+            dam.outline(),
+            Outline::Segments(vec![[
+                Vector2D::new(600.0, 0.0),
+                Vector2D::new(600.0, 800.0)
+            ]])
+        );
+        assert!(!dam.is_active());
+    }
+
+    #[test]
+    fn test_add_query_and_remove_obstacles() {
+        // ARRANGE
+        let mut simulation = Simulation::new(&Scene::dam_break([1000, 800], 10));
+
+        // ACT
+        let index = simulation.add_obstacle(Box::new(DamObstacle::new(800.0, 800.0)));
+        simulation.break_dam();
+        let breakable: Vec<bool> = simulation
+            .obstacles()
+            .iter()
+            .map(|obstacle| obstacle.is_breakable())
+            .collect();
+        let active: Vec<bool> = simulation
+            .obstacles()
+            .iter()
+            .map(|obstacle| obstacle.is_active())
+            .collect();
+        let removed = simulation.remove_obstacle(0);
+        let out_of_range = simulation.remove_obstacle(5);
+
+        // ASSERT
+        assert_eq!(index, 2);
+        assert_eq!(breakable, vec![true, false, true]);
+        assert_eq!(active, vec![false, true, false]);
+        assert!(removed.unwrap().is_breakable());
+        assert!(out_of_range.is_none());
+        assert_eq!(simulation.obstacles().len(), 2);
+    }
+
+    #[test]
+    fn test_added_obstacle_applies_in_step() {
+        // ARRANGE
+        let mut scene = Scene::dam_break([1000, 800], 200);
+        scene.obstacles.clear();
+        let mut simulation = Simulation::new(&scene);
+        simulation.add_obstacle(Box::new(DamObstacle::new(300.0, 800.0)));
+
+        // ACT
+        for _ in 0..20 {
+            simulation.update();
+        }
+
+        // ASSERT
+        for particle in simulation.particles.iter() {
+            assert!(particle.position.x <= 300.0);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::recording::{
+        Recorder, Recording, RecordingError, RECORDING_VERSION,
+    };
+    use coding_challenges::fluid_simulation::scene::Scene;
+    use coding_challenges::fluid_simulation::simulation::Simulation;
+    use std::fs;
+    use std::io::Cursor;
+    use std::path::PathBuf;
+
+    fn temporary_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(name)
+    }
+
+    fn record_run(path: &PathBuf, csv_path: Option<&PathBuf>) -> Simulation {
+        let mut simulation = Simulation::new(&Scene::dam_break([1000, 800], 40));
+        let mut recorder = Recorder::create(
+            path,
+            csv_path.map(|csv_path| csv_path.as_path()),
+            &simulation,
+            [1000, 800],
+            3,
+        )
+        .unwrap();
+        recorder.record_step(&simulation, 0).unwrap();
+        for step in 1..=7 {
+            simulation.update();
+            recorder.record_step(&simulation, step).unwrap();
+        }
+        recorder.flush().unwrap();
+        simulation
+    }
+
+    #[test]
+    fn test_recorded_frames_read_back_in_id_order() {
+        // ARRANGE
+        let path = temporary_path("test_recorded_frames_read_back_in_id_order.rec");
+        let simulation = record_run(&path, None);
+
+        // ACT
+        let mut recording = Recording::open(&path).unwrap();
+        let first_frame = recording.read_frame(0).unwrap();
+        let last_frame = recording.read_frame(2).unwrap();
+        let out_of_range = recording.read_frame(3);
+        fs::remove_file(&path).unwrap();
+
+        // ASSERT
+        assert_eq!(recording.frame_count(), 3);
+        assert_eq!(recording.particle_count(), 40);
+        assert_eq!(recording.box_dimensions(), [1000, 800]);
+        assert_eq!(recording.frame_rate(), 100.0 / 3.0);
+        assert_eq!(first_frame.step, 0);
+        assert_eq!(last_frame.step, 6);
+        assert!((last_frame.time - 0.06).abs() < 1e-6);
+        assert!(matches!(
+            out_of_range,
+            Err(RecordingError::FrameOutOfRange { index: 3, .. })
+        ));
+        assert_eq!(last_frame.particles().len(), 40);
+        // The simulation ran one more step after the last recorded frame.
+        for particle in simulation.particles.iter() {
+            let recorded_position = last_frame.positions[particle.id];
+            assert!((recorded_position - particle.position).length() < 50.0);
+        }
+    }
+
+    #[test]
+    fn test_interrupted_recording_ignores_partial_frame() {
+        // ARRANGE
+        let path = temporary_path("test_interrupted_recording_ignores_partial_frame.rec");
+        record_run(&path, None);
+        let mut bytes = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        bytes.truncate(bytes.len() - 5);
+
+        // ACT
+        let recording = Recording::from_reader(Cursor::new(bytes)).unwrap();
+
+        // ASSERT
+        assert_eq!(recording.frame_count(), 2);
+    }
+
+    #[test]
+    fn test_csv_matches_recording() {
+        // ARRANGE
+        let path = temporary_path("test_csv_matches_recording.rec");
+        let csv_path = temporary_path("test_csv_matches_recording.csv");
+        record_run(&path, Some(&csv_path));
+        let mut recording = Recording::open(&path).unwrap();
+
+        // ACT
+        let mut exported = Vec::new();
+        recording.export_csv(&mut exported).unwrap();
+        let written = fs::read_to_string(&csv_path).unwrap();
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&csv_path).unwrap();
+
+        // ASSERT
+        let exported = String::from_utf8(exported).unwrap();
+        assert_eq!(exported, written);
+        let lines: Vec<&str> = exported.lines().collect();
+        assert_eq!(lines[0], "step,time,id,x,y,vx,vy,density");
+        assert_eq!(lines.len(), 1 + 3 * 40);
+        assert!(lines[1].starts_with("0,0,0,"));
+        let last_line: Vec<&str> = lines[lines.len() - 1].split(',').collect();
+        assert_eq!(last_line[0], "6");
+        assert!((last_line[1].parse::<f64>().unwrap() - 0.06).abs() < 1e-6);
+        assert_eq!(last_line[2], "39");
+    }
+
+    #[test]
+    fn test_reject_invalid_recordings() {
+        // ARRANGE
+        let path = temporary_path("test_reject_invalid_recordings.rec");
+        record_run(&path, None);
+        let mut bytes = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        bytes[8..12].copy_from_slice(&(RECORDING_VERSION + 1).to_le_bytes());
+
+        // ACT
+        let other_version = Recording::from_reader(Cursor::new(bytes));
+        let not_a_recording = Recording::from_reader(Cursor::new(b"not a recording".to_vec()));
+
+        // ASSERT
+        assert!(matches!(
+            other_version,
+            Err(RecordingError::UnsupportedVersion(version)) if version == RECORDING_VERSION + 1
+        ));
+        assert!(matches!(
+            not_a_recording,
+            Err(RecordingError::NotARecording)
+        ));
+    }
+}
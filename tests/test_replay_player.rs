@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::replay_player::ReplayPlayer;
+
+    #[test]
+    fn test_advance_at_speed_and_pause_at_end() {
+        // ARRANGE
+        let mut player = ReplayPlayer::new(10, 20.0);
+
+        // ACT
+        player.advance(0.1);
+        let frame_at_normal_speed = player.current_frame();
+        player.faster();
+        player.advance(0.1);
+        let frame_at_double_speed = player.current_frame();
+        player.advance(1.0);
+
+        // ASSERT
+        assert_eq!(frame_at_normal_speed, 2);
+        assert_eq!(frame_at_double_speed, 6);
+        assert_eq!(player.current_frame(), 9);
+        assert!(player.is_paused());
+    }
+
+    #[test]
+    fn test_seek_while_paused() {
+        // ARRANGE
+        let mut player = ReplayPlayer::new(10, 20.0);
+        player.toggle_pause();
+
+        // ACT
+        player.advance(1.0);
+        let frame_while_paused = player.current_frame();
+        player.seek(4);
+        let frame_after_seek = player.current_frame();
+        player.seek(-10);
+        let frame_after_seek_before_start = player.current_frame();
+        player.seek_to(50);
+
+        // ASSERT
+        assert_eq!(frame_while_paused, 0);
+        assert_eq!(frame_after_seek, 4);
+        assert_eq!(frame_after_seek_before_start, 0);
+        assert_eq!(player.current_frame(), 9);
+    }
+
+    #[test]
+    fn test_play_from_end_restarts() {
+        // ARRANGE
+        let mut player = ReplayPlayer::new(5, 10.0);
+        player.advance(10.0);
+
+        // ACT
+        player.toggle_pause();
+
+        // ASSERT
+        assert!(!player.is_paused());
+        assert_eq!(player.current_frame(), 0);
+    }
+
+    #[test]
+    fn test_speed_is_bounded() {
+        // ARRANGE
+        let mut player = ReplayPlayer::new(5, 10.0);
+
+        // ACT
+        for _ in 0..10 {
+            player.faster();
+        }
+        let fastest = player.speed();
+        for _ in 0..20 {
+            player.slower();
+        }
+
+        // ASSERT
+        assert_eq!(fastest, 16.0);
+        assert_eq!(player.speed(), 1.0 / 16.0);
+    }
+}
@@ -0,0 +1,337 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::boundary_material::{BoundaryMaterial, WallMaterials};
+    use coding_challenges::fluid_simulation::obstacles::dam_obstacle::DamObstacle;
+    use coding_challenges::fluid_simulation::obstacles::motions::pose::{Pose, PoseVelocity};
+    use coding_challenges::fluid_simulation::obstacles::obstacle_trait::Obstacle;
+    use coding_challenges::fluid_simulation::obstacles::shape_obstacle::ShapeObstacle;
+    use coding_challenges::fluid_simulation::obstacles::shapes::circle_shape::CircleShape;
+    use coding_challenges::fluid_simulation::obstacles::shapes::difference_shape::DifferenceShape;
+    use coding_challenges::fluid_simulation::obstacles::shapes::rounded_box_shape::RoundedBoxShape;
+    use coding_challenges::fluid_simulation::particle::Particle;
+    use coding_challenges::fluid_simulation::particle_store::ParticleStore;
+    use coding_challenges::fluid_simulation::rigid_body::{
+        area_moments, RigidBody, RigidBodyError,
+    };
+    use coding_challenges::fluid_simulation::rigid_body_manager::RigidBodyManager;
+    use coding_challenges::fluid_simulation::scene::Scene;
+    use coding_challenges::fluid_simulation::simulation::Simulation;
+    use std::f64::consts::PI;
+    use vector2d::Vector2D;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    fn disk(center: [f64; 2], radius: f64, mass: f64) -> RigidBody<f64> {
+        RigidBody::new(
+            Box::new(CircleShape::new(
+                Vector2D::new(center[0], center[1]),
+                radius,
+            )),
+            mass,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mass_properties_of_disk_and_box() {
+        // ARRANGE
+        let circle = CircleShape::new(Vector2D::new(50.0, 40.0), 10.0);
+
+        // ACT
+        let moments = area_moments(&circle);
+        let spinning_disk = disk([50.0, 40.0], 10.0, 2.0);
+        let plank: RigidBody<f64> = RigidBody::with_density(
+            Box::new(RoundedBoxShape::new(
+                Vector2D::new(0.0, 0.0),
+                Vector2D::new(20.0, 10.0),
+                0.0,
+            )),
+            0.5,
+        )
+        .unwrap();
+
+        // ASSERT
+        assert_close(moments.area, PI * 100.0, 0.01 * PI * 100.0);
+        assert_close(moments.centroid.x, 50.0, 1e-6);
+        assert_close(moments.centroid.y, 40.0, 1e-6);
+        assert_close(spinning_disk.moment_of_inertia(), 100.0, 2.0);
+        assert_close(plank.mass(), 100.0, 1.0);
+        assert_close(plank.center().x, 10.0, 1e-6);
+        assert_close(plank.center().y, 5.0, 1e-6);
+        assert_close(plank.moment_of_inertia(), 100.0 * 500.0 / 12.0, 50.0);
+    }
+
+    #[test]
+    fn test_reject_body_without_area() {
+        // ARRANGE
+        let hollowed: DifferenceShape<f64> = DifferenceShape::new(
+            Box::new(CircleShape::new(Vector2D::new(50.0, 50.0), 10.0)),
+            Box::new(CircleShape::new(Vector2D::new(50.0, 50.0), 20.0)),
+        );
+
+        // ACT
+        let result = RigidBody::with_density(Box::new(hollowed), 1.0);
+
+        // ASSERT
+        assert_eq!(result.err(), Some(RigidBodyError::NoArea));
+    }
+
+    #[test]
+    fn test_forces_and_impulses_move_and_turn_body() {
+        // ARRANGE
+        let mut falling = disk([0.0, 0.0], 10.0, 2.0);
+        let mut struck = disk([0.0, 0.0], 10.0, 2.0);
+        let inertia = struck.moment_of_inertia();
+
+        // ACT
+        falling.apply_force(Vector2D::new(4.0, 0.0), Vector2D::new(0.0, 0.0));
+        falling.integrate(Vector2D::new(0.0, 980.0), 0.01);
+        struck.apply_impulse(Vector2D::new(0.0, 2.0), Vector2D::new(10.0, 0.0));
+        struck.integrate(Vector2D::new(0.0, 0.0), 0.01);
+        let settled = struck.velocity();
+        struck.integrate(Vector2D::new(0.0, 0.0), 0.01);
+
+        // ASSERT
+        assert_close(falling.velocity().linear.x, 0.02, 1e-9);
+        assert_close(falling.velocity().linear.y, 9.8, 1e-9);
+        assert_close(falling.pose().translation.y, 0.098, 1e-9);
+        assert_close(falling.velocity().angular, 0.0, 1e-6);
+        assert_close(settled.linear.y, 1.0, 1e-9);
+        assert_close(settled.angular, 20.0 / inertia, 1e-9);
+        // Impulses only act once.
+        assert_eq!(struck.velocity(), settled);
+    }
+
+    #[test]
+    fn test_particle_collisions_conserve_momentum() {
+        // ARRANGE
+        let mut manager = RigidBodyManager::new(
+            vec![disk([50.0, 50.0], 10.0, 0.05)],
+            [100, 100],
+            true,
+            WallMaterials::default(),
+        );
+        let mut particles = ParticleStore::from_particles(&[
+            Particle {
+                velocity: Vector2D::new(100.0, 20.0),
+                ..Particle::new(0, Vector2D::new(41.0, 48.0))
+            },
+            Particle {
+                velocity: Vector2D::new(0.0, -30.0),
+                ..Particle::new(1, Vector2D::new(50.0, 58.0))
+            },
+            Particle::new(2, Vector2D::new(10.0, 10.0)),
+        ]);
+        let momentum = |particles: &ParticleStore<f64>, manager: &RigidBodyManager<f64>| {
+            particles.iter().fold(
+                manager.rigid_bodies()[0].linear_momentum(),
+                |sum, particle| sum + particle.velocity * particle.mass,
+            )
+        };
+        let initial_momentum = momentum(&particles, &manager);
+
+        // ACT
+        manager.collide(&mut particles);
+        manager.integrate(Vector2D::new(0.0, 0.0), 0.01, &[]);
+        let final_momentum = momentum(&particles, &manager);
+
+        // ASSERT
+        assert!(particles.velocity(0).x < 0.0);
+        assert!(particles.velocity(1).y > 0.0);
+        assert_eq!(particles.velocity(2), Vector2D::new(0.0, 0.0));
+        assert_close(final_momentum.x, initial_momentum.x, 1e-9);
+        assert_close(final_momentum.y, initial_momentum.y, 1e-9);
+        let body = &manager.rigid_bodies()[0];
+        assert!(body.velocity().linear.x > 0.0);
+        assert!(body.velocity().linear.y < 0.0);
+    }
+
+    #[test]
+    fn test_wall_materials_stop_and_bounce_bodies() {
+        // ARRANGE
+        let materials = WallMaterials {
+            bottom: BoundaryMaterial {
+                restitution: 0.0,
+                ..BoundaryMaterial::default()
+            },
+            ..WallMaterials::default()
+        };
+        let falling_velocity = PoseVelocity::new(Vector2D::new(0.0, 100.0), 0.0);
+        let mut landing = disk([50.0, 85.0], 10.0, 1.0);
+        landing.set_state(Pose::new(Vector2D::new(0.0, 10.0), 0.0), falling_velocity);
+        let mut bouncing = disk([50.0, 15.0], 10.0, 1.0);
+        bouncing.set_state(
+            Pose::new(Vector2D::new(0.0, -10.0), 0.0),
+            PoseVelocity::new(Vector2D::new(0.0, -100.0), 0.0),
+        );
+
+        // ACT
+        landing.collide_with_walls([100.0, 100.0], &materials);
+        bouncing.collide_with_walls([100.0, 100.0], &materials);
+
+        // ASSERT
+        assert_close(landing.center().y, 90.0, 0.1);
+        assert_close(landing.velocity().linear.y, 0.0, 1e-6);
+        assert_close(landing.velocity().angular, 0.0, 1e-6);
+        assert_close(bouncing.center().y, 10.0, 0.1);
+        assert_close(bouncing.velocity().linear.y, 100.0, 1e-6);
+    }
+
+    #[test]
+    fn test_bodies_collide_with_active_obstacles() {
+        // ARRANGE
+        let mut floor = ShapeObstacle::new(Box::new(RoundedBoxShape::new(
+            Vector2D::new(0.0, 90.0),
+            Vector2D::new(100.0, 120.0),
+            0.0,
+        )));
+        floor.set_material(BoundaryMaterial {
+            restitution: 0.0,
+            ..BoundaryMaterial::default()
+        });
+        let mut landing = disk([50.0, 85.0], 10.0, 1.0);
+        landing.set_state(
+            Pose::identity(),
+            PoseVelocity::new(Vector2D::new(0.0, 100.0), 0.0),
+        );
+        let moving_right = PoseVelocity::new(Vector2D::new(100.0, 0.0), 0.0);
+        let dam: DamObstacle<f64> = DamObstacle::new(55.0, 100.0);
+        let mut held = disk([50.0, 50.0], 10.0, 1.0);
+        held.set_state(Pose::identity(), moving_right);
+        let mut broken_dam: DamObstacle<f64> = DamObstacle::new(55.0, 100.0);
+        broken_dam.set_active(false);
+        let mut manager = RigidBodyManager::new(
+            vec![disk([50.0, 50.0], 10.0, 1.0)],
+            [100, 100],
+            false,
+            WallMaterials::default(),
+        );
+        manager.rigid_bodies_mut()[0].set_state(Pose::identity(), moving_right);
+
+        // ACT
+        landing.collide_with_obstacle(&floor);
+        held.collide_with_obstacle(&dam);
+        manager.integrate(Vector2D::new(0.0, 0.0), 0.0, &[Box::new(broken_dam)]);
+
+        // ASSERT
+        assert_close(landing.center().y, 80.0, 0.1);
+        assert_close(landing.velocity().linear.y, 0.0, 1e-6);
+        assert_close(landing.velocity().angular, 0.0, 1e-6);
+        assert_close(held.center().x, 45.0, 0.1);
+        assert_close(held.velocity().linear.x, -100.0, 1e-6);
+        assert_close(manager.rigid_bodies()[0].center().x, 50.0, 1e-6);
+        assert_eq!(manager.rigid_bodies()[0].velocity(), moving_right);
+    }
+
+    #[test]
+    fn test_elastic_bodies_collide_with_each_other() {
+        // ARRANGE
+        let mut manager = RigidBodyManager::new(
+            vec![disk([40.0, 50.0], 10.0, 1.0), disk([59.0, 50.0], 10.0, 1.0)],
+            [100, 100],
+            false,
+            WallMaterials::default(),
+        );
+        manager.rigid_bodies_mut()[0].set_state(
+            Pose::identity(),
+            PoseVelocity::new(Vector2D::new(10.0, 0.0), 0.0),
+        );
+        manager.rigid_bodies_mut()[1].set_state(
+            Pose::identity(),
+            PoseVelocity::new(Vector2D::new(-10.0, 0.0), 0.0),
+        );
+
+        // ACT
+        manager.integrate(Vector2D::new(0.0, 0.0), 0.0, &[]);
+
+        // ASSERT
+        let [left, right] = manager.rigid_bodies() else {
+            panic!("expected two bodies");
+        };
+        assert!(right.center().x - left.center().x >= 19.9);
+        assert_close(left.center().x + right.center().x, 99.0, 1e-6);
+        assert_close(left.velocity().linear.x, -10.0, 1e-6);
+        assert_close(right.velocity().linear.x, 10.0, 1e-6);
+        assert_close(left.velocity().linear.y, 0.0, 1e-6);
+        assert_close(left.velocity().angular, 0.0, 1e-6);
+        assert_close(
+            (left.linear_momentum() + right.linear_momentum()).x,
+            0.0,
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn test_fluid_holds_up_light_bodies() {
+        // ARRANGE
+        // A light and a heavy disk, each half the density or twice the
+        // density of the water they are dropped into.
+        let contents = r#"
+            [domain]
+            width = 300
+            height = 200
+
+            [[fluid_blocks]]
+            top_left = [0.0, 100.0]
+            bottom_right = [300.0, 200.0]
+            particle_count = 1200
+
+            [[rigid_bodies]]
+            type = "circle"
+            center = [75.0, 90.0]
+            radius = 15.0
+            density = 0.000015
+
+            [[rigid_bodies]]
+            type = "circle"
+            center = [225.0, 90.0]
+            radius = 15.0
+            density = 0.00006
+        "#;
+        let scene = Scene::from_toml_str(contents).unwrap();
+        let mut simulation = Simulation::new(&scene);
+        let mut checkpoint = Vec::new();
+
+        // ACT
+        for _ in 0..150 {
+            simulation.update();
+        }
+        simulation.write_checkpoint(&mut checkpoint).unwrap();
+        let mut restored_simulation = Simulation::new(&scene);
+        restored_simulation
+            .read_checkpoint(&mut checkpoint.as_slice())
+            .unwrap();
+
+        // ASSERT
+        let [light, heavy] = simulation.rigid_bodies() else {
+            panic!("the scene has two rigid bodies");
+        };
+        // The water surface settles near y = 100 and the floor is at 200.
+        assert!(
+            light.center().y < 110.0,
+            "light body sank to {:?}",
+            light.center()
+        );
+        assert!(
+            heavy.center().y > 150.0,
+            "heavy body floats at {:?}",
+            heavy.center()
+        );
+        assert_eq!(simulation.outlines().len(), 2);
+        for (rigid_body, restored_rigid_body) in simulation
+            .rigid_bodies()
+            .iter()
+            .zip(restored_simulation.rigid_bodies())
+        {
+            assert_eq!(restored_rigid_body.pose(), rigid_body.pose());
+            assert_eq!(restored_rigid_body.velocity(), rigid_body.velocity());
+        }
+    }
+}
@@ -0,0 +1,477 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::boundary_material::{BoundaryMaterial, Friction};
+    use coding_challenges::fluid_simulation::grids::grid_type::GridType;
+    use coding_challenges::fluid_simulation::rigid_body::RigidBody;
+    use coding_challenges::fluid_simulation::scene::{ObstacleDescription, Scene, SceneError};
+    use coding_challenges::fluid_simulation::vtk_writer::VtkFormat;
+    use std::path::PathBuf;
+    use vector2d::Vector2D;
+
+    const MINIMAL_SCENE: &str = r#"
+        [domain]
+        width = 400
+        height = 300
+
+        [[fluid_blocks]]
+        top_left = [0.0, 0.0]
+        bottom_right = [100.0, 300.0]
+        particle_count = 50
+
+        [[fluid_blocks]]
+        top_left = [200.0, 0.0]
+        bottom_right = [300.0, 100.0]
+        particle_count = 25
+
+        [[obstacles]]
+        type = "rectangle"
+        top_left = [150.0, 200.0]
+        bottom_right = [180.0, 300.0]
+    "#;
+
+    #[test]
+    fn test_load_scene_with_defaults() {
+        // ACT
+        let scene = Scene::from_toml_str(MINIMAL_SCENE).unwrap();
+
+        // ASSERT
+        assert_eq!(scene.seed, 0);
+        assert_eq!(scene.box_dimensions(), [400, 300]);
+        assert_eq!(scene.particle_count(), 75);
+        assert_eq!(scene.parameters.smoothing_radius, 14.0);
+        assert!(scene.gravity.enabled);
+        assert!(matches!(
+            scene.obstacles[0].obstacle,
+            ObstacleDescription::Rectangle { .. }
+        ));
+    }
+
+    #[test]
+    fn test_load_bundled_dam_break_scene() {
+        // ACT
+        let scene = Scene::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/scenes/dam_break.toml"
+        ));
+
+        // ASSERT
+        let scene = scene.unwrap();
+        assert_eq!(scene.particle_count(), 6500);
+        assert_eq!(scene.obstacles.len(), 2);
+        assert_eq!(scene.attractors.len(), 1);
+    }
+
+    #[test]
+    fn test_load_bundled_falling_drop_scene() {
+        // ACT
+        let scene = Scene::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/scenes/falling_drop.toml"
+        ));
+
+        // ASSERT
+        let scene = scene.unwrap();
+        assert!(!scene.domain.walls);
+        assert_eq!(scene.domain.grid, GridType::Hashed);
+    }
+
+    #[test]
+    fn test_reject_open_domain_with_bounded_grid() {
+        // ARRANGE
+        let contents = MINIMAL_SCENE.replace("height = 300", "height = 300\nwalls = false");
+
+        // ACT
+        let error = Scene::from_toml_str(&contents).unwrap_err();
+
+        // ASSERT
+        assert!(error.to_string().contains("domain.grid"));
+    }
+
+    #[test]
+    fn test_load_scene_with_output() {
+        // ARRANGE
+        let contents = format!(
+            "{}\n[output]\ndirectory = \"output\"\nformats = [\"legacy\", \"xml\"]\n",
+            MINIMAL_SCENE
+        );
+
+        // ACT
+        let scene = Scene::from_toml_str(&contents).unwrap();
+
+        // ASSERT
+        let output = scene.output.unwrap();
+        assert_eq!(output.name, "particles");
+        assert_eq!(output.interval, 10);
+        assert_eq!(output.formats, vec![VtkFormat::Legacy, VtkFormat::Xml]);
+    }
+
+    #[test]
+    fn test_load_scene_with_recording() {
+        // ARRANGE
+        let contents = format!(
+            "{}\n[recording]\npath = \"run.rec\"\ncsv_path = \"run.csv\"\n",
+            MINIMAL_SCENE
+        );
+        let invalid_contents = format!(
+            "{}\n[recording]\npath = \"run.rec\"\ninterval = 0\n",
+            MINIMAL_SCENE
+        );
+
+        // ACT
+        let scene = Scene::from_toml_str(&contents).unwrap();
+        let error = Scene::from_toml_str(&invalid_contents).unwrap_err();
+
+        // ASSERT
+        let recording = scene.recording.unwrap();
+        assert_eq!(recording.path, PathBuf::from("run.rec"));
+        assert_eq!(recording.interval, 1);
+        assert_eq!(recording.csv_path, Some(PathBuf::from("run.csv")));
+        assert!(error.to_string().contains("recording.interval"));
+    }
+
+    #[test]
+    fn test_set_particle_count_keeps_block_proportions() {
+        // ARRANGE
+        let mut scene = Scene::from_toml_str(MINIMAL_SCENE).unwrap();
+
+        // ACT
+        scene.set_particle_count(301).unwrap();
+
+        // ASSERT
+        assert_eq!(scene.fluid_blocks[0].particle_count, 200);
+        assert_eq!(scene.fluid_blocks[1].particle_count, 101);
+    }
+
+    #[test]
+    fn test_reject_particle_count_without_fluid_blocks() {
+        // ARRANGE
+        let mut scene = Scene::from_toml_str(MINIMAL_SCENE).unwrap();
+        scene.fluid_blocks.clear();
+
+        // ACT
+        let error = scene.set_particle_count(100).unwrap_err();
+
+        // ASSERT
+        assert!(matches!(error, SceneError::Invalid(_)));
+        assert!(error.to_string().contains("without fluid blocks"));
+    }
+
+    #[test]
+    fn test_reject_invalid_parameter() {
+        // ARRANGE
+        let contents = format!("{}\n[parameters]\nsmoothing_radius = -2.0\n", MINIMAL_SCENE);
+
+        // ACT
+        let error = Scene::from_toml_str(&contents).unwrap_err();
+
+        // ASSERT
+        assert!(matches!(error, SceneError::Invalid(_)));
+        assert!(error.to_string().contains("parameters.smoothing_radius"));
+    }
+
+    #[test]
+    fn test_reject_pcisph_without_neighbors_at_rest() {
+        // ARRANGE
+        let pcisph = format!("{}\n[solver]\ntype = \"pcisph\"\n", MINIMAL_SCENE);
+        let sparse = format!("{}\n[parameters]\nparticle_mass = 0.006\n", pcisph);
+
+        // ACT
+        let scene = Scene::from_toml_str(&pcisph);
+        let error = Scene::from_toml_str(&sparse).unwrap_err();
+
+        // ASSERT
+        assert!(scene.is_ok());
+        assert!(matches!(error, SceneError::Invalid(_)));
+        assert!(error.to_string().contains("pressure kernel support"));
+    }
+
+    #[test]
+    fn test_reject_fluid_block_outside_domain() {
+        // ARRANGE
+        let contents = MINIMAL_SCENE.replace("[300.0, 100.0]", "[500.0, 100.0]");
+
+        // ACT
+        let error = Scene::from_toml_str(&contents).unwrap_err();
+
+        // ASSERT
+        assert!(error.to_string().contains("fluid_blocks[1]"));
+    }
+
+    #[test]
+    fn test_reject_unknown_obstacle_type() {
+        // ARRANGE
+        let contents = MINIMAL_SCENE.replace("\"rectangle\"", "\"triangle\"");
+
+        // ACT
+        let error = Scene::from_toml_str(&contents).unwrap_err();
+
+        // ASSERT
+        assert!(matches!(error, SceneError::Parse(_)));
+    }
+
+    #[test]
+    fn test_reject_unknown_keys_in_obstacles_and_rigid_bodies() {
+        // ARRANGE
+        let circle = "type = \"circle\"\ncenter = [50.0, 50.0]\nradius = 10.0";
+        let misspelled_obstacle = format!(
+            "{}\n[[obstacles]]\n{}\nradius_ = 1.0\n",
+            MINIMAL_SCENE, circle
+        );
+        let misspelled_motion = format!(
+            "{}\n[[obstacles]]\n{}\n[obstacles.motion]\ntype = \"rotating\"\nangular_velocity = 1.0\npivto = [1.0, 1.0]\n",
+            MINIMAL_SCENE, circle
+        );
+        let misspelled_rigid_body = format!(
+            "{}\n[[rigid_bodies]]\n{}\ndensity = 1.0\nangular_velocty = 1.0\n",
+            MINIMAL_SCENE, circle
+        );
+
+        // ACT
+        let errors: Vec<SceneError> = [
+            misspelled_obstacle,
+            misspelled_motion,
+            misspelled_rigid_body,
+        ]
+        .iter()
+        .map(|contents| Scene::from_toml_str(contents).unwrap_err())
+        .collect();
+
+        // ASSERT
+        for (error, key) in errors.iter().zip(["radius_", "pivto", "angular_velocty"]) {
+            assert!(matches!(error, SceneError::Parse(_)));
+            assert!(error.to_string().contains(key), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_load_scene_with_nested_shapes() {
+        // ARRANGE
+        let contents = format!(
+            "{}{}",
+            MINIMAL_SCENE,
+            r#"
+        [[obstacles]]
+        type = "difference"
+        shape = { type = "rounded_box", top_left = [200.0, 150.0], bottom_right = [300.0, 250.0], corner_radius = 10.0 }
+
+        [[obstacles.subtract]]
+        type = "circle"
+        center = [250.0, 150.0]
+        radius = 30.0
+
+        [[obstacles.subtract]]
+        type = "union"
+        shapes = [
+            { type = "rectangle", top_left = [290.0, 240.0], bottom_right = [320.0, 260.0] },
+            { type = "capsule", start = [200.0, 250.0], end = [220.0, 250.0], radius = 5.0 },
+        ]
+        "#
+        );
+
+        // ACT
+        let scene = Scene::from_toml_str(&contents).unwrap();
+        let shape = scene.obstacles[1].obstacle.shape::<f32>().unwrap();
+
+        // ASSERT
+        assert!(matches!(
+            &scene.obstacles[1].obstacle,
+            ObstacleDescription::Difference { subtract, .. } if subtract.len() == 2
+        ));
+        assert!(shape.distance(Vector2D::new(250.0, 220.0)) < 0.0);
+        assert!(shape.distance(Vector2D::new(250.0, 160.0)) > 0.0);
+        assert!(shape.distance(Vector2D::new(295.0, 245.0)) > 0.0);
+    }
+
+    #[test]
+    fn test_reject_invalid_nested_shape() {
+        // ARRANGE
+        let shape = |description: &str| {
+            format!(
+                "{}\n[[obstacles]]\ntype = \"union\"\nshapes = [{}]\n",
+                MINIMAL_SCENE, description
+            )
+        };
+        let negative_radius = shape("{ type = \"circle\", center = [10.0, 10.0], radius = -1.0 }");
+        let short_polygon = shape("{ type = \"polygon\", vertices = [[0.0, 0.0], [10.0, 0.0]] }");
+        let nested_dam = shape("{ type = \"dam\", x_position = 10.0 }");
+        let wide_corner = shape(
+            "{ type = \"rounded_box\", top_left = [0.0, 0.0], bottom_right = [20.0, 10.0], corner_radius = 6.0 }",
+        );
+
+        // ACT
+        let errors: Vec<String> = [negative_radius, short_polygon, nested_dam, wide_corner]
+            .iter()
+            .map(|contents| Scene::from_toml_str(contents).unwrap_err().to_string())
+            .collect();
+
+        // ASSERT
+        assert!(errors[0].contains("obstacles[1].shapes[0].radius"));
+        assert!(errors[1].contains("at least 3 vertices"));
+        assert!(errors[2].contains("obstacles[1].shapes[0]: a dam"));
+        assert!(errors[3].contains("corner_radius"));
+    }
+
+    #[test]
+    fn test_load_scene_with_boundary_materials() {
+        // ARRANGE
+        let contents = format!(
+            "{}{}",
+            MINIMAL_SCENE,
+            r#"
+        [obstacles.material]
+        restitution = 0.25
+        friction = { coulomb = 0.4 }
+
+        [domain.wall_materials.bottom]
+        friction = "no_slip"
+        "#
+        );
+        let invalid_contents = contents.replace("0.25", "1.5");
+
+        // ACT
+        let scene = Scene::from_toml_str(&contents).unwrap();
+        let error = Scene::from_toml_str(&invalid_contents).unwrap_err();
+
+        // ASSERT
+        assert_eq!(
+            scene.obstacles[0].material,
+            BoundaryMaterial {
+                restitution: 0.25,
+                friction: Friction::Coulomb(0.4),
+            }
+        );
+        assert_eq!(
+            scene.domain.wall_materials.bottom.friction,
+            Friction::NoSlip
+        );
+        assert_eq!(scene.domain.wall_materials.bottom.restitution, 1.0);
+        assert_eq!(scene.domain.wall_materials.top, BoundaryMaterial::default());
+        assert!(error
+            .to_string()
+            .contains("obstacles[0].material.restitution"));
+    }
+
+    #[test]
+    fn test_reject_invalid_motion() {
+        // ARRANGE
+        let moving = |obstacle: &str, motion: &str| {
+            format!(
+                "{}\n[[obstacles]]\n{}\n[obstacles.motion]\n{}\n",
+                MINIMAL_SCENE, obstacle, motion
+            )
+        };
+        let circle = "type = \"circle\"\ncenter = [50.0, 50.0]\nradius = 10.0";
+        let valid = moving(
+            circle,
+            "type = \"rotating\"\nangular_velocity = 1.0\npivot = [50.0, 40.0]",
+        );
+        let moving_dam = moving(
+            "type = \"dam\"\nx_position = 10.0",
+            "type = \"rotating\"\nangular_velocity = 1.0",
+        );
+        let unordered_keyframes = moving(
+            circle,
+            "type = \"keyframes\"\nkeyframes = [{ time = 1.0 }, { time = 0.5 }]",
+        );
+        let zero_frequency = moving(circle, "type = \"oscillating\"\nfrequency = 0.0");
+
+        // ACT
+        let scene = Scene::from_toml_str(&valid).unwrap();
+        let errors: Vec<String> = [moving_dam, unordered_keyframes, zero_frequency]
+            .iter()
+            .map(|contents| Scene::from_toml_str(contents).unwrap_err().to_string())
+            .collect();
+
+        // ASSERT
+        assert_eq!(
+            scene.obstacles[1].motion.as_ref().unwrap().pivot,
+            Some([50.0, 40.0])
+        );
+        assert!(errors[0].contains("obstacles[1]: a dam cannot move"));
+        assert!(errors[1].contains("obstacles[1].motion.keyframes[1]"));
+        assert!(errors[2].contains("obstacles[1].motion.frequency"));
+    }
+
+    #[test]
+    fn test_load_scene_with_rigid_bodies() {
+        // ARRANGE
+        let contents = format!(
+            "{}{}",
+            MINIMAL_SCENE,
+            r#"
+            [[rigid_bodies]]
+            type = "rounded_box"
+            top_left = [20.0, 20.0]
+            bottom_right = [60.0, 40.0]
+            corner_radius = 2.0
+            density = 0.00001
+            velocity = [5.0, 0.0]
+
+            [rigid_bodies.material]
+            restitution = 0.2
+
+            [[rigid_bodies]]
+            type = "circle"
+            center = [100.0, 50.0]
+            radius = 10.0
+            mass = 0.5
+            moment_of_inertia = 40.0
+            "#
+        );
+
+        // ACT
+        let scene = Scene::from_toml_str(&contents).unwrap();
+        let rigid_bodies: Vec<RigidBody> = scene
+            .rigid_bodies
+            .iter()
+            .map(|rigid_body| rigid_body.build().unwrap())
+            .collect();
+
+        // ASSERT
+        assert!(matches!(
+            scene.rigid_bodies[0].shape,
+            ObstacleDescription::RoundedBox { .. }
+        ));
+        assert_eq!(scene.rigid_bodies[0].material.restitution, 0.2);
+        assert_eq!(rigid_bodies[0].velocity().linear, Vector2D::new(5.0, 0.0));
+        assert!((rigid_bodies[0].center().x - 40.0).abs() < 1e-3);
+        assert_eq!(rigid_bodies[1].mass(), 0.5);
+        assert_eq!(rigid_bodies[1].moment_of_inertia(), 40.0);
+    }
+
+    #[test]
+    fn test_reject_invalid_rigid_body() {
+        // ARRANGE
+        let rigid_body = |body: &str| format!("{}\n[[rigid_bodies]]\n{}\n", MINIMAL_SCENE, body);
+        let circle = "type = \"circle\"\ncenter = [50.0, 50.0]\nradius = 10.0";
+        let weightless = rigid_body(circle);
+        let both = rigid_body(&format!("{}\ndensity = 1.0\nmass = 1.0", circle));
+        let negative_mass = rigid_body(&format!("{}\nmass = -1.0", circle));
+        let dam = rigid_body("type = \"dam\"\nx_position = 10.0\nmass = 1.0");
+        let collinear = rigid_body(
+            "type = \"polygon\"
+            vertices = [[10.0, 10.0], [20.0, 20.0], [30.0, 30.0]]
+            density = 1.0",
+        );
+        let hollowed = rigid_body(
+            "type = \"difference\"
+            shape = { type = \"circle\", center = [50.0, 50.0], radius = 10.0 }
+            subtract = [{ type = \"circle\", center = [50.0, 50.0], radius = 20.0 }]
+            density = 1.0",
+        );
+
+        // ACT
+        let errors: Vec<String> = [weightless, both, negative_mass, dam, collinear, hollowed]
+            .iter()
+            .map(|contents| Scene::from_toml_str(contents).unwrap_err().to_string())
+            .collect();
+
+        // ASSERT
+        assert!(errors[0].contains("rigid_bodies[0]: give either a density or a mass"));
+        assert!(errors[1].contains("rigid_bodies[0]: give either a density or a mass"));
+        assert!(errors[2].contains("rigid_bodies[0].mass must be positive"));
+        assert!(errors[3].contains("rigid_bodies[0]: a dam cannot be a rigid body"));
+        assert!(errors[4].contains("rigid_bodies[0]: the polygon vertices enclose no area"));
+        assert!(errors[5].contains("rigid_bodies[0]: the shape encloses no area"));
+    }
+}
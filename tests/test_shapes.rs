@@ -0,0 +1,180 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::obstacles::obstacle_trait::{Obstacle, Outline};
+    use coding_challenges::fluid_simulation::obstacles::shape_obstacle::ShapeObstacle;
+    use coding_challenges::fluid_simulation::obstacles::shapes::capsule_shape::CapsuleShape;
+    use coding_challenges::fluid_simulation::obstacles::shapes::circle_shape::CircleShape;
+    use coding_challenges::fluid_simulation::obstacles::shapes::difference_shape::DifferenceShape;
+    use coding_challenges::fluid_simulation::obstacles::shapes::polygon_shape::PolygonShape;
+    use coding_challenges::fluid_simulation::obstacles::shapes::rounded_box_shape::RoundedBoxShape;
+    use coding_challenges::fluid_simulation::obstacles::shapes::shape_trait::Shape;
+    use coding_challenges::fluid_simulation::obstacles::shapes::union_shape::UnionShape;
+    use coding_challenges::fluid_simulation::particle::Particle;
+    use vector2d::Vector2D;
+
+    const TOLERANCE: f32 = 1e-3;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < TOLERANCE,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_circle_and_capsule_distances() {
+        // ARRANGE
+        let circle = CircleShape::new(Vector2D::new(100.0, 100.0), 10.0);
+        let capsule = CapsuleShape::new(Vector2D::new(0.0, 0.0), Vector2D::new(100.0, 0.0), 5.0);
+
+        // ACT
+        let circle_distances = [
+            circle.distance(Vector2D::new(100.0, 100.0)),
+            circle.distance(Vector2D::new(130.0, 100.0)),
+        ];
+        let capsule_distances = [
+            capsule.distance(Vector2D::new(50.0, 2.0)),
+            capsule.distance(Vector2D::new(50.0, 20.0)),
+            capsule.distance(Vector2D::new(-8.0, 0.0)),
+        ];
+        let normal = circle.normal(Vector2D::new(100.0, 90.0));
+
+        // ASSERT
+        assert_close(circle_distances[0], -10.0);
+        assert_close(circle_distances[1], 20.0);
+        assert_close(capsule_distances[0], -3.0);
+        assert_close(capsule_distances[1], 15.0);
+        assert_close(capsule_distances[2], 3.0);
+        assert_close(normal.x, 0.0);
+        assert_close(normal.y, -1.0);
+    }
+
+    #[test]
+    fn test_concave_polygon_distances() {
+        // ARRANGE
+        // L-shaped polygon, the notch at the top right is outside.
+        let polygon = PolygonShape::new(vec![
+            Vector2D::new(0.0, 0.0),
+            Vector2D::new(50.0, 0.0),
+            Vector2D::new(50.0, 50.0),
+            Vector2D::new(100.0, 50.0),
+            Vector2D::new(100.0, 100.0),
+            Vector2D::new(0.0, 100.0),
+        ]);
+
+        // ACT
+        let inside = polygon.distance(Vector2D::new(25.0, 25.0));
+        let notch = polygon.distance(Vector2D::new(75.0, 25.0));
+        let outside = polygon.distance(Vector2D::new(120.0, 75.0));
+        let normal = polygon.normal(Vector2D::new(75.0, 52.0));
+
+        // ASSERT
+        assert_close(inside, -25.0);
+        assert_close(notch, 25.0);
+        assert_close(outside, 20.0);
+        assert_close(normal.x, 0.0);
+        assert_close(normal.y, -1.0);
+    }
+
+    #[test]
+    fn test_rounded_box_distances() {
+        // ARRANGE
+        let rounded_box =
+            RoundedBoxShape::new(Vector2D::new(0.0, 0.0), Vector2D::new(100.0, 50.0), 10.0);
+
+        // ACT
+        let center = rounded_box.distance(Vector2D::new(50.0, 25.0));
+        let side = rounded_box.distance(Vector2D::new(50.0, -5.0));
+        // The corner at the origin is rounded off around (10, 10).
+        let corner = rounded_box.distance(Vector2D::new(0.0, 0.0));
+
+        // ASSERT
+        assert_close(center, -25.0);
+        assert_close(side, 5.0);
+        assert_close(corner, 200.0_f32.sqrt() - 10.0);
+    }
+
+    #[test]
+    fn test_union_and_difference_distances() {
+        // ARRANGE
+        let union = UnionShape::new(vec![
+            Box::new(CircleShape::new(Vector2D::new(0.0, 0.0), 10.0)),
+            Box::new(CircleShape::new(Vector2D::new(30.0, 0.0), 10.0)),
+        ]);
+        let ring = DifferenceShape::new(
+            Box::new(CircleShape::new(Vector2D::new(0.0, 0.0), 20.0)),
+            Box::new(CircleShape::new(Vector2D::new(0.0, 0.0), 10.0)),
+        );
+
+        // ACT
+        let union_distances = [
+            union.distance(Vector2D::new(30.0, 0.0)),
+            union.distance(Vector2D::new(15.0, 0.0)),
+        ];
+        let ring_distances = [
+            ring.distance(Vector2D::new(0.0, 0.0)),
+            ring.distance(Vector2D::new(15.0, 0.0)),
+            ring.distance(Vector2D::new(30.0, 0.0)),
+        ];
+        let [top_left, bottom_right] = union.bounds();
+
+        // ASSERT
+        assert_close(union_distances[0], -10.0);
+        assert_close(union_distances[1], 5.0);
+        assert_close(ring_distances[0], 10.0);
+        assert_close(ring_distances[1], -5.0);
+        assert_close(ring_distances[2], 10.0);
+        assert_eq!(top_left, Vector2D::new(-10.0, -10.0));
+        assert_eq!(bottom_right, Vector2D::new(40.0, 10.0));
+    }
+
+    #[test]
+    fn test_shape_obstacle_projects_and_reflects_normal_velocity() {
+        // ARRANGE
+        let obstacle = ShapeObstacle::new(Box::new(CircleShape::new(
+            Vector2D::new(100.0, 100.0),
+            20.0,
+        )));
+        let mut entering = Particle {
+            velocity: Vector2D::new(5.0, 10.0),
+            ..Particle::new(0, Vector2D::new(100.0, 85.0))
+        };
+        let mut leaving = Particle {
+            velocity: Vector2D::new(5.0, -10.0),
+            ..Particle::new(1, Vector2D::new(100.0, 85.0))
+        };
+
+        // ACT
+        obstacle.apply_obstruction_boundary(&mut entering);
+        obstacle.apply_obstruction_boundary(&mut leaving);
+
+        // ASSERT
+        assert_close(entering.position.x, 100.0);
+        assert_close(entering.position.y, 80.0);
+        assert_close(entering.velocity.x, 5.0);
+        assert_close(entering.velocity.y, -10.0);
+        assert_close(leaving.velocity.y, -10.0);
+        assert!(!obstacle.is_breakable());
+    }
+
+    #[test]
+    fn test_shape_obstacle_outline_follows_surface() {
+        // ARRANGE
+        let circle: CircleShape = CircleShape::new(Vector2D::new(100.0, 100.0), 20.0);
+        let obstacle = ShapeObstacle::new(Box::new(circle));
+
+        // ACT
+        let outline = obstacle.outline();
+
+        // ASSERT
+        let Outline::Segments(segments) = outline else {
+            panic!("shape outlines are segments");
+        };
+        assert!(segments.len() > 20);
+        for point in segments.iter().flatten() {
+            assert!(obstacle.shape().distance(*point).abs() < 0.5);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::grids::grid_type::GridType;
+    use coding_challenges::fluid_simulation::scene::Scene;
+    use coding_challenges::fluid_simulation::simulation::Simulation;
+
+    #[test]
+    fn test_update_keeps_particles_inside_box() {
+        // ARRANGE
+        let box_dimensions: [usize; 2] = [1000, 800];
+        let mut simulation = Simulation::new(&Scene::dam_break(box_dimensions, 200));
+
+        // ACT
+        for _ in 0..20 {
+            simulation.update();
+        }
+
+        // ASSERT
+        for particle in simulation.particles.iter() {
+            assert!(particle.position.x.is_finite() && particle.position.y.is_finite());
+            assert!(particle.position.x >= 0.0 && particle.position.x <= box_dimensions[0] as f32);
+            assert!(particle.position.y >= 0.0 && particle.position.y <= box_dimensions[1] as f32);
+        }
+    }
+
+    #[test]
+    fn test_update_in_double_precision_keeps_particles_inside_box() {
+        // ARRANGE
+        let box_dimensions: [usize; 2] = [1000, 800];
+        let mut simulation = Simulation::<f64>::from_scene(&Scene::dam_break(box_dimensions, 200));
+
+        // ACT
+        for _ in 0..20 {
+            simulation.update();
+        }
+
+        // ASSERT
+        for particle in simulation.particles.iter() {
+            assert!(particle.position.x.is_finite() && particle.position.y.is_finite());
+            assert!(particle.position.x >= 0.0 && particle.position.x <= box_dimensions[0] as f64);
+            assert!(particle.position.y >= 0.0 && particle.position.y <= box_dimensions[1] as f64);
+        }
+        assert!(simulation.kinetic_energy() > 0.0);
+    }
+
+    fn run_dam_break(seed: u64, threads: usize) -> Vec<[u32; 4]> {
+        let mut scene = Scene::dam_break([1000, 800], 300);
+        scene.seed = seed;
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        pool.install(|| {
+            let mut simulation = Simulation::new(&scene);
+            simulation.break_dam();
+            for _ in 0..30 {
+                simulation.update();
+            }
+            simulation
+                .particles
+                .iter()
+                .map(|particle| {
+                    [
+                        particle.position.x.to_bits(),
+                        particle.position.y.to_bits(),
+                        particle.velocity.x.to_bits(),
+                        particle.velocity.y.to_bits(),
+                    ]
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_runs_with_same_seed_are_identical() {
+        // ACT
+        let first_run = run_dam_break(7, 4);
+        let second_run = run_dam_break(7, 4);
+        let other_seed_run = run_dam_break(8, 4);
+
+        // ASSERT
+        assert_eq!(first_run, second_run);
+        assert_ne!(first_run, other_seed_run);
+    }
+
+    #[test]
+    fn test_runs_with_same_seed_are_identical_across_thread_counts() {
+        // ACT
+        let single_thread_run = run_dam_break(7, 1);
+        let multi_thread_run = run_dam_break(7, 3);
+
+        // ASSERT
+        assert_eq!(single_thread_run, multi_thread_run);
+    }
+
+    #[test]
+    fn test_update_without_particles() {
+        // ARRANGE
+        let mut simulation = Simulation::new(&Scene::dam_break([1000, 800], 0));
+
+        // ACT
+        simulation.update();
+
+        // ASSERT
+        assert!(simulation.particles.is_empty());
+    }
+
+    #[test]
+    fn test_update_lets_particles_leave_open_domain() {
+        // ARRANGE
+        let box_dimensions: [usize; 2] = [1000, 800];
+        let mut scene = Scene::dam_break(box_dimensions, 200);
+        scene.domain.walls = false;
+        scene.domain.grid = GridType::Hashed;
+        scene.obstacles.clear();
+        let mut simulation = Simulation::new(&scene);
+
+        // ACT
+        for _ in 0..150 {
+            simulation.update();
+        }
+
+        // ASSERT
+        let mut left_domain = false;
+        for particle in simulation.particles.iter() {
+            assert!(particle.position.x.is_finite() && particle.position.y.is_finite());
+            left_domain |= particle.position.y > box_dimensions[1] as f32;
+        }
+        assert!(left_domain);
+    }
+}
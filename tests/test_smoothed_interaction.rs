@@ -0,0 +1,246 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::equation_of_state::{EquationOfState, PressureLaw};
+    use coding_challenges::fluid_simulation::kernels::kernel_type::KernelType;
+    use coding_challenges::fluid_simulation::particle::Particle;
+    use coding_challenges::fluid_simulation::particle_store::ParticleStore;
+    use coding_challenges::fluid_simulation::smoothed_interaction::{
+        PressureGradient, SmoothedInteraction,
+    };
+    use vector2d::Vector2D;
+
+    fn build_interaction(pressure_gradient: PressureGradient) -> SmoothedInteraction {
+        let equation_of_state = EquationOfState::new(
+            PressureLaw::Tait {
+                speed_of_sound: 10.0,
+                gamma: 7.0,
+            },
+            1.0,
+            false,
+        );
+        SmoothedInteraction::new(
+            equation_of_state,
+            pressure_gradient,
+            1.0,
+            0.0,
+            KernelType::Sb.build(),
+            KernelType::Spiky.build(),
+            KernelType::Viscosity.build(),
+        )
+    }
+
+    #[test]
+    fn test_tait_pressure_vanishes_at_target_density() {
+        // ARRANGE
+        let equation_of_state: EquationOfState = EquationOfState::new(
+            PressureLaw::Tait {
+                speed_of_sound: 10.0,
+                gamma: 7.0,
+            },
+            2.0,
+            false,
+        );
+
+        // ACT
+        let rest_pressure = equation_of_state.pressure(2.0);
+        let compressed_pressure = equation_of_state.pressure(2.0 * 1.001);
+
+        // ASSERT
+        assert!(rest_pressure.abs() < 1e-6);
+        let slope = compressed_pressure / (2.0 * 0.001);
+        assert!((slope - 100.0).abs() < 1.0, "dp/drho = {}", slope);
+    }
+
+    #[test]
+    fn test_clamp_negative_pressure() {
+        // ARRANGE
+        let law = PressureLaw::Linear {
+            pressure_multiplier: 5.0,
+        };
+        let unclamped = EquationOfState::new(law, 1.0, false);
+        let clamped = EquationOfState::new(law, 1.0, true);
+
+        // ASSERT
+        assert_eq!(unclamped.pressure(0.5), -2.5);
+        assert_eq!(clamped.pressure(0.5), 0.0);
+        assert_eq!(clamped.pressure(1.5), 2.5);
+    }
+
+    #[test]
+    fn test_symmetric_pressure_gradient_conserves_momentum() {
+        // ARRANGE
+        let interaction = build_interaction(PressureGradient::Symmetric);
+        let particles = ParticleStore::from_particles(&[
+            Particle::new(0, Vector2D::new(0.0, 0.0)),
+            Particle::new(1, Vector2D::new(0.4, 0.3)),
+        ]);
+        let densities = vec![1.2, 0.9];
+        let pressures: Vec<f32> = densities
+            .iter()
+            .map(|&density| interaction.convert_density_to_pressure(density))
+            .collect();
+
+        // ACT
+        let accelerations: Vec<Vector2D<f32>> = (0..2)
+            .map(|index| {
+                interaction.calculate_pressure_acceleration(
+                    index,
+                    &[0, 1],
+                    &particles,
+                    &densities,
+                    &pressures,
+                )
+            })
+            .collect();
+
+        // ASSERT
+        let momentum =
+            accelerations[0] * particles.masses[0] + accelerations[1] * particles.masses[1];
+        assert!(accelerations[0].length() > 0.0);
+        assert!(momentum.length() < 1e-6 * accelerations[0].length());
+    }
+
+    #[test]
+    fn test_coincident_pair_is_separated_deterministically() {
+        // ARRANGE
+        let interaction = build_interaction(PressureGradient::Shared);
+        let particles = vec![
+            Particle::new(0, Vector2D::new(0.5, 0.5)),
+            Particle::new(1, Vector2D::new(0.5, 0.5)),
+        ];
+        let store = ParticleStore::from_particles(&particles);
+        let densities = vec![1.1, 1.1];
+        let pressures = vec![interaction.convert_density_to_pressure(1.1); 2];
+        let neighbors = [0, 1];
+
+        // ACT
+        let accelerations: Vec<Vector2D<f32>> = (0..2)
+            .map(|index| {
+                interaction.calculate_pressure_acceleration(
+                    index, &neighbors, &store, &densities, &pressures,
+                )
+            })
+            .collect();
+        let particle_acceleration = interaction.calculate_pressure_acceleration_from_particles(
+            0,
+            0..2,
+            &particles,
+            &densities,
+            &pressures,
+        );
+        let repeated_acceleration = interaction
+            .calculate_pressure_acceleration(0, &neighbors, &store, &densities, &pressures);
+
+        // ASSERT
+        assert!(accelerations[0].length() > 0.0);
+        assert_eq!(accelerations[0].x, -accelerations[1].x);
+        assert_eq!(accelerations[0].y, -accelerations[1].y);
+        assert_eq!(particle_acceleration.x, accelerations[0].x);
+        assert_eq!(particle_acceleration.y, accelerations[0].y);
+        assert_eq!(repeated_acceleration.x, accelerations[0].x);
+        assert_eq!(repeated_acceleration.y, accelerations[0].y);
+    }
+
+    #[test]
+    fn test_compressed_pair_repels() {
+        // ARRANGE
+        let interaction = build_interaction(PressureGradient::Shared);
+        let particles = ParticleStore::from_particles(&[
+            Particle::new(0, Vector2D::new(0.0, 0.0)),
+            Particle::new(1, Vector2D::new(0.5, 0.0)),
+        ]);
+        let densities = vec![1.1, 1.1];
+        let pressures = vec![interaction.convert_density_to_pressure(1.1); 2];
+
+        // ACT
+        let acceleration = interaction.calculate_pressure_acceleration(
+            0,
+            &[0, 1],
+            &particles,
+            &densities,
+            &pressures,
+        );
+
+        // ASSERT
+        assert!(acceleration.x < 0.0);
+        assert!(acceleration.y.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_store_calculations_match_particle_calculations() {
+        // ARRANGE
+        let interaction = SmoothedInteraction::new(
+            EquationOfState::new(
+                PressureLaw::Linear {
+                    pressure_multiplier: 5.0,
+                },
+                1.0,
+                false,
+            ),
+            PressureGradient::Symmetric,
+            1.0,
+            0.1,
+            KernelType::Sb.build(),
+            KernelType::Spiky.build(),
+            KernelType::Viscosity.build(),
+        );
+        let particles: Vec<Particle> = (0..100)
+            .map(|index| Particle {
+                velocity: Vector2D::new((index % 7) as f32, (index % 5) as f32),
+                ..Particle::new(
+                    index,
+                    Vector2D::new(
+                        (index * 37 % 100) as f32 / 50.0,
+                        (index * 61 % 100) as f32 / 50.0,
+                    ),
+                )
+            })
+            .collect();
+        let store = ParticleStore::from_particles(&particles);
+        let neighbors: Vec<usize> = (0..particles.len()).collect();
+        let densities: Vec<f32> = (0..particles.len())
+            .map(|index| {
+                interaction.calculate_density_from_particles(index, 0..particles.len(), &particles)
+            })
+            .collect();
+        let pressures: Vec<f32> = densities
+            .iter()
+            .map(|&density| interaction.convert_density_to_pressure(density))
+            .collect();
+
+        for index in 0..particles.len() {
+            // ACT
+            let density = interaction.calculate_density(index, &neighbors, &store);
+            let pressure_acceleration = interaction
+                .calculate_pressure_acceleration(index, &neighbors, &store, &densities, &pressures);
+            let viscosity_acceleration =
+                interaction.calculate_viscosity_acceleration(index, &neighbors, &store, &densities);
+
+            // ASSERT
+            assert!((density - densities[index]).abs() < 1e-4 * densities[index]);
+            let expected_pressure_acceleration = interaction
+                .calculate_pressure_acceleration_from_particles(
+                    index,
+                    0..particles.len(),
+                    &particles,
+                    &densities,
+                    &pressures,
+                );
+            assert!(
+                (pressure_acceleration - expected_pressure_acceleration).length()
+                    <= 1e-4 * expected_pressure_acceleration.length().max(1.0)
+            );
+            let expected_viscosity_acceleration = interaction
+                .calculate_viscosity_acceleration_from_particles(
+                    index,
+                    0..particles.len(),
+                    &particles,
+                    &densities,
+                );
+            assert!(
+                (viscosity_acceleration - expected_viscosity_acceleration).length()
+                    <= 1e-4 * expected_viscosity_acceleration.length().max(1.0)
+            );
+        }
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::scene::{FluidBlockDescription, Scene};
+    use coding_challenges::fluid_simulation::simulation::Simulation;
+    use coding_challenges::fluid_simulation::solvers::solver_type::SolverType;
+    use vector2d::Vector2D;
+
+    const COLUMNS: usize = 15;
+
+    // A square block of particles on a lattice 10% tighter than the rest
+    // spacing, so every interior particle starts out compressed.
+    fn build_compressed_block(solver_type: SolverType) -> (Scene, Simulation) {
+        let mut scene = Scene::dam_break([200, 200], 0);
+        scene.gravity.enabled = false;
+        scene.obstacles.clear();
+        scene.solver.solver_type = solver_type;
+        scene.fluid_blocks = vec![FluidBlockDescription {
+            top_left: [60.0, 60.0],
+            bottom_right: [140.0, 140.0],
+            particle_count: COLUMNS * COLUMNS,
+        }];
+        let mut simulation = Simulation::new(&scene);
+        let parameters = &scene.parameters;
+        let spacing = 0.9 * (parameters.particle_mass / parameters.target_density).sqrt();
+        simulation.particles.par_update(|index, particle| {
+            particle.position = Vector2D::new(
+                60.0 + (index % COLUMNS) as f32 * spacing,
+                60.0 + (index / COLUMNS) as f32 * spacing,
+            );
+        });
+        (scene, simulation)
+    }
+
+    #[test]
+    fn test_explicit_solver_runs_a_single_iteration() {
+        // ARRANGE
+        let (_, mut simulation) = build_compressed_block(SolverType::Explicit);
+
+        // ACT
+        simulation.update();
+
+        // ASSERT
+        let statistics = simulation.solver_statistics();
+        assert_eq!(statistics.iterations, 1);
+        assert!(statistics.density_error > 0.0);
+    }
+
+    #[test]
+    fn test_pcisph_solver_reaches_density_tolerance() {
+        // ARRANGE
+        let (scene, mut simulation) = build_compressed_block(SolverType::Pcisph);
+
+        // ACT
+        simulation.update();
+
+        // ASSERT
+        let statistics = simulation.solver_statistics();
+        assert!(statistics.iterations < scene.solver.max_iterations);
+        assert!(
+            statistics.density_error <= scene.solver.density_tolerance,
+            "density error {} after {} iterations",
+            statistics.density_error,
+            statistics.iterations
+        );
+        assert!(simulation
+            .pressures()
+            .iter()
+            .all(|pressure| *pressure >= 0.0));
+    }
+
+    #[test]
+    fn test_dfsph_solver_reaches_density_tolerance() {
+        // ARRANGE
+        let (scene, mut simulation) = build_compressed_block(SolverType::Dfsph);
+
+        // ACT
+        simulation.update();
+
+        // ASSERT
+        let statistics = simulation.solver_statistics();
+        assert!(statistics.iterations < scene.solver.max_iterations);
+        assert!(
+            statistics.density_error <= scene.solver.density_tolerance,
+            "density error {} after {} iterations",
+            statistics.density_error,
+            statistics.iterations
+        );
+        assert!(simulation
+            .pressures()
+            .iter()
+            .all(|pressure| *pressure >= 0.0));
+    }
+
+    #[test]
+    fn test_dfsph_solver_removes_converging_velocities() {
+        // ARRANGE
+        let (scene, mut simulation) = build_compressed_block(SolverType::Dfsph);
+        let center = Vector2D::new(100.0, 100.0);
+        simulation.particles.par_update(|_, particle| {
+            particle.velocity = (center - particle.position) * 2.0;
+        });
+
+        // ACT
+        simulation.update();
+
+        // ASSERT
+        let statistics = simulation.solver_statistics();
+        assert!(statistics.divergence_iterations >= 1);
+        assert!(statistics.divergence_iterations < scene.solver.max_iterations);
+        assert!(
+            statistics.divergence_error <= scene.solver.divergence_tolerance,
+            "divergence error {} after {} iterations",
+            statistics.divergence_error,
+            statistics.divergence_iterations
+        );
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::scene::Scene;
+    use coding_challenges::fluid_simulation::simulation::Simulation;
+    use coding_challenges::fluid_simulation::time_step_manager::TimeStepManager;
+
+    fn build_manager(is_adaptive: bool) -> TimeStepManager {
+        TimeStepManager::new(0.01, is_adaptive, 0.4, 0.25, 0.125, 0.0001, 0.01)
+    }
+
+    #[test]
+    fn test_fixed_time_step_uses_frame_time() {
+        // ARRANGE
+        let time_step_manager = build_manager(false);
+
+        // ACT
+        let delta_time = time_step_manager.calculate_delta_time(1000.0, 1e6, 14.0, 1000.0);
+
+        // ASSERT
+        assert_eq!(delta_time, 0.01);
+    }
+
+    #[test]
+    fn test_adaptive_time_step_criteria() {
+        // ARRANGE
+        let time_step_manager = build_manager(true);
+
+        // ACT
+        let at_rest = time_step_manager.calculate_delta_time(0.0, 0.0, 14.0, 0.0);
+        let speed_limited = time_step_manager.calculate_delta_time(1400.0, 0.0, 14.0, 0.0);
+        let force_limited = time_step_manager.calculate_delta_time(0.0, 14000.0, 14.0, 0.0);
+        let viscosity_limited = time_step_manager.calculate_delta_time(0.0, 0.0, 14.0, 9800.0);
+        let bounded = time_step_manager.calculate_delta_time(1e9, 0.0, 14.0, 0.0);
+
+        // ASSERT
+        assert_eq!(at_rest, 0.01);
+        assert!((speed_limited - 0.004).abs() < 1e-6);
+        assert!((force_limited - 0.25 * 0.001f32.sqrt()).abs() < 1e-6);
+        assert!((viscosity_limited - 0.0025).abs() < 1e-6);
+        assert_eq!(bounded, 0.0001);
+    }
+
+    #[test]
+    fn test_sub_step_fits_remaining_frame_time() {
+        // ARRANGE
+        let time_step_manager = build_manager(true);
+
+        // ACT
+        let sub_step = time_step_manager.calculate_sub_step(0.004, 0.01);
+        let last_sub_step = time_step_manager.calculate_sub_step(0.004, 0.00405);
+
+        // ASSERT
+        assert_eq!(sub_step, 0.004);
+        assert_eq!(last_sub_step, 0.00405);
+    }
+
+    #[test]
+    fn test_update_sub_steps_fast_particles() {
+        // ARRANGE
+        let mut simulation = Simulation::new(&Scene::dam_break([1000, 800], 200));
+        simulation.particles.velocity_x.fill(2000.0);
+
+        // ACT
+        simulation.update();
+
+        // ASSERT
+        assert!(simulation.sub_steps() > 1);
+        assert!(simulation.delta_time() < 0.01);
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use coding_challenges::fluid_simulation::scene::Scene;
+    use coding_challenges::fluid_simulation::simulation::Simulation;
+    use coding_challenges::fluid_simulation::vtk_writer::{VtkFormat, VtkWriter};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn output_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    #[test]
+    fn test_write_every_interval_with_collection() {
+        // ARRANGE
+        let directory = output_directory("test_vtk_writer_interval");
+        let mut simulation = Simulation::new(&Scene::dam_break([1000, 800], 50));
+        let mut writer = VtkWriter::new(
+            directory.clone(),
+            "run".to_string(),
+            vec![VtkFormat::Legacy, VtkFormat::Xml],
+            2,
+        );
+
+        // ACT
+        let mut written_steps = Vec::new();
+        for step in 0..5 {
+            if writer.write_step(&simulation, step).unwrap() {
+                written_steps.push(step);
+            }
+            simulation.update();
+        }
+
+        // ASSERT
+        assert_eq!(written_steps, vec![0, 2, 4]);
+        for step in written_steps.iter() {
+            assert!(directory.join(format!("run_{:06}.vtk", step)).exists());
+            assert!(directory.join(format!("run_{:06}.vtu", step)).exists());
+        }
+        assert!(!directory.join("run_000001.vtu").exists());
+        let collection = fs::read_to_string(directory.join("run.pvd")).unwrap();
+        assert_eq!(collection.matches("<DataSet").count(), 3);
+        assert!(
+            collection.contains("timestep=\"0.02\" group=\"\" part=\"0\" file=\"run_000002.vtu\"")
+        );
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_files_hold_all_particles_and_point_data() {
+        // ARRANGE
+        let directory = output_directory("test_vtk_writer_contents");
+        let simulation = Simulation::new(&Scene::dam_break([1000, 800], 30));
+        let mut writer = VtkWriter::new(
+            directory.clone(),
+            "run".to_string(),
+            vec![VtkFormat::Legacy, VtkFormat::Xml],
+            1,
+        );
+
+        // ACT
+        writer.write_step(&simulation, 0).unwrap();
+
+        // ASSERT
+        let legacy = fs::read_to_string(directory.join("run_000000.vtk")).unwrap();
+        assert!(legacy.starts_with("# vtk DataFile Version 3.0\n"));
+        assert!(legacy.contains("POINTS 30 float\n"));
+        assert!(legacy.contains("CELLS 30 60\n"));
+        assert!(legacy.contains("POINT_DATA 30\nVECTORS velocity float\n"));
+        for name in ["density", "pressure", "speed"] {
+            assert!(legacy.contains(&format!("SCALARS {} float 1\n", name)));
+        }
+        assert!(legacy.contains("SCALARS id int 1\n"));
+        let xml = fs::read_to_string(directory.join("run_000000.vtu")).unwrap();
+        assert!(xml.contains("<Piece NumberOfPoints=\"30\" NumberOfCells=\"30\">"));
+        for name in ["velocity", "density", "pressure", "speed", "id", "position"] {
+            assert!(xml.contains(&format!("Name=\"{}\"", name)));
+        }
+        let first_position = simulation.particles.position(0);
+        assert!(xml.contains(&format!("{:?} {:?} 0", first_position.x, first_position.y)));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_double_precision_output_uses_double_types() {
+        // ARRANGE
+        let directory = output_directory("test_vtk_writer_double");
+        let simulation = Simulation::<f64>::from_scene(&Scene::dam_break([1000, 800], 10));
+        let mut writer = VtkWriter::new(
+            directory.clone(),
+            "run".to_string(),
+            vec![VtkFormat::Xml],
+            1,
+        );
+
+        // ACT
+        writer.write_step(&simulation, 0).unwrap();
+
+        // ASSERT
+        let xml = fs::read_to_string(directory.join("run_000000.vtu")).unwrap();
+        assert!(xml.contains("type=\"Float64\" Name=\"density\""));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}